@@ -0,0 +1,42 @@
+//! Updates to a 10,000-item `SignalVec` with a memo deriving from it.
+//!
+//! Each mutation notifies the memo, so these measure the mutation plus one recompute
+//! over the whole collection.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use gpui_signals::{Memo, SignalVec};
+use std::hint::black_box;
+
+const ITEMS: u64 = 10_000;
+
+fn collection(c: &mut Criterion) {
+    let items = SignalVec::new((0..ITEMS).collect());
+    let total = Memo::new(move || items.with(|items| items.iter().sum::<u64>()));
+
+    let mut group = c.benchmark_group("collection_10k");
+    group.bench_function("update_one", |b| {
+        let mut index = 0;
+        b.iter(|| {
+            items.update(|items| items[index] += 1);
+            index = (index + 1) % ITEMS as usize;
+            black_box(total.get())
+        })
+    });
+    group.bench_function("update_all", |b| {
+        b.iter(|| {
+            items.update(|items| items.iter_mut().for_each(|item| *item += 1));
+            black_box(total.get())
+        })
+    });
+    group.bench_function("push_and_remove", |b| {
+        b.iter(|| {
+            items.push(0);
+            items.remove(0);
+            black_box(total.get())
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, collection);
+criterion_main!(benches);
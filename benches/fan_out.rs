@@ -0,0 +1,62 @@
+//! One write to a signal read by many effects and memos.
+//!
+//! Measures the notification path: collecting subscribers, marking memos dirty, and
+//! running effects, as the number of dependents grows.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gpui_signals::{Effect, Memo, Signal, SignalRuntime};
+use std::hint::black_box;
+
+fn effects(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fan_out_effects");
+    for dependents in [1, 10, 100, 1000] {
+        let runtime = SignalRuntime::new();
+        let source = Signal::new_in(0u64, &runtime);
+        let effects: Vec<Effect> = (0..dependents)
+            .map(|_| {
+                Effect::new_in(
+                    move || {
+                        black_box(source.get());
+                    },
+                    &runtime,
+                )
+            })
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(dependents),
+            &dependents,
+            |b, _| b.iter(|| source.update(|value| *value += 1)),
+        );
+        drop(effects);
+    }
+    group.finish();
+}
+
+fn memos(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fan_out_memos");
+    for dependents in [1, 10, 100, 1000] {
+        let runtime = SignalRuntime::new();
+        let source = Signal::new_in(0u64, &runtime);
+        let memos: Vec<Memo<u64>> = (0..dependents)
+            .map(|offset| Memo::new_in(move || source.get() + offset, &runtime))
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(dependents),
+            &dependents,
+            |b, _| {
+                b.iter(|| {
+                    source.update(|value| *value += 1);
+                    for memo in &memos {
+                        black_box(memo.get());
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, effects, memos);
+criterion_main!(benches);
@@ -0,0 +1,60 @@
+//! Writes to the head of a memo chain followed by one read of its tail.
+//!
+//! With dirty-flag propagation the first write marks the chain dirty and later writes
+//! stop at the already dirty head, so the cost per read stays close to one recompute of
+//! the chain however many writes precede it. The depth group shows how that recompute
+//! grows with the length of the chain.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gpui_signals::{Memo, Signal};
+use std::hint::black_box;
+
+const CHAIN_LENGTH: usize = 20;
+
+fn chain(length: usize) -> (Signal<u64>, Memo<u64>) {
+    let source = Signal::new(0u64);
+    let mut tail = Memo::new(move || source.get() + 1);
+    for _ in 1..length {
+        let previous = tail;
+        tail = Memo::new(move || previous.get() + 1);
+    }
+    (source, tail)
+}
+
+fn memo_chain(c: &mut Criterion) {
+    let (source, tail) = chain(CHAIN_LENGTH);
+
+    let mut group = c.benchmark_group("memo_chain");
+    for writes_per_read in [1, 10, 100, 1000] {
+        group.bench_with_input(
+            BenchmarkId::new("writes_per_read", writes_per_read),
+            &writes_per_read,
+            |b, &writes_per_read| {
+                b.iter(|| {
+                    for _ in 0..writes_per_read {
+                        source.update(|value| *value += 1);
+                    }
+                    black_box(tail.get())
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn memo_chain_depth(c: &mut Criterion) {
+    let mut group = c.benchmark_group("memo_chain_depth");
+    for depth in [1, 10, 100, 1000] {
+        let (source, tail) = chain(depth);
+        group.bench_with_input(BenchmarkId::from_parameter(depth), &depth, |b, _| {
+            b.iter(|| {
+                source.update(|value| *value += 1);
+                black_box(tail.get())
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, memo_chain, memo_chain_depth);
+criterion_main!(benches);
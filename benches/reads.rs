@@ -0,0 +1,55 @@
+//! Reads of many signals, as a render reading a view's state does.
+//!
+//! Untracked reads, and reads with no observer set, stay off the mutable borrow of
+//! the storage; tracked reads from a memo computation take it to record the
+//! dependency.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use gpui_signals::{Memo, Signal, SignalRuntime};
+use std::hint::black_box;
+
+fn reads(c: &mut Criterion) {
+    let mut group = c.benchmark_group("reads");
+    for signals in [10, 100, 1000] {
+        let runtime = SignalRuntime::new();
+        let values: Vec<Signal<u64>> = (0..signals)
+            .map(|value| Signal::new_in(value, &runtime))
+            .collect();
+
+        group.bench_with_input(BenchmarkId::new("get", signals), &values, |b, values| {
+            b.iter(|| values.iter().map(|value| value.get()).sum::<u64>())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("with_untracked", signals),
+            &values,
+            |b, values| {
+                b.iter(|| {
+                    values
+                        .iter()
+                        .map(|value| value.with_untracked(|value| black_box(*value)))
+                        .sum::<u64>()
+                })
+            },
+        );
+
+        let tracked = values.clone();
+        let sum = Memo::new_in(
+            move || tracked.iter().map(|value| value.get()).sum::<u64>(),
+            &runtime,
+        );
+        group.bench_with_input(
+            BenchmarkId::new("tracked", signals),
+            &values,
+            |b, values| {
+                b.iter(|| {
+                    values[0].update(|value| *value += 1);
+                    black_box(sum.get())
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, reads);
+criterion_main!(benches);
@@ -103,16 +103,11 @@ impl Render for AsyncDemo {
                 div()
                     .px_4()
                     .py_2()
-                    .bg(if loading {
-                        rgb(0x555555)
-                    } else {
-                        rgb(0x4a9eff)
-                    })
+                    .bg(rgb(0x4a9eff))
                     .rounded_md()
-                    .cursor(if loading {
-                        CursorStyle::Arrow
-                    } else {
-                        CursorStyle::PointingHand
+                    .cursor(CursorStyle::PointingHand)
+                    .when_signal(self.loading, |this| {
+                        this.bg(rgb(0x555555)).cursor(CursorStyle::Arrow)
                     })
                     .child(if loading { "Fetching..." } else { "Fetch User" })
                     .on_mouse_down(
@@ -1,63 +1,53 @@
 //! Global Signal Example
 //!
-//! Demonstrates how to use global signals to share state across views.
+//! Demonstrates how to share state across views with the global theme: every token
+//! read while rendering re-renders the view when the theme is swapped, and the
+//! colors fade between themes.
 
 use gpui::*;
 use gpui_signals::prelude::*;
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum Theme {
-    Light,
-    Dark,
-}
+use gpui_signals::theme::{self, Theme};
+use std::time::Duration;
 
 struct ThemeToggle;
 
 impl Render for ThemeToggle {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        // Subscribe to global theme signal
-        let theme_signal = cx.use_global::<Theme>();
-        let theme = theme_signal.get();
+        cx.track_render(|_cx| {
+            let dark = theme::current().get() == Theme::dark();
 
-        div()
-            .flex()
-            .items_center()
-            .justify_center()
-            .p_4()
-            .bg(match theme {
-                Theme::Light => rgb(0xffffff),
-                Theme::Dark => rgb(0x000000),
-            })
-            .text_color(match theme {
-                Theme::Light => rgb(0x000000),
-                Theme::Dark => rgb(0xffffff),
-            })
-            .child(
-                div()
-                    .cursor_pointer()
-                    .p_2()
-                    .border_1()
-                    .rounded_md()
-                    .child(match theme {
-                        Theme::Light => "Switch to Dark Mode",
-                        Theme::Dark => "Switch to Light Mode",
-                    })
-                    .on_mouse_down(MouseButton::Left, cx.listener(move |_, _, _, cx| {
-                        // Update global signal without needing subscription (can use global_signal for this)
-                        let theme_signal = cx.global_signal::<Theme>();
-                        theme_signal.update(|t| *t = match *t {
-                            Theme::Light => Theme::Dark,
-                            Theme::Dark => Theme::Light,
-                        });
-                    })),
-            )
+            div()
+                .flex()
+                .items_center()
+                .justify_center()
+                .size_full()
+                .bg(theme::bg_primary().get())
+                .text_color(theme::text_primary().get())
+                .child(
+                    div()
+                        .cursor_pointer()
+                        .p(theme::spacing_md().get())
+                        .border_1()
+                        .border_color(theme::border().get())
+                        .rounded(theme::radius_md().get())
+                        .bg(theme::bg_secondary().get())
+                        .child(if dark {
+                            "Switch to Light Mode"
+                        } else {
+                            "Switch to Dark Mode"
+                        })
+                        .on_mouse_down(MouseButton::Left, move |_, _, _| {
+                            // Any code can swap the theme; no view needs a handle to it.
+                            theme::set_theme(if dark { Theme::light() } else { Theme::dark() });
+                        }),
+                )
+        })
     }
 }
 
 fn main() {
     Application::new().run(|cx: &mut App| {
-        // Initialize global signal
-        cx.init_global(Theme::Light);
+        theme::animate_transitions(Duration::from_millis(200), cx);
 
         let bounds = Bounds::centered(None, Size::new(px(400.0), px(300.0)), cx);
         cx.open_window(
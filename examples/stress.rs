@@ -0,0 +1,134 @@
+//! Stress test of the notification path.
+//!
+//! This example shows:
+//! - A 50x50 grid of cells, each its own signal, rewritten at random every frame
+//! - A memo summing every cell, recomputed once per frame however many cells change
+//! - Render-time read tracking with `cx.track_render` instead of one subscription per cell
+
+use gpui::*;
+use gpui_signals::prelude::*;
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+const COLUMNS: usize = 50;
+const ROWS: usize = 50;
+
+struct Stress {
+    cells: Vec<Signal<u8>>,
+    total: Memo<u64>,
+    writes_per_tick: Signal<usize>,
+    ticks: Signal<u64>,
+    tick_time: Signal<Duration>,
+    _ticker: Task<()>,
+}
+
+impl Stress {
+    fn new(cx: &mut Context<Self>) -> Self {
+        let cells: Vec<Signal<u8>> = (0..COLUMNS * ROWS).map(|_| Signal::new(0)).collect();
+        let total = cx.create_memo({
+            let cells = cells.clone();
+            move || cells.iter().map(|cell| u64::from(cell.get())).sum()
+        });
+        let ticker = cx.spawn(
+            async move |this: WeakEntity<Self>, cx: &mut AsyncApp| loop {
+                cx.background_executor()
+                    .timer(Duration::from_millis(16))
+                    .await;
+                if this.update(cx, |this, _cx| this.tick()).is_err() {
+                    break;
+                }
+            },
+        );
+
+        Self {
+            cells,
+            total,
+            writes_per_tick: cx.create_signal(100),
+            ticks: cx.create_signal(0),
+            tick_time: cx.create_signal(Duration::ZERO),
+            _ticker: ticker,
+        }
+    }
+
+    fn tick(&mut self) {
+        let started = Instant::now();
+        let mut rng = rand::thread_rng();
+        for _ in 0..self.writes_per_tick.get_untracked() {
+            let cell = self.cells[rng.gen_range(0..self.cells.len())];
+            cell.set(rng.gen());
+        }
+        self.tick_time.set(started.elapsed());
+        self.ticks.update(|ticks| *ticks += 1);
+    }
+
+    fn change_writes(&mut self, factor: f32) {
+        self.writes_per_tick.update(|writes| {
+            *writes = ((*writes as f32 * factor) as usize).clamp(1, COLUMNS * ROWS * 10);
+        });
+    }
+}
+
+fn button(
+    label: &'static str,
+    cx: &mut Context<Stress>,
+    on_click: impl Fn(&mut Stress) + 'static,
+) -> impl IntoElement {
+    div()
+        .id(label)
+        .px_3()
+        .py_1()
+        .rounded_md()
+        .bg(rgb(0x3a3a3a))
+        .child(label)
+        .on_click(cx.listener(move |this, _, _, _| on_click(this)))
+}
+
+impl Render for Stress {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let header = div()
+            .flex()
+            .gap_4()
+            .items_center()
+            .child(format!("Tick {}", self.ticks.get()))
+            .child(format!("{} writes per tick", self.writes_per_tick.get()))
+            .child(format!("{:?} per tick", self.tick_time.get()))
+            .child(format!("Total {}", self.total.get()))
+            .child(button("Fewer", cx, |this| this.change_writes(0.5)))
+            .child(button("More", cx, |this| this.change_writes(2.)));
+
+        let grid = cx.track_render(|_| {
+            div().flex().flex_col().children((0..ROWS).map(|row| {
+                div().flex().children((0..COLUMNS).map(|column| {
+                    let value = self.cells[row * COLUMNS + column].get();
+                    div().size(px(10.)).bg(rgb(u32::from(value) << 8))
+                }))
+            }))
+        });
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .p_4()
+            .size_full()
+            .bg(rgb(0x2d2d2d))
+            .text_color(rgb(0xffffff))
+            .child(header)
+            .child(grid)
+    }
+}
+
+fn main() {
+    Application::new().run(|cx: &mut App| {
+        let bounds = Bounds::centered(None, Size::new(px(700.0), px(640.0)), cx);
+        cx.open_window(
+            WindowOptions {
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                ..Default::default()
+            },
+            |_window, cx| cx.new(Stress::new),
+        )
+        .unwrap();
+        cx.activate(true);
+    });
+}
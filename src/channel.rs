@@ -0,0 +1,111 @@
+//! Adapters between signals and tokio channels.
+//!
+//! These let a tokio-based service layer feed values into the UI's signal graph, and
+//! observe UI state, without custom glue. The channels come from `tokio::sync`, which
+//! does not need a tokio runtime, so the receiving side runs on GPUI's executor.
+
+use crate::context::track_subscription;
+use crate::{Signal, SignalContext};
+use gpui::{Context, Subscription};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, watch};
+
+impl<T: Clone + 'static> Signal<T> {
+    /// Create a signal that follows the latest value of a watch channel.
+    ///
+    /// The entity is notified whenever a new value arrives. The signal keeps its last
+    /// value once the sender is dropped.
+    pub fn from_watch<V: 'static>(mut receiver: watch::Receiver<T>, cx: &mut Context<V>) -> Self {
+        let signal = cx.create_signal(receiver.borrow_and_update().clone());
+        let task = cx.spawn(async move |_, _| {
+            while receiver.changed().await.is_ok() {
+                let value = receiver.borrow_and_update().clone();
+                signal.set(value);
+            }
+        });
+        track_subscription(cx, Subscription::new(move || drop(task)));
+        signal
+    }
+
+    /// Create a signal holding the most recent message of a broadcast channel.
+    ///
+    /// The signal starts at `initial`. Messages missed because the receiver lagged
+    /// are skipped.
+    pub fn from_broadcast<V: 'static>(
+        mut receiver: broadcast::Receiver<T>,
+        initial: T,
+        cx: &mut Context<V>,
+    ) -> Self {
+        let signal = cx.create_signal(initial);
+        let task = cx.spawn(async move |_, _| loop {
+            match receiver.recv().await {
+                Ok(value) => signal.set(value),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            }
+        });
+        track_subscription(cx, Subscription::new(move || drop(task)));
+        signal
+    }
+
+    /// A watch channel receiver that follows this signal's value.
+    ///
+    /// Services can await `changed()` on the receiver to observe UI state.
+    pub fn to_watch(&self) -> watch::Receiver<T> {
+        let (sender, receiver) = watch::channel(self.get_untracked());
+        let signal = *self;
+        self.subscribe(move || {
+            sender.send_replace(signal.get_untracked());
+        });
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext, TestAppContext};
+
+    struct Status {
+        progress: Signal<u32>,
+    }
+
+    #[gpui::test]
+    async fn test_from_watch_follows_sender(cx: &mut TestAppContext) {
+        let (sender, receiver) = watch::channel(0);
+        let status = cx.new(|cx| Status {
+            progress: Signal::from_watch(receiver, cx),
+        });
+        let progress = status.read_with(cx, |status, _| status.progress);
+        assert_eq!(progress.get(), 0);
+
+        sender.send(40).unwrap();
+        cx.run_until_parked();
+        assert_eq!(progress.get(), 40);
+    }
+
+    #[gpui::test]
+    async fn test_from_broadcast_takes_latest_message(cx: &mut TestAppContext) {
+        let (sender, receiver) = broadcast::channel(4);
+        let status = cx.new(|cx| Status {
+            progress: Signal::from_broadcast(receiver, 0, cx),
+        });
+        let progress = status.read_with(cx, |status, _| status.progress);
+
+        sender.send(10).unwrap();
+        sender.send(20).unwrap();
+        cx.run_until_parked();
+        assert_eq!(progress.get(), 20);
+    }
+
+    #[test]
+    fn test_to_watch_observes_signal() {
+        let signal = Signal::new(1);
+        let mut receiver = signal.to_watch();
+        assert_eq!(*receiver.borrow_and_update(), 1);
+
+        signal.set(2);
+        assert!(receiver.has_changed().unwrap());
+        assert_eq!(*receiver.borrow(), 2);
+    }
+}
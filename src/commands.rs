@@ -0,0 +1,423 @@
+//! Command registry and command palette state.
+//!
+//! Commands register into a global [`SignalVec<Command>`], and each command carries
+//! an `enabled` memo derived from whatever app signals it depends on. Menus and the
+//! [`CommandPalette`] read both, so they stay in sync with app state automatically.
+
+use crate::{Memo, Signal, SignalContext, SignalVec};
+use gpui::{
+    div, prelude::*, px, rgb, App, Context, FontWeight, Global, MouseButton, SharedString, Window,
+};
+use std::fmt;
+use std::rc::Rc;
+
+type CommandHandler = Rc<dyn Fn(&mut Window, &mut App)>;
+
+/// A named action that can be run from menus, key handlers, or the command palette.
+#[derive(Clone)]
+pub struct Command {
+    id: SharedString,
+    label: SharedString,
+    keybinding: Option<SharedString>,
+    /// `None` for commands that are always enabled, which share one memo instead of
+    /// each allocating their own.
+    enabled: Option<Memo<bool>>,
+    handler: CommandHandler,
+}
+
+impl Command {
+    /// Create an always-enabled command.
+    pub fn new(
+        id: impl Into<SharedString>,
+        label: impl Into<SharedString>,
+        handler: impl Fn(&mut Window, &mut App) + 'static,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            label: label.into(),
+            keybinding: None,
+            enabled: None,
+            handler: Rc::new(handler),
+        }
+    }
+
+    /// Set the keybinding shown next to the command.
+    pub fn keybinding(mut self, keybinding: impl Into<SharedString>) -> Self {
+        self.keybinding = Some(keybinding.into());
+        self
+    }
+
+    /// Derive the command's enabled state from signals.
+    ///
+    /// `predicate` is wrapped in a memo, so it is re-evaluated whenever a signal it
+    /// reads changes. The memo is disposed when the command is replaced or
+    /// unregistered.
+    pub fn enabled_when(mut self, predicate: impl Fn() -> bool + 'static) -> Self {
+        if let Some(previous) = self.enabled.replace(Memo::new(predicate)) {
+            previous.signal().dispose();
+        }
+        self
+    }
+
+    /// The command's unique identifier.
+    pub fn id(&self) -> &SharedString {
+        &self.id
+    }
+
+    /// The human-readable label.
+    pub fn label(&self) -> &SharedString {
+        &self.label
+    }
+
+    /// The keybinding hint, if one was set.
+    pub fn keybinding_hint(&self) -> Option<&SharedString> {
+        self.keybinding.as_ref()
+    }
+
+    /// A memo tracking whether the command can currently run.
+    pub fn enabled(&self) -> Memo<bool> {
+        self.enabled.unwrap_or_else(always_enabled)
+    }
+
+    /// Run the command if it is enabled.
+    ///
+    /// Returns true if the handler ran. A copy of a command that has since been
+    /// replaced or unregistered doesn't run.
+    pub fn run(&self, window: &mut Window, cx: &mut App) -> bool {
+        let enabled = match self.enabled {
+            Some(enabled) => enabled.signal().is_alive() && enabled.get_untracked(),
+            None => true,
+        };
+        if !enabled {
+            return false;
+        }
+        (self.handler)(window, cx);
+        true
+    }
+}
+
+/// The memo shared by every command without an `enabled_when` predicate.
+fn always_enabled() -> Memo<bool> {
+    thread_local! {
+        static ALWAYS_ENABLED: Memo<bool> = Memo::new(|| true);
+    }
+    ALWAYS_ENABLED.with(|enabled| *enabled)
+}
+
+impl PartialEq for Command {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl fmt::Debug for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Command")
+            .field("id", &self.id)
+            .field("label", &self.label)
+            .field("keybinding", &self.keybinding)
+            .field("enabled", &self.enabled())
+            .finish_non_exhaustive()
+    }
+}
+
+struct CommandRegistry {
+    commands: SignalVec<Command>,
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self {
+            commands: SignalVec::new(Vec::new()),
+        }
+    }
+}
+
+impl Global for CommandRegistry {}
+
+/// Extension trait for registering and looking up commands.
+pub trait CommandContext {
+    /// The global list of registered commands.
+    fn commands(&mut self) -> SignalVec<Command>;
+
+    /// Register a command, replacing any existing command with the same id.
+    fn register_command(&mut self, command: Command);
+
+    /// Remove the command with the given id, if it is registered.
+    fn unregister_command(&mut self, id: &str);
+}
+
+impl CommandContext for App {
+    fn commands(&mut self) -> SignalVec<Command> {
+        self.default_global::<CommandRegistry>().commands
+    }
+
+    fn register_command(&mut self, command: Command) {
+        let enabled = command.enabled;
+        let mut replaced = None;
+        self.commands().update(|commands| {
+            if let Some(existing) = commands.iter_mut().find(|c| c.id == command.id) {
+                replaced = Some(std::mem::replace(existing, command));
+            } else {
+                commands.push(command);
+            }
+        });
+        if let Some(Some(replaced)) = replaced.map(|command| command.enabled) {
+            if Some(replaced) != enabled {
+                replaced.signal().dispose();
+            }
+        }
+    }
+
+    fn unregister_command(&mut self, id: &str) {
+        let commands = self.commands();
+        let Some(index) =
+            commands.with_untracked(|commands| commands.iter().position(|c| c.id.as_ref() == id))
+        else {
+            return;
+        };
+        let mut removed = None;
+        commands.update(|commands| removed = Some(commands.remove(index)));
+        if let Some(enabled) = removed.and_then(|command| command.enabled) {
+            enabled.signal().dispose();
+        }
+    }
+}
+
+/// Run the registered command with the given id.
+///
+/// Returns true if the command exists, is enabled, and ran.
+pub fn run_command(id: &str, window: &mut Window, cx: &mut App) -> bool {
+    let command = cx
+        .commands()
+        .with_untracked(|commands| commands.iter().find(|c| c.id.as_ref() == id).cloned());
+    command.is_some_and(|command| command.run(window, cx))
+}
+
+/// Filter commands whose label contains `query`, ignoring case.
+pub fn filter_commands(commands: &[Command], query: &str) -> Vec<Command> {
+    let query = query.trim().to_lowercase();
+    commands
+        .iter()
+        .filter(|command| query.is_empty() || command.label.to_lowercase().contains(&query))
+        .cloned()
+        .collect()
+}
+
+/// A command palette view over the global command registry.
+///
+/// The palette re-renders when commands are registered or removed, when the query
+/// changes, and when any listed command becomes enabled or disabled.
+pub struct CommandPalette {
+    query: Signal<String>,
+    matches: Memo<Vec<Command>>,
+    enabled: Memo<Vec<bool>>,
+    selected: Signal<usize>,
+}
+
+impl CommandPalette {
+    /// Create a palette listing every registered command.
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let commands = cx.commands();
+        let query = cx.create_signal(String::new());
+        let matches = cx
+            .create_memo(move || query.with(|query| commands.with(|c| filter_commands(c, query))));
+        let enabled = cx.create_memo(move || {
+            matches.with(|matches| matches.iter().map(|c| c.enabled().get()).collect())
+        });
+        let selected = cx.create_signal(0);
+
+        Self {
+            query,
+            matches,
+            enabled,
+            selected,
+        }
+    }
+
+    /// The signal holding the palette's filter query.
+    pub fn query(&self) -> Signal<String> {
+        self.query
+    }
+
+    /// The commands matching the current query.
+    pub fn matches(&self) -> Memo<Vec<Command>> {
+        self.matches
+    }
+
+    /// The index of the highlighted match.
+    pub fn selected(&self) -> Signal<usize> {
+        self.selected
+    }
+
+    /// Replace the filter query and reset the highlight to the first match.
+    pub fn set_query(&mut self, query: impl Into<String>) {
+        self.query.set(query.into());
+        self.selected.set_if_changed(0);
+    }
+
+    /// Move the highlight down, wrapping around at the end.
+    pub fn select_next(&mut self) {
+        let count = self.matches.with_untracked(|matches| matches.len());
+        if count > 0 {
+            self.selected
+                .update(|selected| *selected = (*selected + 1) % count);
+        }
+    }
+
+    /// Move the highlight up, wrapping around at the start.
+    pub fn select_previous(&mut self) {
+        let count = self.matches.with_untracked(|matches| matches.len());
+        if count > 0 {
+            self.selected
+                .update(|selected| *selected = (*selected + count - 1) % count);
+        }
+    }
+
+    /// Run the highlighted command.
+    pub fn confirm(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let selected = self.selected.get_untracked();
+        self.run_match(selected, window, cx);
+    }
+
+    fn run_match(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(command) = self
+            .matches
+            .with_untracked(|matches| matches.get(index).cloned())
+        else {
+            return;
+        };
+        // Defer so that command handlers are free to update this palette.
+        window.defer(cx, move |window, cx| {
+            command.run(window, cx);
+        });
+    }
+}
+
+impl Render for CommandPalette {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let matches = self.matches.get();
+        let enabled = self.enabled.get();
+        let selected = self.selected.get();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .p_2()
+            .min_w(px(320.0))
+            .bg(rgb(0x1d1d1d))
+            .border_1()
+            .border_color(rgb(0x444444))
+            .rounded_md()
+            .text_sm()
+            .text_color(rgb(0xffffff))
+            .when(matches.is_empty(), |this| {
+                this.child(
+                    div()
+                        .px_2()
+                        .py_1()
+                        .text_color(rgb(0x888888))
+                        .child("No matching commands"),
+                )
+            })
+            .children(matches.into_iter().enumerate().map(|(index, command)| {
+                let is_enabled = enabled.get(index).copied().unwrap_or(false);
+                div()
+                    .flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    .rounded_md()
+                    .when(index == selected, |this| this.bg(rgb(0x3a3a3a)))
+                    .when(!is_enabled, |this| this.text_color(rgb(0x666666)))
+                    .child(div().font_weight(FontWeight::MEDIUM).child(command.label))
+                    .when_some(command.keybinding, |this, keybinding| {
+                        this.child(div().text_color(rgb(0x888888)).child(keybinding))
+                    })
+                    .when(is_enabled, |this| {
+                        this.on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, _, window, cx| {
+                                this.selected.set_if_changed(index);
+                                this.run_match(index, window, cx);
+                            }),
+                        )
+                    })
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_register_replaces_by_id(cx: &TestAppContext) {
+        cx.update(|cx| {
+            cx.register_command(Command::new("file.save", "Save", |_, _| {}));
+            cx.register_command(Command::new("file.open", "Open", |_, _| {}));
+            cx.register_command(Command::new("file.save", "Save File", |_, _| {}));
+
+            let labels: Vec<_> = cx
+                .commands()
+                .with(|commands| commands.iter().map(|c| c.label().clone()).collect());
+            assert_eq!(labels, vec!["Save File", "Open"]);
+
+            cx.unregister_command("file.open");
+            assert_eq!(cx.commands().len(), 1);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_replaced_and_unregistered_commands_free_their_memos(cx: &mut TestAppContext) {
+        let dirty = Signal::new(true);
+        let save = move |label: &'static str| {
+            Command::new("file.save", label, |_, _| {}).enabled_when(move || dirty.get())
+        };
+        let first = save("Save");
+        cx.update(|cx| {
+            cx.register_command(first.clone());
+            cx.register_command(save("Save File"));
+        });
+        assert!(!first.enabled().signal().is_alive());
+
+        let cx = cx.add_empty_window();
+        assert!(!cx.update(|window, cx| first.run(window, cx)));
+        let second =
+            cx.update(|_, cx| cx.commands().with_untracked(|commands| commands[0].clone()));
+        assert!(cx.update(|window, cx| second.run(window, cx)));
+
+        cx.update(|_, cx| cx.unregister_command("file.save"));
+        assert!(!second.enabled().signal().is_alive());
+        assert!(!cx.update(|window, cx| second.run(window, cx)));
+    }
+
+    #[gpui::test]
+    async fn test_enabled_memo_follows_signals(cx: &TestAppContext) {
+        cx.update(|cx| {
+            let dirty = Signal::new(false);
+            cx.register_command(
+                Command::new("file.save", "Save", |_, _| {}).enabled_when(move || dirty.get()),
+            );
+
+            let enabled = cx.commands().with(|commands| commands[0].enabled());
+            assert!(!enabled.get());
+            dirty.set(true);
+            assert!(enabled.get());
+        });
+    }
+
+    #[test]
+    fn test_filter_commands_ignores_case() {
+        let commands = vec![
+            Command::new("file.save", "Save File", |_, _| {}),
+            Command::new("view.zoom", "Zoom In", |_, _| {}),
+        ];
+        let matches = filter_commands(&commands, "  sAVE ");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].id(), "file.save");
+        assert_eq!(filter_commands(&commands, "").len(), 2);
+    }
+}
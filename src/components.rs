@@ -0,0 +1,481 @@
+//! Input components bound to signals.
+//!
+//! Each component reads its current value from a signal while rendering and writes
+//! user input straight back to it, so the owning view needs no event plumbing.
+
+use crate::{Progress, Signal, TaskState};
+use gpui::{
+    anchored, canvas, deferred, div, prelude::*, px, relative, rgb, App, Bounds, DispatchPhase,
+    ElementId, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, Point,
+    Refineable, SharedString, StyleRefinement, Window,
+};
+use slotmap::Key;
+use std::cell::Cell;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::rc::Rc;
+
+/// A horizontal slider that edits a `Signal<f32>` within a range.
+///
+/// Clicking or dragging anywhere on the track sets the value; dragging continues to
+/// track the pointer after it leaves the slider until the button is released.
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::components::Slider;
+/// use gpui_signals::prelude::*;
+///
+/// fn volume(volume: Signal<f32>) -> impl IntoElement {
+///     Slider::bound(volume, 0.0..=1.0).step(0.05)
+/// }
+/// ```
+#[derive(IntoElement)]
+pub struct Slider {
+    value: Signal<f32>,
+    range: RangeInclusive<f32>,
+    step: Option<f32>,
+    style: StyleRefinement,
+}
+
+impl Slider {
+    /// Create a slider that reads and writes `value`, limited to `range`.
+    pub fn bound(value: Signal<f32>, range: RangeInclusive<f32>) -> Self {
+        Self {
+            value,
+            range,
+            step: None,
+            style: StyleRefinement::default(),
+        }
+    }
+
+    /// Snap values to multiples of `step` from the start of the range.
+    pub fn step(mut self, step: f32) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    fn value_at(&self, position: Point<Pixels>, bounds: Bounds<Pixels>) -> f32 {
+        let (start, end) = (*self.range.start(), *self.range.end());
+        let fraction = if bounds.size.width > px(0.) {
+            ((position.x - bounds.left()) / bounds.size.width).clamp(0., 1.)
+        } else {
+            0.
+        };
+        let value = start + fraction * (end - start);
+        match self.step {
+            Some(step) if step > 0. => (start + ((value - start) / step).round() * step).min(end),
+            _ => value,
+        }
+    }
+
+    fn fraction(&self) -> f32 {
+        let (start, end) = (*self.range.start(), *self.range.end());
+        if end > start {
+            ((self.value.get() - start) / (end - start)).clamp(0., 1.)
+        } else {
+            0.
+        }
+    }
+}
+
+impl Styled for Slider {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for Slider {
+    fn render(self, window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        // Bound sliders have no id of their own, so key their drag state by signal.
+        let id = ElementId::NamedInteger("slider".into(), self.value.id().data().as_ffi());
+        let dragging = window.with_global_id(id, |global_id, window| {
+            window.with_element_state(global_id, |state: Option<Rc<Cell<bool>>>, _| {
+                let state = state.unwrap_or_default();
+                (state.clone(), state)
+            })
+        });
+        let fraction = self.fraction();
+        let mut root = div().relative().w_full().h(px(20.)).flex().items_center();
+        root.style().refine(&self.style);
+
+        root.child(
+            div()
+                .w_full()
+                .h(px(4.))
+                .rounded_full()
+                .bg(rgb(0x444444))
+                .child(
+                    div()
+                        .h_full()
+                        .w(relative(fraction))
+                        .rounded_full()
+                        .bg(rgb(0x4a9eff)),
+                ),
+        )
+        .child(
+            div()
+                .absolute()
+                .top(px(4.))
+                .left(relative(fraction))
+                .ml(px(-6.))
+                .size(px(12.))
+                .rounded_full()
+                .bg(rgb(0xffffff)),
+        )
+        .child(
+            canvas(
+                |_, _, _| {},
+                move |bounds, _, window, _| {
+                    let slider = Rc::new(self);
+                    window.on_mouse_event({
+                        let (slider, dragging) = (slider.clone(), dragging.clone());
+                        move |event: &MouseDownEvent, phase, _, _| {
+                            if phase == DispatchPhase::Bubble
+                                && event.button == MouseButton::Left
+                                && bounds.contains(&event.position)
+                            {
+                                dragging.set(true);
+                                slider
+                                    .value
+                                    .set_if_changed(slider.value_at(event.position, bounds));
+                            }
+                        }
+                    });
+                    window.on_mouse_event({
+                        let (slider, dragging) = (slider.clone(), dragging.clone());
+                        move |event: &MouseMoveEvent, phase, _, _| {
+                            if phase == DispatchPhase::Bubble && dragging.get() {
+                                if event.pressed_button == Some(MouseButton::Left) {
+                                    slider
+                                        .value
+                                        .set_if_changed(slider.value_at(event.position, bounds));
+                                } else {
+                                    dragging.set(false);
+                                }
+                            }
+                        }
+                    });
+                    window.on_mouse_event(move |event: &MouseUpEvent, phase, _, _| {
+                        if phase == DispatchPhase::Bubble && event.button == MouseButton::Left {
+                            dragging.set(false);
+                        }
+                    });
+                },
+            )
+            .absolute()
+            .size_full(),
+        )
+    }
+}
+
+/// A `- value +` control that edits a `Signal<i32>`.
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::components::Stepper;
+/// use gpui_signals::prelude::*;
+///
+/// fn quantity(quantity: Signal<i32>) -> impl IntoElement {
+///     Stepper::bound(quantity).range(1..=99)
+/// }
+/// ```
+#[derive(IntoElement)]
+pub struct Stepper {
+    value: Signal<i32>,
+    step: i32,
+    range: Option<RangeInclusive<i32>>,
+}
+
+impl Stepper {
+    /// Create a stepper that reads and writes `value`.
+    pub fn bound(value: Signal<i32>) -> Self {
+        Self {
+            value,
+            step: 1,
+            range: None,
+        }
+    }
+
+    /// Change the value by `step` per click instead of 1.
+    pub fn step(mut self, step: i32) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Keep the value within `range`.
+    pub fn range(mut self, range: RangeInclusive<i32>) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    fn button(
+        label: &'static str,
+        value: Signal<i32>,
+        delta: i32,
+        range: Option<RangeInclusive<i32>>,
+    ) -> impl IntoElement {
+        let current = value.get();
+        let next = clamp_to(current.saturating_add(delta), range.as_ref());
+        div()
+            .px_2()
+            .rounded_md()
+            .bg(rgb(0x3a3a3a))
+            .when(next == current, |this| this.text_color(rgb(0x666666)))
+            .when(next != current, |this| {
+                this.cursor_pointer()
+                    .on_mouse_down(MouseButton::Left, move |_, _, _| {
+                        value.update(|value| {
+                            *value = clamp_to(value.saturating_add(delta), range.as_ref())
+                        });
+                    })
+            })
+            .child(label)
+    }
+}
+
+fn clamp_to(value: i32, range: Option<&RangeInclusive<i32>>) -> i32 {
+    match range {
+        Some(range) => value.clamp(*range.start(), *range.end()),
+        None => value,
+    }
+}
+
+impl RenderOnce for Stepper {
+    fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        let text = SharedString::from(self.value.get().to_string());
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .child(Self::button(
+                "-",
+                self.value,
+                -self.step,
+                self.range.clone(),
+            ))
+            .child(div().min_w(px(32.)).flex().justify_center().child(text))
+            .child(Self::button("+", self.value, self.step, self.range))
+    }
+}
+
+/// A dropdown that shows the value of a signal and writes the chosen option back.
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::components::Select;
+/// use gpui_signals::prelude::*;
+/// use std::fmt;
+///
+/// #[derive(Clone, Copy, PartialEq)]
+/// enum Filter {
+///     All,
+///     Active,
+///     Completed,
+/// }
+///
+/// impl fmt::Display for Filter {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         let label = match self {
+///             Filter::All => "All",
+///             Filter::Active => "Active",
+///             Filter::Completed => "Completed",
+///         };
+///         f.write_str(label)
+///     }
+/// }
+///
+/// fn filter_picker(filter: Signal<Filter>) -> impl IntoElement {
+///     Select::bound(filter, [Filter::All, Filter::Active, Filter::Completed])
+/// }
+/// ```
+#[derive(IntoElement)]
+pub struct Select<T: Clone + PartialEq + Display + 'static> {
+    value: Signal<T>,
+    options: Vec<T>,
+    style: StyleRefinement,
+}
+
+impl<T: Clone + PartialEq + Display + 'static> Select<T> {
+    /// Create a select that reads and writes `value`, offering `options`.
+    pub fn bound(value: Signal<T>, options: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            value,
+            options: options.into_iter().collect(),
+            style: StyleRefinement::default(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + Display + 'static> Styled for Select<T> {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl<T: Clone + PartialEq + Display + 'static> RenderOnce for Select<T> {
+    fn render(self, window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        let id = ElementId::NamedInteger("select".into(), self.value.id().data().as_ffi());
+        let open = window.with_global_id(id, |global_id, window| {
+            window.with_element_state(global_id, |state: Option<Signal<bool>>, _| {
+                let open = state.unwrap_or_else(|| Signal::new(false));
+                (open, open)
+            })
+        });
+        let value = self.value;
+        let current = value.get();
+        let is_open = open.get();
+        let mut root = div().flex().flex_col();
+        root.style().refine(&self.style);
+
+        root.child(
+            div()
+                .flex()
+                .justify_between()
+                .gap_2()
+                .px_2()
+                .py_1()
+                .rounded_md()
+                .border_1()
+                .border_color(rgb(0x444444))
+                .bg(rgb(0x1d1d1d))
+                .cursor_pointer()
+                .child(current.to_string())
+                .child(if is_open { "▴" } else { "▾" })
+                // An open menu closes itself on any click outside it, including this one.
+                .when(!is_open, |this| {
+                    this.on_mouse_down(MouseButton::Left, move |_, _, _| open.set(true))
+                }),
+        )
+        .when(is_open, |this| {
+            this.child(deferred(
+                anchored().snap_to_window().child(
+                    div()
+                        .occlude()
+                        .mt_1()
+                        .p_1()
+                        .min_w(px(120.))
+                        .flex()
+                        .flex_col()
+                        .bg(rgb(0x1d1d1d))
+                        .border_1()
+                        .border_color(rgb(0x444444))
+                        .rounded_md()
+                        .on_mouse_down_out(move |_, _, _| open.set(false))
+                        .children(self.options.into_iter().map(|option| {
+                            let selected = option == current;
+                            div()
+                                .px_2()
+                                .py_1()
+                                .rounded_md()
+                                .cursor_pointer()
+                                .when(selected, |this| this.bg(rgb(0x3a3a3a)))
+                                .child(option.to_string())
+                                .on_mouse_down(MouseButton::Left, move |_, _, _| {
+                                    value.set_if_changed(option.clone());
+                                    open.set(false);
+                                })
+                        })),
+                ),
+            ))
+        })
+    }
+}
+
+/// A bar showing how far a [`Progress`] has come, with its status message below.
+///
+/// The bar turns green once the task finishes and red if it fails, in which case the
+/// failure message replaces the status message.
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::components::ProgressBar;
+/// use gpui_signals::prelude::*;
+///
+/// fn import_status(progress: &Progress) -> impl IntoElement {
+///     ProgressBar::bound(progress).w(px(240.))
+/// }
+/// ```
+#[derive(IntoElement)]
+pub struct ProgressBar {
+    fraction: Signal<f32>,
+    message: Signal<SharedString>,
+    state: Signal<TaskState>,
+    style: StyleRefinement,
+}
+
+impl ProgressBar {
+    /// Create a bar that reads `progress`.
+    pub fn bound(progress: &Progress) -> Self {
+        Self {
+            fraction: progress.fraction(),
+            message: progress.message(),
+            state: progress.state(),
+            style: StyleRefinement::default(),
+        }
+    }
+}
+
+impl Styled for ProgressBar {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for ProgressBar {
+    fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        let fraction = self.fraction.get().clamp(0., 1.);
+        let (color, message) = match self.state.get() {
+            TaskState::Finished => (rgb(0x3fb950), self.message.get()),
+            TaskState::Failed(error) => (rgb(0xf85149), error),
+            TaskState::Idle | TaskState::Running => (rgb(0x4a9eff), self.message.get()),
+        };
+        let mut root = div().w_full().flex().flex_col().gap_1();
+        root.style().refine(&self.style);
+
+        root.child(
+            div()
+                .w_full()
+                .h(px(6.))
+                .rounded_full()
+                .bg(rgb(0x444444))
+                .child(
+                    div()
+                        .h_full()
+                        .w(relative(fraction))
+                        .rounded_full()
+                        .bg(color),
+                ),
+        )
+        .when(!message.is_empty(), |this| {
+            this.child(div().text_sm().text_color(rgb(0xaaaaaa)).child(message))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{point, size};
+
+    #[test]
+    fn test_slider_maps_position_to_range() {
+        let value = Signal::new(0.);
+        let slider = Slider::bound(value, 10.0..=20.0);
+        let bounds = Bounds::new(point(px(100.), px(0.)), size(px(200.), px(20.)));
+
+        assert_eq!(slider.value_at(point(px(50.), px(0.)), bounds), 10.);
+        assert_eq!(slider.value_at(point(px(200.), px(0.)), bounds), 15.);
+        assert_eq!(slider.value_at(point(px(400.), px(0.)), bounds), 20.);
+
+        let slider = slider.step(3.);
+        assert_eq!(slider.value_at(point(px(200.), px(0.)), bounds), 16.);
+        assert_eq!(slider.value_at(point(px(300.), px(0.)), bounds), 19.);
+    }
+
+    #[test]
+    fn test_stepper_clamps_to_range() {
+        assert_eq!(clamp_to(12, Some(&(0..=10))), 10);
+        assert_eq!(clamp_to(-3, Some(&(0..=10))), 0);
+        assert_eq!(clamp_to(-3, None), -3);
+    }
+}
@@ -1,14 +1,18 @@
 //! Computed signals (memos) that derive from other signals.
 
-use crate::signal::Signal;
-use crate::storage::with_signal_storage;
-use gpui::{IntoElement, SharedString};
+use crate::error::{report_panic, Computation};
+use crate::runtime::{SignalRuntime, WaveEvent};
+use crate::signal::{ReadOnlySignal, Signal};
+use crate::storage::untracked;
 use std::hash::{Hash, Hasher};
+use std::panic::{self, AssertUnwindSafe};
 use std::{cell::Cell, fmt, marker::PhantomData, rc::Rc};
 
 /// A computed signal that derives its value from other signals.
 ///
-/// Memos automatically track dependencies and recompute when any dependency changes.
+/// Memos automatically track dependencies. A change to a dependency only marks the
+/// memo dirty and notifies its subscribers; the value is recomputed on the next read,
+/// so a memo whose inputs change many times between reads computes once per read.
 ///
 /// # Examples
 ///
@@ -58,57 +62,162 @@ impl<T> Hash for Memo<T> {
     }
 }
 
-impl<T: fmt::Display + Clone + 'static> IntoElement for Memo<T> {
-    type Element = SharedString;
+impl<T: 'static + Clone> Memo<T> {
+    /// Create a new memo from a computation function.
+    ///
+    /// The function is called immediately, and again on the first read after a
+    /// dependency changes.
+    /// In a GPUI view, prefer `cx.create_memo`, which also re-renders the view when
+    /// the value changes.
+    pub fn new(compute: impl Fn() -> T + 'static) -> Self {
+        Self::new_in(compute, &SignalRuntime::default())
+    }
 
-    fn into_element(self) -> Self::Element {
-        self.get().to_string().into()
+    /// Create a new memo in `runtime`.
+    ///
+    /// Only reads of signals in the same runtime are tracked as dependencies.
+    pub fn new_in(compute: impl Fn() -> T + 'static, runtime: &SignalRuntime) -> Self {
+        Self::build(move |_| compute(), false, None, runtime).computed()
     }
-}
 
-impl<T: 'static + Clone> Memo<T> {
-    /// Create a new memo from a computation function.
+    /// Create a memo whose computation also receives its previous value.
     ///
-    /// The function will be called immediately and whenever dependencies change.
-    pub(crate) fn new(compute: impl Fn() -> T + 'static) -> Self {
-        let compute = Rc::new(compute);
-        let recomputing = Rc::new(Cell::new(false));
-        let signal = Signal::new(compute());
-        let recompute_signal = signal;
-
-        let recompute: Rc<dyn Fn()> = {
-            let compute = compute.clone();
-            let signal = recompute_signal;
-            let recomputing = recomputing.clone();
-            Rc::new(move || {
-                if recomputing.replace(true) {
-                    return;
-                }
+    /// The previous value is `None` on the first run. This suits running totals,
+    /// smoothing, or "changed since last" flags without stashing the old value in
+    /// another signal.
+    pub fn new_with_prev(compute: impl Fn(Option<&T>) -> T + 'static) -> Self {
+        Self::new_with_prev_in(compute, &SignalRuntime::default())
+    }
 
-                // Track dependencies while we compute the new value so updates
-                // to those signals will notify this memo's signal.
-                let previous =
-                    with_signal_storage(|storage| storage.set_observer(Some(signal.id())));
-                let value = compute();
-                with_signal_storage(|storage| storage.set_observer(previous));
+    /// Create a memo in `runtime` whose computation also receives its previous value.
+    pub fn new_with_prev_in(
+        compute: impl Fn(Option<&T>) -> T + 'static,
+        runtime: &SignalRuntime,
+    ) -> Self {
+        Self::build(compute, true, None, runtime).computed()
+    }
 
-                signal.set(value);
-                recomputing.set(false);
-            })
-        };
+    /// Create a memo from the current values of explicit sources.
+    ///
+    /// `sources` is a tuple of one to four signals, read-only signals, or memos, and
+    /// `compute` receives a clone of each value as a separate argument. Only the
+    /// sources are tracked, so a stray `get()` inside `compute` doesn't add a
+    /// dependency.
+    ///
+    /// ```rust,no_run
+    /// use gpui_signals::prelude::*;
+    ///
+    /// let first = Signal::new("Ada".to_string());
+    /// let last = Signal::new("Lovelace".to_string());
+    /// let full = Memo::from_sources((first, last), |first, last| format!("{first} {last}"));
+    /// assert_eq!(full.get(), "Ada Lovelace");
+    /// ```
+    pub fn from_sources<S, F>(sources: S, compute: F) -> Self
+    where
+        S: MemoSources<F, T> + 'static,
+        F: 'static,
+    {
+        Self::from_sources_in(sources, compute, &SignalRuntime::default())
+    }
+
+    /// Create a memo in `runtime` from the current values of explicit sources.
+    pub fn from_sources_in<S, F>(sources: S, compute: F, runtime: &SignalRuntime) -> Self
+    where
+        S: MemoSources<F, T> + 'static,
+        F: 'static,
+    {
+        let runtime_id = runtime.id();
+        Self::build(
+            move |_| {
+                let values = sources.values();
+                untracked(runtime_id, || S::call(&compute, values))
+            },
+            false,
+            None,
+            runtime,
+        )
+        .computed()
+    }
+
+    /// Create a memo that computes only when read.
+    ///
+    /// Nothing is computed until the first read, so an expensive memo whose readers
+    /// are hidden, such as content behind a collapsed panel, costs nothing until it is
+    /// shown.
+    pub fn lazy(compute: impl Fn() -> T + 'static) -> Self {
+        Self::lazy_in(compute, &SignalRuntime::default())
+    }
+
+    /// Create a memo in `runtime` that computes only when read.
+    pub fn lazy_in(compute: impl Fn() -> T + 'static, runtime: &SignalRuntime) -> Self {
+        Self::build(move |_| compute(), false, None, runtime)
+    }
 
-        // Run once to register dependencies and seed the memo value.
-        recompute();
+    /// Create a memo whose panics are reported under `name`.
+    ///
+    /// See [`on_signal_error`](crate::on_signal_error).
+    pub fn named(name: impl Into<Rc<str>>, compute: impl Fn() -> T + 'static) -> Self {
+        Self::build(
+            move |_| compute(),
+            false,
+            Some(name.into()),
+            &SignalRuntime::default(),
+        )
+        .computed()
+    }
+
+    fn build(
+        compute: impl Fn(Option<&T>) -> T + 'static,
+        reads_prev: bool,
+        name: Option<Rc<str>>,
+        runtime: &SignalRuntime,
+    ) -> Self {
+        // The slot exists before the first run so that run can be tracked under it.
+        let signal = Signal::reserve_in(runtime);
+        let seeded = Cell::new(false);
+        #[cfg(feature = "gpui")]
+        if let Some(name) = &name {
+            signal.with_storage(|storage| storage.set_name(signal.id(), name.clone()));
+        }
 
-        // Dependencies are automatically tracked via track_read() during recompute().
-        // When dependencies change, they notify this memo's signal via notify_subscribers().
-        // We need to subscribe to our own signal to receive those notifications.
-        // However, we must be careful: when we update our signal via signal.set(), it will
-        // notify subscribers including ourselves. The recomputing flag prevents infinite loops.
-        signal.subscribe({
-            let recompute = recompute.clone();
-            move || recompute()
+        let refresh: Rc<dyn Fn()> = Rc::new(move || {
+            #[cfg(feature = "metrics")]
+            crate::telemetry::memo_recomputed();
+            // Only clone the previous value for computations that use it.
+            let prev = (reads_prev && seeded.get()).then(|| signal.get_untracked());
+            let recorded = signal.with_storage(|storage| {
+                storage.record_wave_event(WaveEvent::MemoRecomputed(signal.id()), true)
+            });
+            // Caught inside the tracking scope so the scope is restored after a panic.
+            let result = signal.with_read_only(true, || {
+                signal.track_as_observer(|| {
+                    panic::catch_unwind(AssertUnwindSafe(|| compute(prev.as_ref())))
+                })
+            });
+            if recorded {
+                signal.with_storage(|storage| storage.end_wave_event());
+            }
+            match result {
+                Ok(value) => {
+                    seeded.set(true);
+                    // Subscribers and dependents were notified when the memo was marked
+                    // dirty.
+                    signal.set_without_notify(value);
+                }
+                Err(payload) => {
+                    let reported =
+                        report_panic(Computation::Memo, name.clone(), signal.id(), &*payload);
+                    if !seeded.get() {
+                        // There is no value to fall back on, so the next read tries again.
+                        signal.with_storage(|storage| storage.mark_dirty(signal.id()));
+                    }
+                    if !reported || !seeded.get() {
+                        panic::resume_unwind(payload);
+                    }
+                }
+            }
         });
+        signal.with_storage(|storage| storage.set_refresher(signal.id(), refresh));
 
         Self {
             signal,
@@ -116,6 +225,23 @@ impl<T: 'static + Clone> Memo<T> {
         }
     }
 
+    /// Compute the first value now instead of on the first read.
+    fn computed(self) -> Self {
+        self.signal.with_untracked(|_| ());
+        self
+    }
+
+    /// Recompute on the next read, and notify subscribers and dependent memos now.
+    ///
+    /// Use this when the computation also reads data the memo can't track, such as
+    /// file contents or the system time, and the app knows that data has changed.
+    pub fn invalidate(&self) {
+        let callbacks = self
+            .signal
+            .with_storage(|storage| storage.invalidate(self.signal.id()));
+        self.signal.deliver(callbacks);
+    }
+
     /// Get the underlying signal.
     pub fn signal(&self) -> Signal<T> {
         self.signal
@@ -126,6 +252,11 @@ impl<T: 'static + Clone> Memo<T> {
         self.signal.get()
     }
 
+    /// Get the current computed value, or `None` if the memo has been disposed.
+    pub fn try_get(&self) -> Option<T> {
+        self.signal.try_get()
+    }
+
     /// Get the current value without tracking the read.
     pub fn get_untracked(&self) -> T {
         self.signal.get_untracked()
@@ -141,10 +272,65 @@ impl<T: 'static + Clone> Memo<T> {
         self.signal.with_untracked(f)
     }
 
+    /// Get the current computed value, or `T::default()` if the memo has been
+    /// disposed and the runtime's stale handle policy lets the read carry on.
+    #[track_caller]
+    pub fn get_or_default(&self) -> T
+    where
+        T: Default,
+    {
+        self.signal.get_or_default()
+    }
+
+    /// Read the computed value with a closure, or read `T::default()` if the memo has
+    /// been disposed and the runtime's stale handle policy lets the read carry on.
+    #[track_caller]
+    pub fn with_or_default<R>(&self, f: impl FnOnce(&T) -> R) -> R
+    where
+        T: Default,
+    {
+        self.signal.with_or_default(f)
+    }
+
+    /// The number of times the computed value has been stored, without tracking the read.
+    ///
+    /// A dirty memo is recomputed first, so the version reflects the current value.
+    pub fn version(&self) -> u64 {
+        self.signal.version()
+    }
+
+    /// Whether the computed value has been stored since it was at `version`.
+    pub fn changed_since(&self, version: u64) -> bool {
+        self.signal.changed_since(version)
+    }
+
     /// Subscribe to changes in the computed value.
+    #[track_caller]
     pub fn subscribe(&self, callback: impl Fn() + 'static) {
         self.signal.subscribe(callback);
     }
+
+    /// Subscribe to the next change only.
+    #[track_caller]
+    pub fn subscribe_once(&self, callback: impl FnOnce() + 'static) {
+        self.signal.subscribe_once(callback);
+    }
+
+    /// Subscribe to changes after which `predicate` holds for the new value.
+    #[track_caller]
+    pub fn subscribe_when(
+        &self,
+        predicate: impl Fn(&T) -> bool + 'static,
+        callback: impl Fn() + 'static,
+    ) {
+        self.signal.subscribe_when(predicate, callback);
+    }
+}
+
+impl<T: 'static + Clone> From<Memo<T>> for ReadOnlySignal<T> {
+    fn from(memo: Memo<T>) -> Self {
+        memo.signal.read_only()
+    }
 }
 
 impl<T: 'static + Clone + fmt::Debug> fmt::Debug for Memo<T> {
@@ -155,12 +341,210 @@ impl<T: 'static + Clone + fmt::Debug> fmt::Debug for Memo<T> {
     }
 }
 
+/// A signal whose value can be passed to [`Memo::from_sources`].
+pub trait MemoSource {
+    /// The value passed to the computation.
+    type Value;
+
+    /// Read the value, tracking the read.
+    fn tracked_value(&self) -> Self::Value;
+}
 
+impl<T: 'static + Clone> MemoSource for Signal<T> {
+    type Value = T;
+
+    fn tracked_value(&self) -> T {
+        self.get()
+    }
+}
+
+impl<T: 'static + Clone> MemoSource for ReadOnlySignal<T> {
+    type Value = T;
+
+    fn tracked_value(&self) -> T {
+        self.get()
+    }
+}
+
+impl<T: 'static + Clone> MemoSource for Memo<T> {
+    type Value = T;
+
+    fn tracked_value(&self) -> T {
+        self.get()
+    }
+}
+
+/// A tuple of [`MemoSource`]s whose values are passed to a computation `F` returning
+/// `R`, one argument per source.
+pub trait MemoSources<F, R> {
+    #[doc(hidden)]
+    type Values;
+
+    #[doc(hidden)]
+    fn values(&self) -> Self::Values;
+
+    #[doc(hidden)]
+    fn call(compute: &F, values: Self::Values) -> R;
+}
+
+macro_rules! impl_memo_sources {
+    ($($source:ident $value:ident),+) => {
+        impl<$($source: MemoSource,)+ F, R> MemoSources<F, R> for ($($source,)+)
+        where
+            F: Fn($($source::Value),+) -> R,
+        {
+            type Values = ($($source::Value,)+);
+
+            #[allow(non_snake_case)]
+            fn values(&self) -> Self::Values {
+                let ($($source,)+) = self;
+                ($($source.tracked_value(),)+)
+            }
+
+            #[allow(non_snake_case)]
+            fn call(compute: &F, values: Self::Values) -> R {
+                let ($($value,)+) = values;
+                compute($($value),+)
+            }
+        }
+    };
+}
+
+impl_memo_sources!(A a);
+impl_memo_sources!(A a, B b);
+impl_memo_sources!(A a, B b, C c);
+impl_memo_sources!(A a, B b, C c, D d);
+
+/// When an effect runs relative to the others notified by the same write.
+///
+/// Within one notification wave, the callbacks run after a single `set` or `update`,
+/// effects run from `State` to `Io`, so effects that derive state settle before the
+/// ones that act on it. A write made by an effect starts its own wave, which runs
+/// to completion before the outer wave continues.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EffectPriority {
+    /// Derives state from other state.
+    State,
+    /// Measures or arranges UI. Subscribers that aren't effects run at this level.
+    #[default]
+    Layout,
+    /// Talks to the outside world, such as writing files or sending requests.
+    Io,
+}
+
+/// A side effect that re-runs whenever the signals it reads change.
+///
+/// In a GPUI view, prefer `cx.create_effect`, which stops the effect when the entity
+/// is released.
+#[derive(Clone)]
+pub struct Effect {
+    active: Rc<Cell<bool>>,
+    observer: Signal<()>,
+}
+
+impl Effect {
+    /// Run `effect` now and again after every change to a signal it read.
+    pub fn new(effect: impl Fn() + 'static) -> Self {
+        Self::new_in(effect, &SignalRuntime::default())
+    }
+
+    /// Create an effect in `runtime`.
+    pub fn new_in(effect: impl Fn() + 'static, runtime: &SignalRuntime) -> Self {
+        Self::new_with_priority_in(effect, EffectPriority::default(), runtime)
+    }
+
+    /// Create an effect that runs at `priority` relative to others notified by the
+    /// same write.
+    pub fn new_with_priority(effect: impl Fn() + 'static, priority: EffectPriority) -> Self {
+        Self::new_with_priority_in(effect, priority, &SignalRuntime::default())
+    }
+
+    /// Create an effect whose panics are reported under `name`.
+    ///
+    /// See [`on_signal_error`](crate::on_signal_error).
+    pub fn named(name: impl Into<Rc<str>>, effect: impl Fn() + 'static) -> Self {
+        Self::build(
+            effect,
+            EffectPriority::default(),
+            Some(name.into()),
+            &SignalRuntime::default(),
+        )
+    }
+
+    /// Create an effect in `runtime` that runs at `priority`.
+    pub fn new_with_priority_in(
+        effect: impl Fn() + 'static,
+        priority: EffectPriority,
+        runtime: &SignalRuntime,
+    ) -> Self {
+        Self::build(effect, priority, None, runtime)
+    }
+
+    fn build(
+        effect: impl Fn() + 'static,
+        priority: EffectPriority,
+        name: Option<Rc<str>>,
+        runtime: &SignalRuntime,
+    ) -> Self {
+        let active = Rc::new(Cell::new(true));
+        // Reads made by the effect are tracked under this signal, so a change to any
+        // of them notifies its subscriber below.
+        let observer = Signal::new_in((), runtime);
+        observer.with_storage(|storage| storage.set_priority(observer.id(), priority));
+        #[cfg(feature = "gpui")]
+        if let Some(name) = &name {
+            observer.with_storage(|storage| storage.set_name(observer.id(), name.clone()));
+        }
+        let running = Cell::new(false);
+        let run = {
+            let active = active.clone();
+            move || {
+                // An effect that writes a signal it reads would otherwise re-enter itself.
+                if !active.get() || running.replace(true) {
+                    return;
+                }
+                let recorded = observer.with_storage(|storage| {
+                    storage.record_wave_event(WaveEvent::EffectRun(observer.id()), true)
+                });
+                // Effects may write, even when one runs from inside a render. A panic is
+                // caught inside the tracking scope so the scope is restored after it.
+                let result = observer.with_read_only(false, || {
+                    observer.track_as_observer(|| panic::catch_unwind(AssertUnwindSafe(&effect)))
+                });
+                if recorded {
+                    observer.with_storage(|storage| storage.end_wave_event());
+                }
+                running.set(false);
+                if let Err(payload) = result {
+                    if !report_panic(Computation::Effect, name.clone(), observer.id(), &*payload) {
+                        panic::resume_unwind(payload);
+                    }
+                }
+            }
+        };
+        run();
+        observer.subscribe(run);
+        Self { active, observer }
+    }
+
+    /// Stop re-running the effect and free its tracking state.
+    pub fn stop(&self) {
+        self.active.set(false);
+        self.observer.dispose();
+    }
+
+    /// The id of the signal the effect's reads are tracked under.
+    #[cfg(feature = "gpui")]
+    pub(crate) fn id(&self) -> crate::storage::SignalId {
+        self.observer.id()
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::Signal;
+    use std::cell::RefCell;
 
     #[test]
     fn test_memo_basic() {
@@ -180,6 +564,49 @@ mod tests {
         assert_eq!(doubled.get(), 14);
     }
 
+    #[test]
+    fn test_memo_version_reflects_pending_recompute() {
+        let count = Signal::new(2);
+        let doubled = Memo::new(move || count.get() * 2);
+        let version = doubled.version();
+
+        assert!(!doubled.changed_since(version));
+        count.set(3);
+        assert!(doubled.changed_since(version));
+        assert_eq!(doubled.get(), 6);
+    }
+
+    #[test]
+    fn test_panics_are_reported_and_tracking_recovers() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        crate::on_signal_error({
+            let errors = errors.clone();
+            move |error| errors.borrow_mut().push(error.to_string())
+        });
+        let items = Signal::new(vec![1]);
+        let first = Rc::new(Cell::new(0));
+        let _effect = Effect::named("first item", {
+            let first = first.clone();
+            move || first.set(items.with(|items| items[0]))
+        });
+        let last = Memo::named("last item", move || {
+            items.with(|items| *items.last().expect("no items"))
+        });
+        assert_eq!(last.get(), 1);
+
+        items.set(Vec::new());
+        assert_eq!(last.get(), 1);
+        let errors_so_far = errors.borrow().clone();
+        assert_eq!(errors_so_far.len(), 2);
+        assert!(errors_so_far[0].starts_with("effect `first item` panicked"));
+        assert_eq!(errors_so_far[1], "memo `last item` panicked: no items");
+
+        // Writes are allowed again and both re-run once their input recovers.
+        items.set(vec![4, 5]);
+        assert_eq!(first.get(), 4);
+        assert_eq!(last.get(), 5);
+    }
+
     #[test]
     fn test_memo_with_untracked() {
         let count = Signal::new(3);
@@ -201,4 +628,432 @@ mod tests {
         doubled_signal.set(count.get() * 2);
         assert_eq!(doubled_signal.get(), 20);
     }
+
+    #[test]
+    fn test_memo_with_prev_keeps_running_total() {
+        let amount = Signal::new(5);
+        let total = Memo::new_with_prev(move |prev: Option<&i32>| {
+            prev.copied().unwrap_or(0) + amount.get()
+        });
+        assert_eq!(total.get(), 5);
+
+        amount.set(3);
+        assert_eq!(total.get(), 8);
+        amount.set(2);
+        assert_eq!(total.get(), 10);
+    }
+
+    #[test]
+    fn test_lazy_memo_computes_on_read() {
+        let count = Signal::new(1);
+        let runs = Rc::new(Cell::new(0));
+        let doubled = Memo::lazy({
+            let runs = runs.clone();
+            move || {
+                runs.set(runs.get() + 1);
+                count.get() * 2
+            }
+        });
+        assert_eq!(runs.get(), 0);
+
+        assert_eq!(doubled.get(), 2);
+        assert_eq!(doubled.get(), 2);
+        assert_eq!(runs.get(), 1);
+
+        count.set(2);
+        count.set(3);
+        assert_eq!(runs.get(), 1);
+        assert_eq!(doubled.get(), 6);
+        assert_eq!(runs.get(), 2);
+    }
+
+    #[test]
+    fn test_memo_chain_recomputes_once_per_read() {
+        let count = Signal::new(0);
+        let runs = Rc::new(Cell::new(0));
+        let doubled = Memo::new({
+            let runs = runs.clone();
+            move || {
+                runs.set(runs.get() + 1);
+                count.get() * 2
+            }
+        });
+        let quadrupled = Memo::new({
+            let runs = runs.clone();
+            move || {
+                runs.set(runs.get() + 1);
+                doubled.get() * 2
+            }
+        });
+        assert_eq!(runs.get(), 2);
+
+        for value in 1..=10 {
+            count.set(value);
+        }
+        assert_eq!(runs.get(), 2);
+        assert_eq!(quadrupled.get(), 40);
+        assert_eq!(runs.get(), 4);
+    }
+
+    #[test]
+    fn test_memo_drops_dependencies_it_stops_reading() {
+        let use_detail = Signal::new(true);
+        let detail = Signal::new(1);
+        let runs = Rc::new(Cell::new(0));
+        let shown = Memo::new({
+            let runs = runs.clone();
+            move || {
+                runs.set(runs.get() + 1);
+                if use_detail.get() {
+                    detail.get()
+                } else {
+                    0
+                }
+            }
+        });
+        let seen = Rc::new(Cell::new(0));
+        let _effect = Effect::new({
+            let seen = seen.clone();
+            move || seen.set(shown.get())
+        });
+        assert_eq!(seen.get(), 1);
+
+        use_detail.set(false);
+        assert_eq!(seen.get(), 0);
+        let runs_before = runs.get();
+
+        detail.set(2);
+        assert_eq!(shown.get(), 0);
+        assert_eq!(runs.get(), runs_before);
+
+        use_detail.set(true);
+        assert_eq!(seen.get(), 2);
+        detail.set(3);
+        assert_eq!(seen.get(), 3);
+    }
+
+    #[test]
+    fn test_memo_invalidate_recomputes_untracked_data() {
+        let external = Rc::new(Cell::new(1));
+        let doubled = Memo::new({
+            let external = external.clone();
+            move || external.get() * 2
+        });
+        let quadrupled = Memo::new(move || doubled.get() * 2);
+        let seen = Rc::new(Cell::new(0));
+        let _effect = Effect::new({
+            let seen = seen.clone();
+            move || seen.set(quadrupled.get())
+        });
+        assert_eq!(seen.get(), 4);
+
+        external.set(5);
+        assert_eq!(doubled.get(), 2);
+        doubled.invalidate();
+        assert_eq!(seen.get(), 20);
+        assert_eq!(doubled.get(), 10);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "while rendering or computing a memo")]
+    fn test_write_inside_memo_panics_in_debug_builds() {
+        let count = Signal::new(1);
+        let writes = Signal::new(0);
+        let doubled = Memo::new(move || {
+            writes.update(|writes| *writes += 1);
+            count.get() * 2
+        });
+        doubled.get();
+    }
+
+    #[test]
+    fn test_memo_from_sources_tracks_only_sources() {
+        let first = Signal::new(2);
+        let second = Signal::new(3);
+        let stray = Signal::new(100);
+        let runs = Rc::new(Cell::new(0));
+        let product = Memo::from_sources((first, second.read_only()), {
+            let runs = runs.clone();
+            move |first, second| {
+                runs.set(runs.get() + 1);
+                first * second + stray.get()
+            }
+        });
+        assert_eq!(product.get(), 106);
+
+        second.set(4);
+        assert_eq!(product.get(), 108);
+        let runs_before = runs.get();
+
+        stray.set(0);
+        assert_eq!(product.get(), 108);
+        assert_eq!(runs.get(), runs_before);
+    }
+
+    #[test]
+    fn test_lazy_memo_notifies_readers() {
+        let count = Signal::new(1);
+        let doubled = Memo::lazy(move || count.get() * 2);
+        let seen = Rc::new(Cell::new(0));
+        let _effect = Effect::new({
+            let seen = seen.clone();
+            move || seen.set(doubled.get())
+        });
+        assert_eq!(seen.get(), 2);
+
+        count.set(5);
+        assert_eq!(seen.get(), 10);
+    }
+
+    #[test]
+    fn test_effects_run_in_priority_order() {
+        let count = Signal::new(0);
+        let order = Rc::new(std::cell::RefCell::new(Vec::new()));
+        for priority in [
+            EffectPriority::Io,
+            EffectPriority::Layout,
+            EffectPriority::State,
+        ] {
+            let order = order.clone();
+            Effect::new_with_priority(
+                move || {
+                    count.get();
+                    order.borrow_mut().push(priority);
+                },
+                priority,
+            );
+        }
+
+        order.borrow_mut().clear();
+        count.set(1);
+        assert_eq!(
+            *order.borrow(),
+            [
+                EffectPriority::State,
+                EffectPriority::Layout,
+                EffectPriority::Io
+            ]
+        );
+    }
+
+    #[test]
+    fn test_effect_stops_rerunning() {
+        let count = Signal::new(1);
+        let seen = Rc::new(Cell::new(0));
+        let effect = Effect::new({
+            let seen = seen.clone();
+            move || seen.set(count.get())
+        });
+        assert_eq!(seen.get(), 1);
+
+        count.set(2);
+        assert_eq!(seen.get(), 2);
+
+        effect.stop();
+        count.set(3);
+        assert_eq!(seen.get(), 2);
+    }
+
+    /// Random graphs of signals, memos, and effects checked against a plain
+    /// evaluation of the same graph.
+    mod graph_properties {
+        use super::*;
+        use crate::{BatchScheduler, SignalRuntime};
+        use proptest::collection::vec;
+        use proptest::prelude::*;
+        use proptest::sample::Index;
+
+        #[derive(Clone, Debug)]
+        struct GraphSpec {
+            signals: usize,
+            /// Each memo's sources, picked from the signals and the memos before it.
+            memos: Vec<Vec<Index>>,
+            /// Each effect's sources, picked from every signal and memo.
+            effects: Vec<Vec<Index>>,
+            writes: Vec<(Index, i64)>,
+        }
+
+        fn graph_spec() -> impl Strategy<Value = GraphSpec> {
+            (
+                1..5usize,
+                vec(vec(any::<Index>(), 1..4), 0..8),
+                vec(vec(any::<Index>(), 1..4), 0..4),
+                vec((any::<Index>(), -100..100i64), 1..30),
+            )
+                .prop_map(|(signals, memos, effects, writes)| GraphSpec {
+                    signals,
+                    memos,
+                    effects,
+                    writes,
+                })
+        }
+
+        /// Reads the rest of the sources only when the first is even, so dependencies
+        /// change as values do.
+        fn evaluate(sources: &[usize], read: impl Fn(usize) -> i64) -> i64 {
+            let first = read(sources[0]);
+            if first % 2 == 0 {
+                sources[1..]
+                    .iter()
+                    .fold(first, |total, &source| total.wrapping_add(read(source)))
+            } else {
+                first.wrapping_mul(2)
+            }
+        }
+
+        #[derive(Clone, Copy)]
+        enum Node {
+            Signal(Signal<i64>),
+            Memo(Memo<i64>),
+        }
+
+        impl Node {
+            fn get(&self) -> i64 {
+                match self {
+                    Node::Signal(signal) => signal.get(),
+                    Node::Memo(memo) => memo.get(),
+                }
+            }
+        }
+
+        struct Graph {
+            nodes: Rc<Vec<Node>>,
+            memo_sources: Vec<Vec<usize>>,
+            effect_sources: Vec<Vec<usize>>,
+            effect_runs: Vec<Rc<Cell<usize>>>,
+            effect_seen: Vec<Rc<Cell<i64>>>,
+            _effects: Vec<Effect>,
+        }
+
+        impl Graph {
+            fn build(spec: &GraphSpec, runtime: &SignalRuntime) -> Self {
+                let mut nodes: Vec<Node> = (0..spec.signals)
+                    .map(|_| Node::Signal(Signal::new_in(0, runtime)))
+                    .collect();
+                let mut memo_sources = Vec::new();
+                for sources in &spec.memos {
+                    let sources: Vec<usize> = sources
+                        .iter()
+                        .map(|index| index.index(nodes.len()))
+                        .collect();
+                    let inputs = nodes.clone();
+                    let compute = {
+                        let sources = sources.clone();
+                        move || evaluate(&sources, |source| inputs[source].get())
+                    };
+                    nodes.push(Node::Memo(Memo::new_in(compute, runtime)));
+                    memo_sources.push(sources);
+                }
+
+                let nodes = Rc::new(nodes);
+                let mut effect_sources = Vec::new();
+                let mut effect_runs = Vec::new();
+                let mut effect_seen = Vec::new();
+                let mut effects = Vec::new();
+                for sources in &spec.effects {
+                    let sources: Vec<usize> = sources
+                        .iter()
+                        .map(|index| index.index(nodes.len()))
+                        .collect();
+                    let runs = Rc::new(Cell::new(0));
+                    let seen = Rc::new(Cell::new(0));
+                    effects.push(Effect::new_in(
+                        {
+                            let (nodes, sources) = (nodes.clone(), sources.clone());
+                            let (runs, seen) = (runs.clone(), seen.clone());
+                            move || {
+                                runs.set(runs.get() + 1);
+                                seen.set(evaluate(&sources, |source| nodes[source].get()));
+                            }
+                        },
+                        runtime,
+                    ));
+                    effect_sources.push(sources);
+                    effect_runs.push(runs);
+                    effect_seen.push(seen);
+                }
+
+                Self {
+                    nodes,
+                    memo_sources,
+                    effect_sources,
+                    effect_runs,
+                    effect_seen,
+                    _effects: effects,
+                }
+            }
+
+            fn write(&self, index: Index, value: i64, signals: usize) {
+                match self.nodes[index.index(signals)] {
+                    Node::Signal(signal) => signal.set(value),
+                    Node::Memo(_) => unreachable!("only signals are written"),
+                }
+            }
+
+            /// Every node's value, computed without the reactive graph.
+            fn expected(&self, signals: &[i64]) -> Vec<i64> {
+                let mut values = signals.to_vec();
+                for sources in &self.memo_sources {
+                    let value = evaluate(sources, |source| values[source]);
+                    values.push(value);
+                }
+                values
+            }
+
+            fn assert_settled(&self, signals: &[i64]) {
+                let expected = self.expected(signals);
+                for (node, expected) in self.nodes.iter().zip(&expected) {
+                    assert_eq!(node.get(), *expected);
+                }
+                for (sources, seen) in self.effect_sources.iter().zip(&self.effect_seen) {
+                    assert_eq!(seen.get(), evaluate(sources, |source| expected[source]));
+                }
+            }
+
+            fn runs(&self) -> Vec<usize> {
+                self.effect_runs.iter().map(|runs| runs.get()).collect()
+            }
+        }
+
+        proptest! {
+            #[test]
+            fn test_memos_and_effects_match_plain_evaluation(spec in graph_spec()) {
+                let runtime = SignalRuntime::new();
+                let graph = Graph::build(&spec, &runtime);
+                let mut signals = vec![0; spec.signals];
+                graph.assert_settled(&signals);
+
+                for &(index, value) in &spec.writes {
+                    let before = graph.runs();
+                    graph.write(index, value, spec.signals);
+                    signals[index.index(spec.signals)] = value;
+                    for (before, after) in before.iter().zip(graph.runs()) {
+                        prop_assert!(after - before <= 1);
+                    }
+                    graph.assert_settled(&signals);
+                }
+            }
+
+            #[test]
+            fn test_batched_effects_run_once_per_flush(spec in graph_spec()) {
+                let runtime = SignalRuntime::new();
+                let batch = BatchScheduler::new();
+                runtime.set_scheduler(batch.clone());
+                let graph = Graph::build(&spec, &runtime);
+                let mut signals = vec![0; spec.signals];
+
+                let before = graph.runs();
+                for &(index, value) in &spec.writes {
+                    graph.write(index, value, spec.signals);
+                    signals[index.index(spec.signals)] = value;
+                }
+                batch.flush();
+                for (before, after) in before.iter().zip(graph.runs()) {
+                    prop_assert!(after - before <= 1);
+                }
+                graph.assert_settled(&signals);
+            }
+        }
+    }
 }
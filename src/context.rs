@@ -2,13 +2,26 @@
 //!
 //! This module provides extension methods for GPUI's Context to work with signals.
 
-use crate::storage::SignalId;
-use crate::{Memo, Signal};
+use crate::devtools;
+use crate::error::{report_panic, Computation};
+use crate::queries::{Mutation, Query};
+use crate::runtime::WaveEvent;
+use crate::storage::{with_signal_storage, SignalId};
+use crate::testing;
+use crate::validated::Validator;
+use crate::{
+    DraftSignal, Effect, EffectPriority, ListNavigation, Memo, MemoSources, NavStack,
+    ReadOnlySignal, ResettableSignal, Resource, ResourceState, Signal, SignalVec, TaskTracker,
+    ValidatedSignal,
+};
 use futures::channel::mpsc;
 use futures::StreamExt;
-use gpui::{EntityId, Subscription, WeakEntity};
+use gpui::{AsyncApp, ElementId, EntityId, SharedString, Subscription, WeakEntity};
+use std::any::{Any, TypeId};
 use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe, Location};
 use std::rc::Rc;
 
 /// Extension trait for GPUI Context to work with signals.
@@ -44,63 +57,686 @@ use std::rc::Rc;
 /// }
 /// ```
 pub trait SignalContext {
+    /// The entity this context belongs to.
+    type Entity: 'static;
+
     /// Create a new signal with the given initial value.
     ///
     /// The signal will automatically notify the entity when it changes.
     /// Subscriptions are automatically managed and cleaned up when the entity is dropped.
     /// No manual `auto_notify()` calls or subscription storage needed!
+    ///
+    /// The signal is disposed when the entity is released, so a handle kept elsewhere
+    /// must not be read after that. Use `Signal::new` for state that outlives the entity.
     fn create_signal<T: 'static>(&mut self, initial: T) -> Signal<T>;
 
+    /// Create a new list signal with the given initial values.
+    ///
+    /// Like `create_signal`, the entity is notified whenever the list changes.
+    fn create_signal_vec<T: 'static>(&mut self, initial: Vec<T>) -> SignalVec<T>;
+
+    /// Create a signal whose writes must pass `validate`.
+    ///
+    /// See [`ValidatedSignal`]. The entity is notified when the value or the error
+    /// changes.
+    fn create_validated_signal<T: 'static, E: 'static>(
+        &mut self,
+        initial: T,
+        validate: impl Fn(&T) -> Result<(), E> + 'static,
+    ) -> ValidatedSignal<T, E>;
+
+    /// Create a signal that remembers `default`, to reset to and compare against.
+    ///
+    /// See [`ResettableSignal`]. The entity is notified when the value changes.
+    fn create_signal_with_reset<T: 'static + Clone + PartialEq>(
+        &mut self,
+        default: T,
+    ) -> ResettableSignal<T>;
+
+    /// Create a working copy of `source` that is written back on `commit()`.
+    ///
+    /// See [`DraftSignal`]. The entity is notified when the draft changes.
+    fn create_draft<T: 'static + Clone + PartialEq>(&mut self, source: Signal<T>)
+        -> DraftSignal<T>;
+
+    /// Create a navigation stack holding only `root`.
+    ///
+    /// See [`NavStack`]. The entity is notified when the stack changes.
+    fn create_nav_stack<T: 'static + Clone>(&mut self, root: T) -> NavStack<T>;
+
+    /// Create a keyboard navigation over `items`, highlighting the first.
+    ///
+    /// See [`ListNavigation`]. The entity is notified when the highlight moves or an
+    /// item is selected.
+    fn create_list_navigation<T: 'static + Clone>(
+        &mut self,
+        items: impl Into<ReadOnlySignal<Vec<T>>>,
+    ) -> ListNavigation<T>;
+
     /// Create a computed signal (memo) from a computation function.
     ///
     /// The memo will be automatically cleaned up when the entity is dropped.
     fn create_memo<T: 'static + Clone>(&mut self, compute: impl Fn() -> T + 'static) -> Memo<T>;
 
+    /// Create a memo shared by every entity that asks for the same `key` and value
+    /// type.
+    ///
+    /// The first call creates the memo from `compute`; later calls with the same key
+    /// return that memo and ignore their `compute`, so views deriving the same
+    /// expensive value, such as the parsed form of a shared document, compute it once
+    /// instead of once each. Each entity is notified when the memo changes, and the
+    /// memo is disposed once every entity that asked for it is released.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    ///
+    /// struct Preview {
+    ///     words: Memo<usize>,
+    /// }
+    ///
+    /// impl Preview {
+    ///     fn new(document: Signal<String>, cx: &mut Context<Self>) -> Self {
+    ///         let words = cx.create_shared_memo("document word count", move || {
+    ///             document.with(|text| text.split_whitespace().count())
+    ///         });
+    ///         Self { words }
+    ///     }
+    /// }
+    /// ```
+    fn create_shared_memo<T: 'static + Clone>(
+        &mut self,
+        key: impl Into<String>,
+        compute: impl Fn() -> T + 'static,
+    ) -> Memo<T>;
+
+    /// Create a memo whose computation also receives its previous value.
+    ///
+    /// The previous value is `None` on the first run.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    ///
+    /// struct Meter {
+    ///     sample: Signal<f32>,
+    ///     smoothed: Memo<f32>,
+    /// }
+    ///
+    /// impl Meter {
+    ///     fn new(cx: &mut Context<Self>) -> Self {
+    ///         let sample = cx.create_signal(0.);
+    ///         let smoothed = cx.create_memo_with_prev(move |prev: Option<&f32>| {
+    ///             let sample = sample.get();
+    ///             prev.map_or(sample, |prev| prev * 0.8 + sample * 0.2)
+    ///         });
+    ///         Self { sample, smoothed }
+    ///     }
+    /// }
+    /// ```
+    fn create_memo_with_prev<T: 'static + Clone>(
+        &mut self,
+        compute: impl Fn(Option<&T>) -> T + 'static,
+    ) -> Memo<T>;
+
+    /// Create a memo from the current values of explicit sources.
+    ///
+    /// See [`Memo::from_sources`]. `sources` is a tuple of signals, read-only signals,
+    /// or memos, and `compute` receives their values as separate arguments.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    ///
+    /// struct Invoice {
+    ///     quantity: Signal<u32>,
+    ///     price: Signal<f64>,
+    ///     total: Memo<f64>,
+    /// }
+    ///
+    /// impl Invoice {
+    ///     fn new(cx: &mut Context<Self>) -> Self {
+    ///         let quantity = cx.create_signal(1);
+    ///         let price = cx.create_signal(9.5);
+    ///         let total = cx.create_memo_from((quantity, price), |quantity, price| {
+    ///             quantity as f64 * price
+    ///         });
+    ///         Self { quantity, price, total }
+    ///     }
+    /// }
+    /// ```
+    fn create_memo_from<S, F, T>(&mut self, sources: S, compute: F) -> Memo<T>
+    where
+        S: MemoSources<F, T> + 'static,
+        F: 'static,
+        T: 'static + Clone;
+
+    /// Create a memo that computes only when read.
+    ///
+    /// See [`Memo::lazy`]. The entity is notified when a dependency changes, and the
+    /// value is recomputed when the next render reads it.
+    fn create_lazy_memo<T: 'static + Clone>(
+        &mut self,
+        compute: impl Fn() -> T + 'static,
+    ) -> Memo<T>;
+
     /// Create an effect that runs when signals it reads change.
     ///
     /// The effect will be cleaned up when the entity is dropped.
     fn create_effect(&mut self, effect: impl Fn() + 'static);
+
+    /// Create an effect that runs at `priority` relative to others notified by the
+    /// same write.
+    ///
+    /// See [`EffectPriority`].
+    fn create_effect_with_priority(
+        &mut self,
+        effect: impl Fn() + 'static,
+        priority: EffectPriority,
+    );
+
+    /// Create an effect that runs with the entity and its context when signals it
+    /// reads change, so it can update the view's fields or call its methods.
+    ///
+    /// The first run happens once the entity has been created. Later runs are queued
+    /// after the write that triggered them, rather than run inside it, since the
+    /// entity can't be updated while one of its own handlers is running; several
+    /// writes before the queue is drained cause one run. The effect stops when the
+    /// entity is released. A panic in the effect is passed to
+    /// [`on_signal_error`](crate::on_signal_error), as for other effects.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    ///
+    /// struct Search {
+    ///     query: Signal<String>,
+    ///     items: Vec<String>,
+    ///     matches: Vec<String>,
+    /// }
+    ///
+    /// impl Search {
+    ///     fn new(items: Vec<String>, cx: &mut Context<Self>) -> Self {
+    ///         let query = cx.create_signal(String::new());
+    ///         cx.create_entity_effect(move |this: &mut Self, cx| {
+    ///             let query = query.get();
+    ///             this.matches = this
+    ///                 .items
+    ///                 .iter()
+    ///                 .filter(|item| item.contains(&query))
+    ///                 .cloned()
+    ///                 .collect();
+    ///             cx.notify();
+    ///         });
+    ///         Self {
+    ///             query,
+    ///             items,
+    ///             matches: Vec::new(),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    fn create_entity_effect(
+        &mut self,
+        effect: impl Fn(&mut Self::Entity, &mut gpui::Context<Self::Entity>) + 'static,
+    );
+
+    /// Create a resource whose value is loaded by the async `fetch`.
+    ///
+    /// The first fetch starts immediately, and the entity is notified whenever the
+    /// resource's state changes.
+    fn create_resource<T, E, Fut>(
+        &mut self,
+        fetch: impl Fn(AsyncApp) -> Fut + 'static,
+    ) -> Resource<T, E>
+    where
+        T: 'static,
+        E: 'static,
+        Fut: Future<Output = Result<T, E>> + 'static;
+
+    /// Create a query whose value is loaded by the async `fetch` and refetched when
+    /// one of `tags` is invalidated.
+    ///
+    /// See [`Query`]. The first fetch starts immediately, and the entity is notified
+    /// whenever the query's state or data changes.
+    fn create_query<T, E, Fut>(
+        &mut self,
+        tags: impl IntoIterator<Item = impl Into<SharedString>>,
+        fetch: impl Fn(AsyncApp) -> Fut + 'static,
+    ) -> Query<T, E>
+    where
+        T: Clone + 'static,
+        E: 'static,
+        Fut: Future<Output = Result<T, E>> + 'static;
+
+    /// Create a mutation running the async `mutate` each time it is started.
+    ///
+    /// See [`Mutation`]. The entity is notified when the mutation starts, finishes,
+    /// or fails.
+    fn create_mutation<A, T, E, Fut>(
+        &mut self,
+        mutate: impl Fn(A, AsyncApp) -> Fut + 'static,
+    ) -> Mutation<A, E>
+    where
+        A: 'static,
+        E: 'static,
+        Fut: Future<Output = Result<T, E>> + 'static;
+
+    /// Create a tracker counting the tasks spawned through it.
+    ///
+    /// See [`TaskTracker`]. The entity is notified whenever the count changes.
+    fn create_task_tracker(&mut self) -> TaskTracker;
+
+    /// Run `f` with read tracking scoped to this entity.
+    ///
+    /// Any signal or memo read inside `f` subscribes the entity, so it re-renders
+    /// when that value changes. This covers signals the entity did not create itself,
+    /// such as globals obtained with `global_signal` or signals passed in by a parent.
+    ///
+    /// Each call replaces the reads tracked by the previous one, so a signal the
+    /// render stops reading, such as one behind a branch that flipped, no longer
+    /// re-renders the entity. Wrap the whole render in one call.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    ///
+    /// struct Label {
+    ///     text: Signal<String>,
+    /// }
+    ///
+    /// impl Render for Label {
+    ///     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    ///         let text = self.text;
+    ///         cx.track_render(|_cx| div().child(text.get()))
+    ///     }
+    /// }
+    /// ```
+    fn track_render<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R;
+
+    /// Compute `compute` from the values of `sources` during render, reusing the last
+    /// result while those values are unchanged.
+    ///
+    /// `sources` is a tuple of signals, read-only signals, or memos, as for
+    /// [`create_memo_from`](Self::create_memo_from). Each `key` keeps one cached
+    /// result per call site and entity, so work such as syntax highlighting or text
+    /// layout preparation is skipped when the entity re-renders for unrelated state.
+    /// Calls made in a loop need a distinct key per iteration, such as the item's
+    /// index, or each would overwrite the others' results. The
+    /// values are compared on every call; for large values, pass a memo of a version
+    /// number instead. The cache is dropped when the entity is released.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    ///
+    /// struct Editor {
+    ///     source: Signal<String>,
+    ///     cursor: Signal<usize>,
+    /// }
+    ///
+    /// fn highlight(source: String) -> Vec<SharedString> {
+    ///     source.lines().map(|line| line.to_string().into()).collect()
+    /// }
+    ///
+    /// impl Render for Editor {
+    ///     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    ///         // Moving the cursor re-renders without highlighting again.
+    ///         let lines = cx.render_cache("lines", (self.source,), highlight);
+    ///         div()
+    ///             .child(format!("Cursor at {}", self.cursor.get()))
+    ///             .children(lines)
+    ///     }
+    /// }
+    /// ```
+    #[track_caller]
+    fn render_cache<S, F, R>(&mut self, key: impl Into<ElementId>, sources: S, compute: F) -> R
+    where
+        S: MemoSources<F, R>,
+        S::Values: PartialEq + Clone + 'static,
+        R: 'static + Clone;
 }
 
+/// An entity, and the key and `render_cache` call site it cached a result for.
+type RenderCacheKey = (EntityId, ElementId, &'static Location<'static>);
+
 // Thread-local storage for tracking subscriptions per entity
 thread_local! {
     static ENTITY_SUBSCRIPTIONS: RefCell<HashMap<EntityId, Vec<Subscription>>> = RefCell::new(HashMap::new());
     static ENTITY_CLEANUP_REGISTERED: RefCell<HashSet<EntityId>> = RefCell::new(HashSet::new());
     static ENTITY_SIGNAL_SUBSCRIPTIONS: RefCell<HashMap<EntityId, HashSet<SignalId>>> = RefCell::new(HashMap::new());
+    static ENTITY_RENDER_OBSERVERS: RefCell<HashMap<EntityId, SignalId>> = RefCell::new(HashMap::new());
+    static ENTITY_RENDER_CACHES: RefCell<HashMap<RenderCacheKey, Box<dyn Any>>> = RefCell::new(HashMap::new());
+    static NOTIFYING_SYNCHRONOUSLY: Cell<bool> = const { Cell::new(false) };
 }
 
 impl<T: 'static> SignalContext for gpui::Context<'_, T> {
+    type Entity = T;
+
     fn create_signal<U: 'static>(&mut self, initial: U) -> Signal<U> {
         let signal = Signal::new(initial);
         let subscription = auto_notify(&signal, self);
         track_subscription(self, subscription);
+        dispose_on_release(self, move || signal.dispose());
+        devtools::record_owner(signal.id(), self.entity_id());
 
         signal
     }
 
+    fn create_signal_vec<U: 'static>(&mut self, initial: Vec<U>) -> SignalVec<U> {
+        SignalVec::from_signal(self.create_signal(initial))
+    }
+
+    fn create_validated_signal<U: 'static, E: 'static>(
+        &mut self,
+        initial: U,
+        validate: impl Fn(&U) -> Result<(), E> + 'static,
+    ) -> ValidatedSignal<U, E> {
+        let error = validate(&initial).err();
+        let validator = Signal::new(Rc::new(validate) as Validator<U, E>);
+        dispose_on_release(self, move || validator.dispose());
+        ValidatedSignal::from_signals(
+            self.create_signal(initial),
+            self.create_signal(error),
+            validator,
+        )
+    }
+
+    fn create_signal_with_reset<U: 'static + Clone + PartialEq>(
+        &mut self,
+        default: U,
+    ) -> ResettableSignal<U> {
+        let value = self.create_signal(default.clone());
+        let default = Signal::new(default);
+        dispose_on_release(self, move || default.dispose());
+        let modified = self.create_memo(move || ResettableSignal::differs(value, default));
+        ResettableSignal::from_parts(value, default, modified)
+    }
+
+    fn create_draft<U: 'static + Clone + PartialEq>(
+        &mut self,
+        source: Signal<U>,
+    ) -> DraftSignal<U> {
+        let draft = self.create_signal(source.get_untracked());
+        let dirty = self.create_memo(move || DraftSignal::differs(draft, source));
+        DraftSignal::from_parts(source, draft, dirty)
+    }
+
+    fn create_nav_stack<U: 'static + Clone>(&mut self, root: U) -> NavStack<U> {
+        let entries = self.create_signal(vec![root]);
+        let current = self.create_memo(move || NavStack::top(entries));
+        let can_go_back = self.create_memo(move || entries.with(|entries| entries.len() > 1));
+        NavStack::from_parts(entries, current, can_go_back)
+    }
+
+    fn create_list_navigation<U: 'static + Clone>(
+        &mut self,
+        items: impl Into<ReadOnlySignal<Vec<U>>>,
+    ) -> ListNavigation<U> {
+        let items = items.into();
+        let cursor = self.create_signal(0);
+        let highlighted_index = self.create_memo(move || ListNavigation::clamp(items, cursor));
+        let highlighted =
+            self.create_memo(move || ListNavigation::item_at(items, highlighted_index));
+        let selected = self.create_signal(None);
+        ListNavigation::from_parts(items, cursor, highlighted_index, highlighted, selected)
+    }
+
     fn create_memo<U: 'static + Clone>(&mut self, compute: impl Fn() -> U + 'static) -> Memo<U> {
-        let memo = Memo::new(compute);
+        own_memo(self, Memo::new(compute))
+    }
+
+    fn create_shared_memo<U: 'static + Clone>(
+        &mut self,
+        key: impl Into<String>,
+        compute: impl Fn() -> U + 'static,
+    ) -> Memo<U> {
+        let key = (TypeId::of::<U>(), key.into());
+        let shared = with_signal_storage(|storage| {
+            storage
+                .acquire_shared_memo(&key)
+                .map(|memo| *memo.downcast_ref::<Memo<U>>().expect("shared memo type"))
+        });
+        // Created outside the storage borrow, since creating a memo registers it there.
+        let memo = shared.unwrap_or_else(|| {
+            let memo = Memo::new(compute);
+            with_signal_storage(|storage| storage.insert_shared_memo(key.clone(), Box::new(memo)));
+            memo
+        });
         let subscription = auto_notify(&memo.signal(), self);
         track_subscription(self, subscription);
-
+        dispose_on_release(self, move || {
+            let released = with_signal_storage(|storage| storage.release_shared_memo(&key));
+            if released.is_some() {
+                memo.signal().dispose();
+            }
+        });
         memo
     }
 
+    fn create_memo_with_prev<U: 'static + Clone>(
+        &mut self,
+        compute: impl Fn(Option<&U>) -> U + 'static,
+    ) -> Memo<U> {
+        own_memo(self, Memo::new_with_prev(compute))
+    }
+
+    fn create_memo_from<S, F, U>(&mut self, sources: S, compute: F) -> Memo<U>
+    where
+        S: MemoSources<F, U> + 'static,
+        F: 'static,
+        U: 'static + Clone,
+    {
+        own_memo(self, Memo::from_sources(sources, compute))
+    }
+
+    fn create_lazy_memo<U: 'static + Clone>(
+        &mut self,
+        compute: impl Fn() -> U + 'static,
+    ) -> Memo<U> {
+        own_memo(self, Memo::lazy(compute))
+    }
+
     fn create_effect(&mut self, effect: impl Fn() + 'static) {
-        let active = Rc::new(Cell::new(true));
-        let active_flag = active.clone();
-        let _effect = Memo::new(move || {
-            if active_flag.get() {
-                effect();
+        self.create_effect_with_priority(effect, EffectPriority::default());
+    }
+
+    fn create_effect_with_priority(
+        &mut self,
+        effect: impl Fn() + 'static,
+        priority: EffectPriority,
+    ) {
+        let effect = Effect::new_with_priority(effect, priority);
+        devtools::record_owner(effect.id(), self.entity_id());
+        dispose_on_release(self, move || effect.stop());
+    }
+
+    fn create_entity_effect(&mut self, effect: impl Fn(&mut T, &mut gpui::Context<T>) + 'static) {
+        // Reads are tracked under a unit signal that wakes the task below, the way
+        // render-time reads are tracked under the render observer.
+        let observer = Signal::new(());
+        let (wake, mut woken) = mpsc::unbounded::<()>();
+        observer.subscribe_while({
+            let wake = wake.clone();
+            move || wake.unbounded_send(()).is_ok()
+        });
+        let _ = wake.unbounded_send(());
+        let task = self.spawn(async move |entity: WeakEntity<T>, cx: &mut AsyncApp| {
+            while woken.next().await.is_some() {
+                while woken.try_recv().is_ok() {}
+                // The panic is caught inside the tracking scope so the scope is restored
+                // after it, and resumed outside the update so the entity isn't left leased.
+                let updated = entity.update(cx, |this, cx| {
+                    observer.track_as_observer(|| {
+                        panic::catch_unwind(AssertUnwindSafe(|| effect(this, cx)))
+                    })
+                });
+                match updated {
+                    Ok(Ok(())) => {}
+                    Ok(Err(payload)) => {
+                        if !report_panic(Computation::Effect, None, observer.id(), &*payload) {
+                            panic::resume_unwind(payload);
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+        devtools::record_owner(observer.id(), self.entity_id());
+        track_subscription(self, Subscription::new(move || drop(task)));
+        dispose_on_release(self, move || observer.dispose());
+    }
+
+    fn create_resource<U, E, Fut>(
+        &mut self,
+        fetch: impl Fn(AsyncApp) -> Fut + 'static,
+    ) -> Resource<U, E>
+    where
+        U: 'static,
+        E: 'static,
+        Fut: Future<Output = Result<U, E>> + 'static,
+    {
+        let state = self.create_signal(ResourceState::Loading);
+        let resource = Resource::new(state, fetch, self);
+        dispose_on_release(self, move || resource.dispose());
+        resource
+    }
+
+    fn create_query<U, E, Fut>(
+        &mut self,
+        tags: impl IntoIterator<Item = impl Into<SharedString>>,
+        fetch: impl Fn(AsyncApp) -> Fut + 'static,
+    ) -> Query<U, E>
+    where
+        U: Clone + 'static,
+        E: 'static,
+        Fut: Future<Output = Result<U, E>> + 'static,
+    {
+        Query::new(tags.into_iter().map(Into::into).collect(), fetch, self)
+    }
+
+    fn create_mutation<A, U, E, Fut>(
+        &mut self,
+        mutate: impl Fn(A, AsyncApp) -> Fut + 'static,
+    ) -> Mutation<A, E>
+    where
+        A: 'static,
+        E: 'static,
+        Fut: Future<Output = Result<U, E>> + 'static,
+    {
+        Mutation::new(mutate, self)
+    }
+
+    fn create_task_tracker(&mut self) -> TaskTracker {
+        let pending = self.create_signal(0);
+        TaskTracker::from_parts(pending, self.create_memo(move || pending.get() > 0))
+    }
+
+    #[track_caller]
+    fn render_cache<S, F, R>(&mut self, key: impl Into<ElementId>, sources: S, compute: F) -> R
+    where
+        S: MemoSources<F, R>,
+        S::Values: PartialEq + Clone + 'static,
+        R: 'static + Clone,
+    {
+        let key = (self.entity_id(), key.into(), Location::caller());
+        let values = sources.values();
+        let (cached, first_use) = ENTITY_RENDER_CACHES.with(|caches| {
+            let caches = caches.borrow();
+            let entry = caches.get(&key);
+            let cached = entry
+                .and_then(|entry| entry.downcast_ref::<(S::Values, R)>())
+                .filter(|(last_values, _)| *last_values == values)
+                .map(|(_, result)| result.clone());
+            (cached, entry.is_none())
+        });
+        if let Some(result) = cached {
+            return result;
+        }
+
+        // Computed outside the cache borrow, since the computation may render
+        // cached content of its own.
+        let result = S::call(&compute, values.clone());
+        ENTITY_RENDER_CACHES.with(|caches| {
+            caches
+                .borrow_mut()
+                .insert(key.clone(), Box::new((values, result.clone())))
+        });
+        if first_use {
+            dispose_on_release(self, move || {
+                ENTITY_RENDER_CACHES.with(|caches| caches.borrow_mut().remove(&key));
+            });
+        }
+        result
+    }
+
+    fn track_render<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let observer = render_observer(self);
+        let (previous, was_read_only) = with_signal_storage(|storage| {
+            let previous = storage.set_observer(Some(observer));
+            // Signals read by an earlier render but not this one must not re-render the
+            // entity. A nested call keeps what the outer call has read so far.
+            if previous != Some(observer) {
+                storage.clear_dependencies(observer);
             }
+            (previous, storage.set_read_only(true))
         });
-        let cleanup_sub = self.on_release(move |_, _| {
-            active.set(false);
+        // Left on unwind too, so a panicking render doesn't leave the thread read-only
+        // and tracking reads under this entity.
+        let _render = ExitRender {
+            previous,
+            was_read_only,
+        };
+        f(self)
+    }
+}
+
+/// Restores the observer and read-only flag replaced by `track_render` when dropped.
+struct ExitRender {
+    previous: Option<SignalId>,
+    was_read_only: bool,
+}
+
+impl Drop for ExitRender {
+    fn drop(&mut self) {
+        with_signal_storage(|storage| {
+            storage.set_observer(self.previous);
+            storage.set_read_only(self.was_read_only);
         });
-        track_subscription(self, cleanup_sub);
     }
 }
 
+/// Notify the entity when `memo` changes and dispose it when the entity is released.
+fn own_memo<V: 'static, T: 'static + Clone>(cx: &mut gpui::Context<V>, memo: Memo<T>) -> Memo<T> {
+    let subscription = auto_notify(&memo.signal(), cx);
+    track_subscription(cx, subscription);
+    dispose_on_release(cx, move || memo.signal().dispose());
+    devtools::record_owner(memo.signal().id(), cx.entity_id());
+    memo
+}
+
+/// The observer id that render-time reads for this entity are tracked under.
+///
+/// The observer is a unit signal that auto-notifies the entity, so any signal that
+/// notifies it (via `track_read`) re-renders the entity.
+fn render_observer<V: 'static>(cx: &mut gpui::Context<V>) -> SignalId {
+    let entity_id = cx.entity_id();
+    if let Some(observer) =
+        ENTITY_RENDER_OBSERVERS.with(|observers| observers.borrow().get(&entity_id).copied())
+    {
+        return observer;
+    }
+
+    let observer = Signal::new(());
+    let subscription = auto_notify(&observer, cx);
+    track_subscription(cx, subscription);
+    dispose_on_release(cx, move || observer.dispose());
+    ENTITY_RENDER_OBSERVERS.with(|observers| {
+        observers.borrow_mut().insert(entity_id, observer.id());
+    });
+    observer.id()
+}
+
 
 
 /// Automatically notify an entity when a signal changes.
@@ -119,18 +755,33 @@ where
     T: 'static,
     V: 'static,
 {
-    let _entity = cx.weak_entity();
+    let entity_id = cx.entity_id();
+    let record = {
+        let signal = *signal;
+        move || {
+            signal.with_storage(|storage| {
+                storage.record_wave_event(WaveEvent::EntityNotified(entity_id), false)
+            });
+        }
+    };
+    if testing::is_synchronous() {
+        let entity = cx.weak_entity();
+        let app = cx.to_async();
+        let unsubscribe = signal.subscribe_removable(move || {
+            record();
+            notify_synchronously(&entity, &app);
+        });
+        return Subscription::new(unsubscribe);
+    }
 
     // Create an async channel to communicate signal changes to the foreground thread
     let (tx, mut rx) = mpsc::unbounded::<()>();
 
     // Subscribe to signal changes - when signal updates, send a message
-    signal.subscribe({
-        let tx = tx.clone();
-        move || {
-            // Ignore errors - if the receiver is dropped, the entity is gone
-            let _ = tx.unbounded_send(());
-        }
+    let unsubscribe = signal.subscribe_removable(move || {
+        record();
+        // Ignore errors - if the receiver is dropped, the entity is gone
+        let _ = tx.unbounded_send(());
     });
 
     // Spawn a task that receives notifications and calls notify on the entity
@@ -152,13 +803,44 @@ where
         },
     );
 
-    // Store the task so it doesn't get dropped
-    // We'll return a subscription that drops the task when the entity is dropped
+    // Dropping the subscription removes the subscriber, which closes the channel
+    // and ends the task.
     Subscription::new(move || {
+        unsubscribe();
         task.detach();
     })
 }
 
+/// Notify `entity` before returning.
+///
+/// The notification holds the app until its observers have run. A write one of them
+/// makes can't update the app again, so its notification waits for the foreground
+/// executor instead, as outside synchronous mode.
+fn notify_synchronously<V: 'static>(entity: &WeakEntity<V>, app: &AsyncApp) {
+    let mut app = app.clone();
+    if NOTIFYING_SYNCHRONOUSLY.with(|notifying| notifying.replace(true)) {
+        let entity = entity.clone();
+        app.foreground_executor()
+            .clone()
+            .spawn(async move {
+                entity.update(&mut app, |_, cx| cx.notify()).ok();
+            })
+            .detach();
+        return;
+    }
+    let _notifying = ExitSynchronousNotify;
+    entity.update(&mut app, |_, cx| cx.notify()).ok();
+}
+
+/// Ends a notification started by `notify_synchronously` when dropped.
+struct ExitSynchronousNotify;
+
+impl Drop for ExitSynchronousNotify {
+    fn drop(&mut self) {
+        NOTIFYING_SYNCHRONOUSLY.with(|notifying| notifying.set(false));
+    }
+}
+
 pub(crate) fn track_subscription<V: 'static>(cx: &mut gpui::Context<V>, subscription: Subscription) {
     let entity_id = cx.entity_id();
     ENTITY_SUBSCRIPTIONS.with(|subs| {
@@ -180,15 +862,21 @@ pub(crate) fn track_subscription<V: 'static>(cx: &mut gpui::Context<V>, subscrip
 
     if needs_cleanup {
         let cleanup_sub = cx.on_release(move |_, _| {
-            ENTITY_SUBSCRIPTIONS.with(|subs| {
-                subs.borrow_mut().remove(&entity_id);
-            });
+            // Drop the subscriptions outside the borrow, since dropping one can
+            // dispose signals whose cleanup reaches this map again.
+            let subscriptions =
+                ENTITY_SUBSCRIPTIONS.with(|subs| subs.borrow_mut().remove(&entity_id));
+            drop(subscriptions);
             ENTITY_CLEANUP_REGISTERED.with(|registered| {
                 registered.borrow_mut().remove(&entity_id);
             });
             ENTITY_SIGNAL_SUBSCRIPTIONS.with(|subs| {
                 subs.borrow_mut().remove(&entity_id);
             });
+            ENTITY_RENDER_OBSERVERS.with(|observers| {
+                observers.borrow_mut().remove(&entity_id);
+            });
+            devtools::forget_entity(entity_id);
         });
         ENTITY_SUBSCRIPTIONS.with(|subs| {
             subs.borrow_mut()
@@ -199,6 +887,14 @@ pub(crate) fn track_subscription<V: 'static>(cx: &mut gpui::Context<V>, subscrip
     }
 }
 
+/// Run `dispose` when the entity is released, to free signals it owns.
+pub(crate) fn dispose_on_release<V: 'static>(
+    cx: &mut gpui::Context<V>,
+    dispose: impl FnOnce() + 'static,
+) {
+    track_subscription(cx, Subscription::new(dispose));
+}
+
 pub(crate) fn subscribe_once<V: 'static, T: 'static>(
     cx: &mut gpui::Context<V>,
     signal: &Signal<T>,
@@ -273,11 +969,190 @@ mod tests {
             .borrow()
             .as_ref()
             .expect("signal handle missing");
-        signal.set(2);
+        // Debug builds panic on the stale write; either way the effect must not run.
+        let _ = std::panic::catch_unwind(|| signal.set(2));
 
         assert_eq!(effect_count.get(), initial_count + 1);
     }
 
+    struct LogEntity {
+        lines: Vec<String>,
+    }
+
+    #[gpui::test]
+    async fn test_entity_effect_updates_the_entity(cx: &mut TestAppContext) {
+        let level = Signal::new("info");
+        let entity = cx.update(|cx| {
+            cx.new(|cx| {
+                cx.create_entity_effect(move |this: &mut LogEntity, _cx| {
+                    this.lines.push(format!("level: {}", level.get()));
+                });
+                LogEntity { lines: Vec::new() }
+            })
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            entity.read_with(cx, |this, _| this.lines.clone()),
+            ["level: info"]
+        );
+
+        level.set("warn");
+        level.set("error");
+        cx.run_until_parked();
+        assert_eq!(
+            entity.read_with(cx, |this, _| this.lines.clone()),
+            ["level: info", "level: error"]
+        );
+
+        drop(entity);
+        cx.update(|_| {});
+        level.set("debug");
+        cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    async fn test_entity_effect_panics_are_reported(cx: &mut TestAppContext) {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        crate::on_signal_error({
+            let errors = errors.clone();
+            move |error| errors.borrow_mut().push(error.to_string())
+        });
+        let level = Signal::new("info");
+        let entity = cx.update(|cx| {
+            cx.new(|cx| {
+                cx.create_entity_effect(move |this: &mut LogEntity, _cx| {
+                    let level = level.get();
+                    assert_ne!(level, "fatal", "unsupported level");
+                    this.lines.push(format!("level: {level}"));
+                });
+                LogEntity { lines: Vec::new() }
+            })
+        });
+        cx.run_until_parked();
+
+        level.set("fatal");
+        cx.run_until_parked();
+        assert_eq!(errors.borrow().len(), 1);
+        assert!(errors.borrow()[0].contains("unsupported level"));
+
+        // The effect still re-runs once its input recovers.
+        level.set("warn");
+        cx.run_until_parked();
+        assert_eq!(
+            entity.read_with(cx, |this, _| this.lines.clone()),
+            ["level: info", "level: warn"]
+        );
+    }
+
+    struct MemoEntity {
+        memo: Memo<usize>,
+    }
+
+    #[gpui::test]
+    async fn test_shared_memo_computes_once_for_all_entities(cx: &TestAppContext) {
+        let document = Signal::new("one two".to_string());
+        let runs = Rc::new(Cell::new(0));
+        let new_view = |cx: &mut gpui::App| {
+            let runs = runs.clone();
+            cx.new(|cx| MemoEntity {
+                memo: cx.create_shared_memo("words", move || {
+                    runs.set(runs.get() + 1);
+                    document.with(|text| text.split_whitespace().count())
+                }),
+            })
+        };
+        let first = cx.update(new_view);
+        let second = cx.update(new_view);
+        let memo = first.read_with(cx, |view, _| view.memo);
+        assert_eq!(second.read_with(cx, |view, _| view.memo), memo);
+
+        document.set("one two three".to_string());
+        assert_eq!(memo.get(), 3);
+        assert_eq!(second.read_with(cx, |view, _| view.memo.get()), 3);
+        assert_eq!(runs.get(), 2);
+
+        drop(first);
+        cx.update(|_| {});
+        assert_eq!(memo.try_get(), Some(3));
+        drop(second);
+        cx.update(|_| {});
+        assert_eq!(memo.try_get(), None);
+    }
+
+    #[gpui::test]
+    async fn test_render_cache_skips_unchanged_sources(cx: &TestAppContext) {
+        struct Highlighted {
+            source: Signal<String>,
+            cursor: Signal<usize>,
+        }
+
+        let runs = Rc::new(Cell::new(0));
+        let entity = cx.update(|cx| {
+            cx.new(|cx| Highlighted {
+                source: cx.create_signal("fn main() {}".to_string()),
+                cursor: cx.create_signal(0),
+            })
+        });
+        let render = |cx: &mut gpui::App| {
+            let runs = runs.clone();
+            entity.update(cx, |this, cx| {
+                let _ = this.cursor.get();
+                cx.render_cache("length", (this.source,), move |source| {
+                    runs.set(runs.get() + 1);
+                    source.len()
+                })
+            })
+        };
+
+        assert_eq!(cx.update(render), 12);
+        let (source, cursor) = entity.read_with(cx, |this, _| (this.source, this.cursor));
+        cursor.set(3);
+        assert_eq!(cx.update(render), 12);
+        assert_eq!(runs.get(), 1);
+
+        source.set("fn main() { run() }".to_string());
+        assert_eq!(cx.update(render), 19);
+        assert_eq!(runs.get(), 2);
+
+        drop(entity);
+        cx.update(|_| {});
+        assert!(ENTITY_RENDER_CACHES.with(|caches| caches.borrow().is_empty()));
+    }
+
+    #[gpui::test]
+    async fn test_render_cache_keys_calls_from_one_call_site(cx: &TestAppContext) {
+        struct Lines {
+            lines: Vec<Signal<String>>,
+        }
+
+        let runs = Rc::new(Cell::new(0));
+        let entity = cx.update(|cx| {
+            cx.new(|cx| Lines {
+                lines: vec![
+                    cx.create_signal("a".to_string()),
+                    cx.create_signal("bc".to_string()),
+                ],
+            })
+        });
+        let render = |cx: &mut gpui::App| {
+            entity.update(cx, |this, cx| {
+                let mut lengths = Vec::new();
+                for (index, line) in this.lines.clone().into_iter().enumerate() {
+                    let runs = runs.clone();
+                    lengths.push(cx.render_cache(index, (line,), move |line| {
+                        runs.set(runs.get() + 1);
+                        line.len()
+                    }));
+                }
+                lengths
+            })
+        };
+
+        assert_eq!(cx.update(render), vec![1, 2]);
+        assert_eq!(cx.update(render), vec![1, 2]);
+        assert_eq!(runs.get(), 2);
+    }
+
     #[gpui::test]
     async fn test_subscriptions_cleanup_on_release(cx: &TestAppContext) {
         struct SubscriptionEntity {
@@ -301,6 +1176,137 @@ mod tests {
         assert!(!has_entry);
     }
 
+    #[gpui::test]
+    async fn test_owned_signals_disposed_on_release(cx: &TestAppContext) {
+        struct OwnerEntity {
+            count: Signal<i32>,
+            doubled: Memo<i32>,
+        }
+
+        let entity = cx.update(|cx| {
+            cx.new(|cx| {
+                let count = cx.create_signal(1);
+                let doubled = cx.create_memo(move || count.get() * 2);
+                OwnerEntity { count, doubled }
+            })
+        });
+        let (count, doubled) = entity.read_with(cx, |this, _| (this.count, this.doubled));
+        assert_eq!(count.try_get(), Some(1));
+        assert_eq!(doubled.try_get(), Some(2));
+
+        drop(entity);
+        cx.update(|_| {});
+
+        assert_eq!(count.try_get(), None);
+        assert_eq!(doubled.try_get(), None);
+    }
+
+    #[gpui::test]
+    async fn test_track_render_subscribes_entity(cx: &mut TestAppContext) {
+        struct TrackedEntity;
+
+        let external = Signal::new(0);
+        let untracked = Signal::new(0);
+        let entity = cx.update(|cx| cx.new(|_| TrackedEntity));
+
+        let notify_count = Rc::new(Cell::new(0));
+        let _observation = cx.update(|cx| {
+            let notify_count = notify_count.clone();
+            cx.observe(&entity, move |_, _| notify_count.set(notify_count.get() + 1))
+        });
+
+        let value = cx.update(|cx| {
+            entity.update(cx, |_, cx| {
+                let _ = untracked.get();
+                cx.track_render(|_| external.get())
+            })
+        });
+        assert_eq!(value, 0);
+
+        untracked.set(1);
+        cx.run_until_parked();
+        assert_eq!(notify_count.get(), 0);
+
+        external.set(1);
+        cx.run_until_parked();
+        assert_eq!(notify_count.get(), 1);
+
+        // A render that no longer reads `external` stops re-rendering for it.
+        cx.update(|cx| entity.update(cx, |_, cx| cx.track_render(|_| untracked.get())));
+        external.set(2);
+        cx.run_until_parked();
+        assert_eq!(notify_count.get(), 1);
+        untracked.set(2);
+        cx.run_until_parked();
+        assert_eq!(notify_count.get(), 2);
+
+        let entity_id = entity.entity_id();
+        drop(entity);
+        cx.update(|_| {});
+
+        let has_observer = ENTITY_RENDER_OBSERVERS
+            .with(|observers| observers.borrow().contains_key(&entity_id));
+        assert!(!has_observer);
+    }
+
+    #[gpui::test]
+    fn test_synchronous_mode_notifies_inline(cx: &mut TestAppContext) {
+        struct CountEntity {
+            count: Signal<i32>,
+        }
+
+        let synchronous = testing::synchronous_mode();
+        let entity = cx.new(|cx| CountEntity {
+            count: cx.create_signal(0),
+        });
+        let count = entity.read_with(cx, |this, _| this.count);
+        let notify_count = Rc::new(Cell::new(0));
+        let _observation = cx.update(|cx| {
+            let notify_count = notify_count.clone();
+            cx.observe(&entity, move |_, _| {
+                notify_count.set(notify_count.get() + 1)
+            })
+        });
+
+        count.set(1);
+        assert_eq!(notify_count.get(), 1);
+        count.set(2);
+        assert_eq!(notify_count.get(), 2);
+
+        let external = Signal::new(0);
+        let subscription = entity.update(cx, |_, cx| auto_notify(&external, cx));
+        external.set(1);
+        assert_eq!(notify_count.get(), 3);
+        drop(subscription);
+        external.set(2);
+        assert_eq!(notify_count.get(), 3);
+
+        // A write made by an observer of the notification still holding the app waits
+        // for the executor.
+        let other = cx.new(|cx| CountEntity {
+            count: cx.create_signal(0),
+        });
+        let other_count = other.read_with(cx, |this, _| this.count);
+        let other_notify_count = Rc::new(Cell::new(0));
+        let _observations = cx.update(|cx| {
+            let other_notify_count = other_notify_count.clone();
+            (
+                cx.observe(&entity, move |_, _| other_count.set(1)),
+                cx.observe(&other, move |_, _| {
+                    other_notify_count.set(other_notify_count.get() + 1)
+                }),
+            )
+        });
+        count.set(3);
+        assert_eq!(other_count.get(), 1);
+        assert_eq!(other_notify_count.get(), 0);
+        cx.run_until_parked();
+        assert_eq!(other_notify_count.get(), 1);
+
+        drop(synchronous);
+        assert!(!testing::is_synchronous());
+    }
+
     #[gpui::test]
     async fn test_subscribe_once_per_entity(cx: &TestAppContext) {
         struct SubOnceEntity {
@@ -337,4 +1343,25 @@ mod tests {
             ENTITY_SIGNAL_SUBSCRIPTIONS.with(|subs| subs.borrow().contains_key(&entity_id));
         assert!(!has_entry);
     }
+
+    #[cfg(debug_assertions)]
+    #[gpui::test]
+    async fn test_track_render_restores_writes_after_a_panic(cx: &mut TestAppContext) {
+        struct TrackedEntity;
+
+        let signal = Signal::new(0);
+        let entity = cx.update(|cx| cx.new(|_| TrackedEntity));
+        cx.update(|cx| {
+            entity.update(cx, |_, cx| {
+                let render = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    cx.track_render(|_| signal.set(1))
+                }));
+                assert!(render.is_err());
+            })
+        });
+
+        signal.set(2);
+        assert_eq!(signal.get(), 2);
+        assert_eq!(with_signal_storage(|storage| storage.observer()), None);
+    }
 }
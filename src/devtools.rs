@@ -0,0 +1,436 @@
+//! Introspection for debugging tools.
+//!
+//! Signals and memos created through [`SignalContext`](crate::SignalContext) record
+//! the entity that created them, so a misbehaving signal found by id can be traced
+//! back to the view that owns it. Records are dropped when the entity is released.
+//! [`live_signals`] lists what has not been disposed, which
+//! [`report_leaks_on_quit`] prints when the app exits.
+//!
+//! Signals given a name with [`name_signal`] are captured by [`snapshot`], and
+//! [`diff_snapshots`] reports which of them changed between two snapshots, to answer
+//! "what changed between these two moments" without logging every write.
+//! [`export_graph`] draws which memos and effects each signal feeds.
+
+use crate::storage::with_signal_storage;
+pub use crate::storage::SignalId;
+use crate::ReadOnlySignal;
+use gpui::{App, EntityId};
+use slotmap::Key;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::panic::Location;
+use std::rc::Rc;
+
+/// Formats a named signal's value, or returns `None` once it is disposed.
+type Formatter = Rc<dyn Fn() -> Option<String>>;
+
+thread_local! {
+    static SIGNAL_OWNERS: RefCell<HashMap<SignalId, EntityId>> = RefCell::new(HashMap::new());
+    static NAMED_SIGNALS: RefCell<BTreeMap<String, Formatter>> = RefCell::new(BTreeMap::new());
+}
+
+/// The signals and memos created by `entity`, in no particular order.
+pub fn signals_for_entity(entity: EntityId) -> Vec<SignalId> {
+    SIGNAL_OWNERS.with(|owners| {
+        owners
+            .borrow()
+            .iter()
+            .filter(|(_, owner)| **owner == entity)
+            .map(|(signal, _)| *signal)
+            .collect()
+    })
+}
+
+/// The entity that created `signal`, if it was created through
+/// [`SignalContext`](crate::SignalContext) and the entity is still alive.
+pub fn entity_for_signal(signal: SignalId) -> Option<EntityId> {
+    SIGNAL_OWNERS.with(|owners| owners.borrow().get(&signal).copied())
+}
+
+/// A signal that had not been disposed when [`live_signals`] was called.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LiveSignal {
+    /// The signal's id.
+    pub id: SignalId,
+    /// The name of the signal's value type.
+    pub type_name: &'static str,
+    /// The number of callbacks subscribed to the signal.
+    pub subscribers: usize,
+    /// The entity that created the signal, if it was created through
+    /// [`SignalContext`](crate::SignalContext) and the entity is still alive.
+    pub owner: Option<EntityId>,
+    /// Where the signal was last written with `set` or `update`, in debug builds or
+    /// with the `provenance` feature.
+    pub last_writer: Option<&'static Location<'static>>,
+}
+
+impl fmt::Display for LiveSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} {} with {} subscribers",
+            self.id, self.type_name, self.subscribers
+        )?;
+        match self.owner {
+            Some(owner) => write!(f, ", owned by {owner:?}")?,
+            None => f.write_str(", not owned by an entity")?,
+        }
+        match self.last_writer {
+            Some(location) => write!(f, ", last written at {location}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Every signal and memo in the thread's default runtime that has not been disposed.
+///
+/// This includes the internal signals that memos and effects track their reads
+/// under, which show up with a value type of `()`.
+pub fn live_signals() -> Vec<LiveSignal> {
+    let live = with_signal_storage(|storage| storage.live_signals());
+    live.into_iter()
+        .map(|(id, type_name, subscribers, last_writer)| LiveSignal {
+            id,
+            type_name,
+            subscribers,
+            owner: entity_for_signal(id),
+            last_writer,
+        })
+        .collect()
+}
+
+/// Print the signals still alive when the app quits to stderr, in debug builds.
+///
+/// Signals that outlive every view usually point at a handle kept in a global or a
+/// closure that never released it, which the arena cannot free on its own. Call this
+/// once at startup; it does nothing in release builds.
+pub fn report_leaks_on_quit(cx: &mut App) {
+    if cfg!(debug_assertions) {
+        cx.on_app_quit(|_| {
+            let live = live_signals();
+            if !live.is_empty() {
+                eprintln!("gpui_signals: {} signals alive at quit", live.len());
+                for signal in &live {
+                    eprintln!("  {signal}");
+                }
+            }
+            async {}
+        })
+        .detach();
+    }
+}
+
+/// The signals, memos, and effects of the thread's default runtime as a Graphviz DOT
+/// graph.
+///
+/// Signals are drawn as ellipses, memos as boxes, and effects as octagons. Other
+/// computations that track reads, such as a view's render, are drawn as diamonds.
+/// Each edge points from a signal or memo to a computation that read it on its last
+/// run, so following the edges from a signal shows the side effects a write to it
+/// triggers. Nodes are labelled with the name given to `Memo::named` or
+/// `Effect::named`, or else the value type, and with the owning entity.
+pub fn export_graph() -> String {
+    let graph = with_signal_storage(|storage| storage.graph());
+    let node = |id: SignalId| format!("s{}", id.data().as_ffi());
+    let mut dot = String::from("digraph signals {\n");
+    for entry in &graph {
+        let (kind, shape) = match (entry.is_memo, entry.is_effect) {
+            (true, _) => ("memo", "box"),
+            (_, true) => ("effect", "octagon"),
+            _ if !entry.dependencies.is_empty() => ("computation", "diamond"),
+            _ => ("signal", "ellipse"),
+        };
+        let mut label = match &entry.name {
+            Some(name) => format!("{kind} {name}"),
+            None => format!("{kind} {}", entry.type_name),
+        };
+        if let Some(owner) = entity_for_signal(entry.id) {
+            label.push_str(&format!("\\nowned by {owner:?}"));
+        }
+        let label = label.replace('"', "\\\"");
+        dot.push_str(&format!(
+            "    {} [label=\"{label}\", shape={shape}];\n",
+            node(entry.id)
+        ));
+    }
+    for entry in &graph {
+        for dependency in &entry.dependencies {
+            dot.push_str(&format!(
+                "    {} -> {};\n",
+                node(*dependency),
+                node(entry.id)
+            ));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Include `signal` in [`snapshot`]s under `name`, formatted with `Debug`.
+///
+/// Naming another signal with the same name replaces it. The name is forgotten once
+/// the signal is disposed.
+pub fn name_signal<T: fmt::Debug + 'static>(
+    name: impl Into<String>,
+    signal: impl Into<ReadOnlySignal<T>>,
+) {
+    let signal = signal.into();
+    let format: Formatter = Rc::new(move || {
+        signal
+            .is_alive()
+            .then(|| signal.with_untracked(|value| format!("{value:?}")))
+    });
+    NAMED_SIGNALS.with(|named| named.borrow_mut().insert(name.into(), format));
+}
+
+/// The values of every named signal at one moment, taken with [`snapshot`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    values: BTreeMap<String, String>,
+}
+
+impl Snapshot {
+    /// The `Debug` text of the signal named `name`, if it was alive.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    /// The names and values, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+/// Capture the current value of every signal named with [`name_signal`].
+pub fn snapshot() -> Snapshot {
+    // Collected first, since formatting a memo may recompute it and run code that
+    // names more signals.
+    let named: Vec<(String, Formatter)> = NAMED_SIGNALS.with(|named| {
+        named
+            .borrow()
+            .iter()
+            .map(|(name, format)| (name.clone(), format.clone()))
+            .collect()
+    });
+    let mut values = BTreeMap::new();
+    for (name, format) in named {
+        match format() {
+            Some(value) => {
+                values.insert(name, value);
+            }
+            None => NAMED_SIGNALS.with(|named| {
+                named.borrow_mut().remove(&name);
+            }),
+        }
+    }
+    Snapshot { values }
+}
+
+/// How a named signal differs between two [`Snapshot`]s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SignalChange {
+    /// The signal was only in the later snapshot.
+    Added { name: String, value: String },
+    /// The signal was only in the earlier snapshot, having been disposed since.
+    Removed { name: String, value: String },
+    /// The signal's value changed.
+    Changed {
+        name: String,
+        before: String,
+        after: String,
+    },
+}
+
+impl SignalChange {
+    /// The name of the signal that changed.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Added { name, .. } | Self::Removed { name, .. } | Self::Changed { name, .. } => {
+                name
+            }
+        }
+    }
+}
+
+impl fmt::Display for SignalChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added { name, value } => write!(f, "+ {name} = {value}"),
+            Self::Removed { name, value } => write!(f, "- {name} = {value}"),
+            Self::Changed {
+                name,
+                before,
+                after,
+            } => write!(f, "~ {name}: {before} -> {after}"),
+        }
+    }
+}
+
+/// The named signals that differ between `before` and `after`, sorted by name.
+pub fn diff_snapshots(before: &Snapshot, after: &Snapshot) -> Vec<SignalChange> {
+    let mut changes = Vec::new();
+    for (name, old) in &before.values {
+        match after.values.get(name) {
+            Some(new) if new != old => changes.push(SignalChange::Changed {
+                name: name.clone(),
+                before: old.clone(),
+                after: new.clone(),
+            }),
+            Some(_) => {}
+            None => changes.push(SignalChange::Removed {
+                name: name.clone(),
+                value: old.clone(),
+            }),
+        }
+    }
+    for (name, value) in &after.values {
+        if !before.values.contains_key(name) {
+            changes.push(SignalChange::Added {
+                name: name.clone(),
+                value: value.clone(),
+            });
+        }
+    }
+    changes.sort_by(|a, b| a.name().cmp(b.name()));
+    changes
+}
+
+pub(crate) fn record_owner(signal: SignalId, entity: EntityId) {
+    SIGNAL_OWNERS.with(|owners| {
+        owners.borrow_mut().insert(signal, entity);
+    });
+}
+
+pub(crate) fn forget_entity(entity: EntityId) {
+    SIGNAL_OWNERS.with(|owners| {
+        owners.borrow_mut().retain(|_, owner| *owner != entity);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SignalContext;
+    use gpui::{AppContext, TestAppContext};
+
+    struct Panel;
+
+    #[gpui::test]
+    fn test_signals_map_to_their_entity(cx: &mut TestAppContext) {
+        let (entity, signal, memo) = cx.update(|cx| {
+            let mut ids = None;
+            let entity = cx.new(|cx| {
+                let signal = cx.create_signal(1);
+                let memo = cx.create_memo(move || signal.get() * 2);
+                ids = Some((signal.id(), memo.signal().id()));
+                Panel
+            });
+            let (signal, memo) = ids.unwrap();
+            (entity, signal, memo)
+        });
+        let entity_id = entity.entity_id();
+
+        assert_eq!(entity_for_signal(signal), Some(entity_id));
+        assert_eq!(entity_for_signal(memo), Some(entity_id));
+        let mut owned = signals_for_entity(entity_id);
+        owned.sort();
+        let mut expected = vec![signal, memo];
+        expected.sort();
+        assert_eq!(owned, expected);
+
+        drop(entity);
+        cx.update(|_| {});
+        assert_eq!(entity_for_signal(signal), None);
+        assert!(signals_for_entity(entity_id).is_empty());
+    }
+
+    #[test]
+    fn test_graph_export_shows_effects_downstream_of_signals() {
+        let count = crate::Signal::new(1);
+        let doubled = crate::Memo::named("doubled", move || count.get() * 2);
+        let effect = crate::Effect::named("log \"doubled\"", move || {
+            doubled.get();
+        });
+
+        let dot = export_graph();
+        let node = |id: SignalId| format!("s{}", id.data().as_ffi());
+        let (count, doubled, effect) = (count.id(), doubled.signal().id(), effect.id());
+        assert!(dot.contains(&format!(
+            "{} [label=\"signal i32\", shape=ellipse];",
+            node(count)
+        )));
+        assert!(dot.contains(&format!(
+            "{} [label=\"memo doubled\", shape=box];",
+            node(doubled)
+        )));
+        assert!(dot.contains(&format!(
+            "{} [label=\"effect log \\\"doubled\\\"\", shape=octagon];",
+            node(effect)
+        )));
+        assert!(dot.contains(&format!("{} -> {};", node(count), node(doubled))));
+        assert!(dot.contains(&format!("{} -> {};", node(doubled), node(effect))));
+    }
+
+    #[test]
+    fn test_diff_snapshots_reports_changed_added_and_removed_signals() {
+        let query = crate::Signal::new(String::from("rust"));
+        let page = crate::Signal::new(1);
+        let results = crate::Signal::new(vec![1, 2]);
+        name_signal("search.query", query);
+        name_signal("search.page", page);
+        name_signal("search.results", results);
+        let before = snapshot();
+
+        page.set(2);
+        results.dispose();
+        let selected = crate::Signal::new(Some(0));
+        name_signal("search.selected", selected);
+        let after = snapshot();
+
+        let changes = diff_snapshots(&before, &after);
+        let lines: Vec<String> = changes.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            lines,
+            [
+                "~ search.page: 1 -> 2",
+                "- search.results = [1, 2]",
+                "+ search.selected = Some(0)",
+            ]
+        );
+        assert_eq!(after.get("search.query"), Some("\"rust\""));
+    }
+
+    #[gpui::test]
+    fn test_live_signals_report_type_subscribers_and_owner(cx: &mut TestAppContext) {
+        let (entity, signal) = cx.update(|cx| {
+            let mut signal = None;
+            let entity = cx.new(|cx| {
+                signal = Some(cx.create_signal(String::new()));
+                Panel
+            });
+            (entity, signal.unwrap())
+        });
+        let stray = crate::Signal::new(0u8);
+
+        let live = live_signals();
+        let owned = live.iter().find(|live| live.id == signal.id()).unwrap();
+        assert_eq!(owned.type_name, "alloc::string::String");
+        assert_eq!(owned.subscribers, 1);
+        assert_eq!(owned.owner, Some(entity.entity_id()));
+        let unowned = live.iter().find(|live| live.id == stray.id()).unwrap();
+        assert_eq!(
+            unowned.to_string(),
+            format!(
+                "{:?} u8 with 0 subscribers, not owned by an entity",
+                stray.id()
+            )
+        );
+
+        drop(entity);
+        cx.update(|_| {});
+        assert!(!live_signals().iter().any(|live| live.id == signal.id()));
+    }
+}
@@ -0,0 +1,304 @@
+//! Drag-and-drop state shared across views.
+//!
+//! A single global drag signal holds the in-flight [`DragPayload`], and a second
+//! global signal records which drop target the drag is currently over. Views derive
+//! per-target `is_hovered` memos from these, so drag interactions that span several
+//! entities react to the same state without ad-hoc globals.
+
+use crate::context::{auto_notify, track_subscription};
+use crate::{Memo, Signal};
+use gpui::{
+    App, Context, EntityId, Global, InteractiveElement, MouseButton, SharedString,
+    StatefulInteractiveElement, Window,
+};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+/// The value carried by an in-flight drag.
+///
+/// Payloads are cheap to clone; the value itself is reference counted.
+#[derive(Clone)]
+pub struct DragPayload {
+    kind: SharedString,
+    value: Rc<dyn Any>,
+}
+
+impl DragPayload {
+    /// Create a payload of the given kind wrapping `value`.
+    ///
+    /// The kind lets drop targets decide whether they accept a payload without
+    /// knowing its concrete type.
+    pub fn new<T: 'static>(kind: impl Into<SharedString>, value: T) -> Self {
+        Self {
+            kind: kind.into(),
+            value: Rc::new(value),
+        }
+    }
+
+    /// The kind this payload was created with.
+    pub fn kind(&self) -> &SharedString {
+        &self.kind
+    }
+
+    /// Borrow the payload value if it is of type `T`.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.value.downcast_ref()
+    }
+}
+
+impl PartialEq for DragPayload {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && Rc::ptr_eq(&self.value, &other.value)
+    }
+}
+
+impl fmt::Debug for DragPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DragPayload")
+            .field("kind", &self.kind)
+            .finish_non_exhaustive()
+    }
+}
+
+struct DragState {
+    payload: Signal<Option<DragPayload>>,
+    hovered: Signal<Option<SharedString>>,
+    /// `is_hovered` memos by the entity that asked for them, or `None` for calls on
+    /// `App`, and drop target, so calling it on every render reuses the same memo.
+    targets: HashMap<(Option<EntityId>, SharedString), Memo<bool>>,
+}
+
+impl Default for DragState {
+    fn default() -> Self {
+        Self {
+            payload: Signal::new(None),
+            hovered: Signal::new(None),
+            targets: HashMap::new(),
+        }
+    }
+}
+
+impl Global for DragState {}
+
+fn drag_state(cx: &mut App) -> (Signal<Option<DragPayload>>, Signal<Option<SharedString>>) {
+    let state = cx.default_global::<DragState>();
+    (state.payload, state.hovered)
+}
+
+/// The memo tracking whether a drag is over `target` for `owner`, and whether this
+/// call created it.
+fn hovered_memo(cx: &mut App, owner: Option<EntityId>, target: SharedString) -> (Memo<bool>, bool) {
+    let (drag, hovered) = drag_state(cx);
+    let state = cx.default_global::<DragState>();
+    let mut created = false;
+    let memo = *state
+        .targets
+        .entry((owner, target.clone()))
+        .or_insert_with(|| {
+            created = true;
+            Memo::new(move || {
+                drag.with(|payload| payload.is_some())
+                    && hovered.with(|hovered| hovered.as_ref() == Some(&target))
+            })
+        });
+    (memo, created)
+}
+
+/// Start a drag carrying `payload`.
+///
+/// This is what [`DragDropExt::drag_source`] calls on mouse down; use it directly
+/// when a drag should begin from some other gesture.
+pub fn start_drag(cx: &mut App, payload: DragPayload) {
+    let (drag, hovered) = drag_state(cx);
+    hovered.set_if_changed(None);
+    drag.set(Some(payload));
+}
+
+/// End the current drag, if any, without dropping it anywhere.
+pub fn end_drag(cx: &mut App) {
+    let (drag, hovered) = drag_state(cx);
+    hovered.set_if_changed(None);
+    drag.set_if_changed(None);
+}
+
+/// Extension trait for accessing drag-and-drop state.
+pub trait DragContext {
+    /// The global drag signal.
+    ///
+    /// Holds `Some` while a drag is in flight.
+    fn drag_signal(&mut self) -> Signal<Option<DragPayload>>;
+
+    /// A memo that is `true` while a drag is over the given drop target.
+    ///
+    /// Every call for the same target returns the same memo, so this is cheap to
+    /// call from render. Memos made through an entity's context are disposed when
+    /// the entity is released, so targets named per row or per item don't pile up;
+    /// those made through `App` last as long as the app.
+    fn is_hovered(&mut self, target: impl Into<SharedString>) -> Memo<bool>;
+}
+
+impl DragContext for App {
+    fn drag_signal(&mut self) -> Signal<Option<DragPayload>> {
+        drag_state(self).0
+    }
+
+    fn is_hovered(&mut self, target: impl Into<SharedString>) -> Memo<bool> {
+        hovered_memo(self, None, target.into()).0
+    }
+}
+
+impl<V: 'static> DragContext for Context<'_, V> {
+    fn drag_signal(&mut self) -> Signal<Option<DragPayload>> {
+        drag_state(self).0
+    }
+
+    /// The memo notifies this entity when it changes.
+    fn is_hovered(&mut self, target: impl Into<SharedString>) -> Memo<bool> {
+        let owner = self.entity_id();
+        let target = target.into();
+        let (memo, created) = hovered_memo(self, Some(owner), target.clone());
+        if created {
+            let subscription = auto_notify(&memo.signal(), self);
+            track_subscription(self, subscription);
+            self.on_release(move |_, cx| {
+                let state = cx.default_global::<DragState>();
+                state.targets.remove(&(Some(owner), target));
+                memo.signal().dispose();
+            })
+            .detach();
+        }
+        memo
+    }
+}
+
+/// Element extensions that wire GPUI mouse events into the global drag state.
+pub trait DragDropExt: InteractiveElement {
+    /// Start a drag carrying `payload` when the left mouse button is pressed on
+    /// this element, and end it when the button is released anywhere.
+    fn drag_source(self, payload: DragPayload) -> Self {
+        // Ending the drag is deferred so that a drop target handling the same
+        // mouse up event still sees the payload.
+        self.on_mouse_down(MouseButton::Left, move |_, _, cx| {
+            start_drag(cx, payload.clone());
+        })
+        .on_mouse_up(MouseButton::Left, |_, _, cx| cx.defer(end_drag))
+        .on_mouse_up_out(MouseButton::Left, |_, _, cx| cx.defer(end_drag))
+    }
+
+    /// Mark this element as a drop target named `target`.
+    ///
+    /// While a drag is over the element, memos from [`DragContext::is_hovered`] for
+    /// this target are `true`. Releasing the mouse over it calls `on_drop` with the
+    /// payload and ends the drag.
+    fn drop_target(
+        self,
+        target: impl Into<SharedString>,
+        on_drop: impl Fn(&DragPayload, &mut Window, &mut App) + 'static,
+    ) -> Self
+    where
+        Self: StatefulInteractiveElement,
+    {
+        let target = target.into();
+        self.on_mouse_move({
+            let target = target.clone();
+            move |_, _, cx| {
+                let (drag, hovered) = drag_state(cx);
+                if drag.with_untracked(|payload| payload.is_some()) {
+                    hovered.set_if_changed(Some(target.clone()));
+                }
+            }
+        })
+        .on_hover({
+            let target = target.clone();
+            move |is_hovered, _, cx| {
+                let (_, hovered) = drag_state(cx);
+                if !*is_hovered && hovered.with_untracked(|h| h.as_ref() == Some(&target)) {
+                    hovered.set(None);
+                }
+            }
+        })
+        .on_mouse_up(MouseButton::Left, move |_, window, cx| {
+            let (drag, hovered) = drag_state(cx);
+            if !hovered.with_untracked(|h| h.as_ref() == Some(&target)) {
+                return;
+            }
+            if let Some(payload) = drag.get_untracked() {
+                on_drop(&payload, window, cx);
+            }
+            end_drag(cx);
+        })
+    }
+}
+
+impl<E: InteractiveElement> DragDropExt for E {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext, TestAppContext};
+
+    #[gpui::test]
+    async fn test_drag_lifecycle(cx: &TestAppContext) {
+        cx.update(|cx| {
+            let drag = cx.drag_signal();
+            assert_eq!(drag.get(), None);
+
+            start_drag(cx, DragPayload::new("todo", 7usize));
+            let payload = drag.get().expect("drag should be active");
+            assert_eq!(payload.kind(), "todo");
+            assert_eq!(payload.downcast_ref::<usize>(), Some(&7));
+            assert_eq!(payload.downcast_ref::<String>(), None);
+
+            end_drag(cx);
+            assert_eq!(drag.get(), None);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_is_hovered_per_target(cx: &TestAppContext) {
+        cx.update(|cx| {
+            let inbox = cx.is_hovered("inbox");
+            let archive = cx.is_hovered("archive");
+            let (_, hovered) = drag_state(cx);
+
+            hovered.set(Some("inbox".into()));
+            assert!(!inbox.get(), "no drag in flight");
+
+            start_drag(cx, DragPayload::new("todo", ()));
+            hovered.set(Some("inbox".into()));
+            assert!(inbox.get());
+            assert!(!archive.get());
+
+            hovered.set(Some("archive".into()));
+            assert!(!inbox.get());
+            assert!(archive.get());
+
+            end_drag(cx);
+            assert!(!archive.get());
+            assert_eq!(cx.is_hovered("inbox"), inbox);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_entity_hover_memos_are_freed_on_release(cx: &TestAppContext) {
+        struct Row;
+
+        let (row, memo) = cx.update(|cx| {
+            let row = cx.new(|_| Row);
+            let memo = row.update(cx, |_, cx| {
+                let memo = cx.is_hovered("row-7");
+                assert_eq!(cx.is_hovered("row-7"), memo);
+                memo
+            });
+            (row, memo)
+        });
+        assert_eq!(memo.try_get(), Some(false));
+
+        drop(row);
+        cx.update(|_| {});
+        cx.update(|cx| assert!(cx.default_global::<DragState>().targets.is_empty()));
+        assert_eq!(memo.try_get(), None);
+    }
+}
@@ -0,0 +1,190 @@
+//! Working copies of signals, staged until committed.
+
+use crate::runtime::SignalRuntime;
+use crate::signal::{ReadOnlySignal, Signal};
+use crate::Memo;
+use std::fmt;
+
+/// An editable copy of a source signal's value, written back only on
+/// [`commit`](Self::commit).
+///
+/// Edit dialogs bind their fields to the draft, so shared state and every view
+/// showing it stay unchanged until the user saves, and cancelling is a
+/// [`discard`](Self::discard). [`is_dirty`](Self::is_dirty) compares the draft with
+/// the source, to enable a save button or warn before closing.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+///
+/// struct RenameDialog {
+///     name: DraftSignal<String>,
+/// }
+///
+/// impl RenameDialog {
+///     fn new(name: Signal<String>, cx: &mut Context<Self>) -> Self {
+///         Self {
+///             name: cx.create_draft(name),
+///         }
+///     }
+///
+///     fn save(&mut self, _cx: &mut Context<Self>) {
+///         self.name.commit();
+///     }
+///
+///     fn cancel(&mut self, _cx: &mut Context<Self>) {
+///         self.name.discard();
+///     }
+/// }
+/// ```
+pub struct DraftSignal<T> {
+    source: Signal<T>,
+    draft: Signal<T>,
+    dirty: Memo<bool>,
+}
+
+impl<T> Copy for DraftSignal<T> {}
+
+impl<T> Clone for DraftSignal<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for DraftSignal<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.draft == other.draft
+    }
+}
+
+impl<T> Eq for DraftSignal<T> {}
+
+impl<T: 'static + Clone + PartialEq> DraftSignal<T> {
+    /// Create a draft of `source`, starting at its current value.
+    pub fn new(source: Signal<T>) -> Self {
+        Self::new_in(source, &SignalRuntime::default())
+    }
+
+    /// Create a draft of `source` in `runtime`.
+    pub fn new_in(source: Signal<T>, runtime: &SignalRuntime) -> Self {
+        let draft = Signal::new_in(source.get_untracked(), runtime);
+        Self::from_parts(
+            source,
+            draft,
+            Memo::new_in(move || Self::differs(draft, source), runtime),
+        )
+    }
+
+    pub(crate) fn from_parts(source: Signal<T>, draft: Signal<T>, dirty: Memo<bool>) -> Self {
+        Self {
+            source,
+            draft,
+            dirty,
+        }
+    }
+
+    /// Whether `draft` differs from `source`, tracking both reads.
+    pub(crate) fn differs(draft: Signal<T>, source: Signal<T>) -> bool {
+        // Cloned first, since a signal can't be read while another one's value is
+        // borrowed.
+        let source = source.get();
+        draft.with(|draft| *draft != source)
+    }
+
+    /// The draft value.
+    pub fn value(&self) -> ReadOnlySignal<T> {
+        self.draft.read_only()
+    }
+
+    /// The signal the draft is committed to.
+    pub fn source(&self) -> Signal<T> {
+        self.source
+    }
+
+    /// A memo tracking whether the draft differs from the source.
+    pub fn is_dirty(&self) -> Memo<bool> {
+        self.dirty
+    }
+
+    /// Get the draft value, tracking the read.
+    pub fn get(&self) -> T {
+        self.draft.get()
+    }
+
+    /// Get the draft value without tracking the read.
+    pub fn get_untracked(&self) -> T {
+        self.draft.get_untracked()
+    }
+
+    /// Read the draft value with a closure, tracking the read.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.draft.with(f)
+    }
+
+    /// Set the draft value.
+    #[track_caller]
+    pub fn set(&self, value: T) {
+        self.draft.set(value);
+    }
+
+    /// Update the draft value with a closure.
+    #[track_caller]
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        self.draft.update(f);
+    }
+
+    /// Write the draft to the source, if it differs.
+    #[track_caller]
+    pub fn commit(&self) {
+        let draft = self.draft.get_untracked();
+        self.source.set_if_changed(draft);
+    }
+
+    /// Throw the draft away, copying the source's current value into it.
+    #[track_caller]
+    pub fn discard(&self) {
+        let source = self.source.get_untracked();
+        self.draft.set_if_changed(source);
+    }
+
+    /// Free the draft and its memo, leaving the source alive.
+    pub fn dispose(self) {
+        self.dirty.signal().dispose();
+        self.draft.dispose();
+    }
+}
+
+impl<T: 'static + Clone + PartialEq + fmt::Debug> fmt::Debug for DraftSignal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DraftSignal")
+            .field("draft", &self.draft.get_untracked())
+            .field("source", &self.source.get_untracked())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_and_discard() {
+        let name = Signal::new("notes".to_string());
+        let draft = DraftSignal::new(name);
+        assert!(!draft.is_dirty().get());
+
+        draft.set("todo".to_string());
+        assert!(draft.is_dirty().get());
+        assert_eq!(name.get(), "notes");
+        draft.discard();
+        assert_eq!(draft.get(), "notes");
+        assert!(!draft.is_dirty().get());
+
+        draft.update(|name| name.push_str(".md"));
+        draft.commit();
+        assert_eq!(name.get(), "notes.md");
+        assert!(!draft.is_dirty().get());
+    }
+}
@@ -0,0 +1,573 @@
+//! Element helpers that build children and cached subtrees from signals.
+
+use crate::{Memo, ReadOnlySignal, Signal, SignalContext};
+use gpui::{
+    AnyElement, AnyView, App, AppContext, Context, ElementId, Empty, Entity, IntoElement,
+    ParentElement, Render, RenderOnce, SharedString, StyleRefinement, Styled, Window,
+};
+use slotmap::Key;
+use std::any::Any;
+use std::fmt;
+use std::rc::Rc;
+
+type ItemRender<T> = Rc<dyn Fn(&T, &mut Window, &mut App) -> AnyElement>;
+type BuildElement = Rc<dyn Fn(&mut Window, &mut App) -> AnyElement>;
+
+impl<T: fmt::Display + 'static> IntoElement for Signal<T> {
+    type Element = SharedString;
+
+    fn into_element(self) -> Self::Element {
+        self.read_only().into_element()
+    }
+}
+
+impl<T: fmt::Display + 'static> IntoElement for ReadOnlySignal<T> {
+    type Element = SharedString;
+
+    fn into_element(self) -> Self::Element {
+        // A `SharedString` value is shared as is rather than formatted into new text.
+        let shared = self.with(|value| (value as &dyn Any).downcast_ref::<SharedString>().cloned());
+        shared.unwrap_or_else(|| SharedString::from(self.display_text()))
+    }
+}
+
+impl<T: fmt::Display + Clone + 'static> IntoElement for Memo<T> {
+    type Element = SharedString;
+
+    fn into_element(self) -> Self::Element {
+        self.signal().into_element()
+    }
+}
+
+impl<T: 'static> Signal<T> {
+    /// Render the signal's value as text formatted by `format`.
+    ///
+    /// The returned element reads the signal when it is rendered, like rendering the
+    /// signal itself, so formatting doesn't need a separate memo.
+    pub fn display_with<S: Into<SharedString>>(
+        self,
+        format: impl FnOnce(&T) -> S,
+    ) -> DisplayWith<T, impl FnOnce(&T) -> S> {
+        self.read_only().display_with(format)
+    }
+}
+
+impl<T: 'static> ReadOnlySignal<T> {
+    /// Render the signal's value as text formatted by `format`.
+    pub fn display_with<S: Into<SharedString>>(
+        self,
+        format: impl FnOnce(&T) -> S,
+    ) -> DisplayWith<T, impl FnOnce(&T) -> S> {
+        DisplayWith {
+            signal: self,
+            format,
+        }
+    }
+}
+
+impl<T: 'static + Clone> Memo<T> {
+    /// Render the computed value as text formatted by `format`.
+    pub fn display_with<S: Into<SharedString>>(
+        self,
+        format: impl FnOnce(&T) -> S,
+    ) -> DisplayWith<T, impl FnOnce(&T) -> S> {
+        self.signal().display_with(format)
+    }
+}
+
+/// Text rendered from a signal's value through a formatting function.
+///
+/// Created by `display_with` on [`Signal`], [`ReadOnlySignal`], and [`Memo`].
+pub struct DisplayWith<T, F> {
+    signal: ReadOnlySignal<T>,
+    format: F,
+}
+
+impl<T: 'static, S: Into<SharedString>, F: FnOnce(&T) -> S> IntoElement for DisplayWith<T, F> {
+    type Element = SharedString;
+
+    fn into_element(self) -> Self::Element {
+        let format = self.format;
+        self.signal.with(|value| format(value).into())
+    }
+}
+
+/// Child-building methods that take their items from a collection signal.
+///
+/// Like [`SignalStyled`](crate::SignalStyled), the collection is read while the element
+/// is being built, so the enclosing tracking scope re-renders when it changes.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+///
+/// fn tag_list(tags: SignalVec<String>) -> impl IntoElement {
+///     div().children_signal(tags, |tag| div().child(tag.clone()))
+/// }
+/// ```
+pub trait SignalParentElement: ParentElement + Sized {
+    /// Build one child per item, rebuilding every child when the collection changes.
+    fn children_signal<T, E>(
+        mut self,
+        items: impl Into<ReadOnlySignal<Vec<T>>>,
+        render: impl Fn(&T) -> E,
+    ) -> Self
+    where
+        T: Clone + 'static,
+        E: IntoElement,
+    {
+        let items = items.into().get();
+        self.extend(items.iter().map(|item| render(item).into_any_element()));
+        self
+    }
+
+    /// Build one child per item, identified by `key`.
+    ///
+    /// Each child is rendered as its own cached view. When the collection changes,
+    /// only children whose item differs from the previous render (or whose key is
+    /// new) are rebuilt; the rest reuse their previous layout and paint. Signals
+    /// read inside `render` re-render just that child.
+    ///
+    /// As with [`ReactiveRegion`](crate::ReactiveRegion), cached content is laid out
+    /// inside its root, so `item_style` needs to give each child a size, for example
+    /// `StyleRefinement::default().w_full().h_8()`.
+    fn keyed_children_signal<T, K, E>(
+        mut self,
+        items: impl Into<ReadOnlySignal<Vec<T>>>,
+        key: impl Fn(&T) -> K,
+        item_style: StyleRefinement,
+        render: impl Fn(&T, &mut Window, &mut App) -> E + 'static,
+    ) -> Self
+    where
+        T: Clone + PartialEq + 'static,
+        K: Into<ElementId>,
+        E: IntoElement,
+    {
+        let render: ItemRender<T> =
+            Rc::new(move |item, window, cx| render(item, window, cx).into_any_element());
+        let items = items.into().get();
+        self.extend(items.into_iter().map(|item| {
+            CachedElement {
+                id: key(&item).into(),
+                item,
+                style: item_style.clone(),
+                render: render.clone(),
+            }
+            .into_any_element()
+        }));
+        self
+    }
+}
+
+impl<E: ParentElement> SignalParentElement for E {}
+
+/// Build an element from the value of `key`, reusing the previous build while the
+/// value is unchanged.
+///
+/// The element is rendered as a cached view: until `key` holds a different value,
+/// `build` is not called again and the previous layout and paint are reused, which
+/// skips expensive element construction for static parts of complex views. As with
+/// [`ReactiveRegion`](crate::ReactiveRegion), style methods on the returned element
+/// apply to its root and need to give it a size.
+///
+/// Each call site keeps its own cache, so call this once per key signal in a render.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::cached_element;
+/// use gpui_signals::prelude::*;
+///
+/// fn chart(data: Memo<Vec<f32>>) -> impl IntoElement {
+///     cached_element(data, |points| {
+///         div().children(points.iter().map(|point| div().h(px(*point))))
+///     })
+///     .size_full()
+/// }
+/// ```
+#[track_caller]
+pub fn cached_element<T, E>(
+    key: impl Into<ReadOnlySignal<T>>,
+    build: impl Fn(&T) -> E + 'static,
+) -> CachedElement<T>
+where
+    T: Clone + PartialEq + 'static,
+    E: IntoElement,
+{
+    let key = key.into();
+    let location = std::panic::Location::caller().to_string();
+    CachedElement {
+        id: ElementId::NamedInteger(location.into(), key.id().data().as_ffi()),
+        item: key.get(),
+        style: StyleRefinement::default(),
+        render: Rc::new(move |item, _, _| build(item).into_any_element()),
+    }
+}
+
+/// An element built from a value and cached until the value changes.
+///
+/// Created by [`cached_element`].
+#[derive(IntoElement)]
+pub struct CachedElement<T: Clone + PartialEq + 'static> {
+    id: ElementId,
+    item: T,
+    style: StyleRefinement,
+    render: ItemRender<T>,
+}
+
+impl<T: Clone + PartialEq + 'static> Styled for CachedElement<T> {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> RenderOnce for CachedElement<T> {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let CachedElement {
+            id,
+            item,
+            style,
+            render,
+        } = self;
+        let view = window.with_global_id(id, |global_id, window| {
+            window.with_element_state(global_id, |state: Option<Entity<KeyedItemView<T>>>, _| {
+                // A changed item gets a fresh view: the view cache is keyed by entity, and
+                // notifying the old one mid-frame would only take effect next frame.
+                let view = match state {
+                    Some(view) if view.read(cx).item == item => {
+                        view.update(cx, |view, _| view.render = render);
+                        view
+                    }
+                    _ => cx.new(|_| KeyedItemView { item, render }),
+                };
+                (view.clone(), view)
+            })
+        });
+        AnyView::from(view).cached(style)
+    }
+}
+
+struct KeyedItemView<T> {
+    item: T,
+    render: ItemRender<T>,
+}
+
+impl<T: 'static> Render for KeyedItemView<T> {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let render = self.render.clone();
+        let item = &self.item;
+        cx.track_render(|cx| render(item, window, cx))
+    }
+}
+
+/// A boxed element builder that can be stored in a signal.
+///
+/// Factories compare equal only to clones of themselves, so setting a signal to a new
+/// factory always counts as a change.
+#[derive(Clone)]
+pub struct ElementFactory(BuildElement);
+
+impl ElementFactory {
+    /// Wrap `build` as a factory.
+    pub fn new<E: IntoElement>(build: impl Fn(&mut Window, &mut App) -> E + 'static) -> Self {
+        Self(Rc::new(move |window, cx| {
+            build(window, cx).into_any_element()
+        }))
+    }
+
+    /// Build a fresh element.
+    pub fn build(&self, window: &mut Window, cx: &mut App) -> AnyElement {
+        (self.0)(window, cx)
+    }
+}
+
+impl Default for ElementFactory {
+    /// A factory that renders nothing.
+    fn default() -> Self {
+        Self::new(|_, _| Empty)
+    }
+}
+
+impl PartialEq for ElementFactory {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for ElementFactory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ElementFactory").finish_non_exhaustive()
+    }
+}
+
+/// A slot whose content comes from an [`ElementFactory`] held in a signal.
+///
+/// The signal is read when the slot is created, so swapping in a different factory
+/// re-renders the enclosing scope and the slot builds its content from the new one.
+/// This suits plugin-provided or runtime-swapped regions of a view.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::{DynamicSlot, ElementFactory};
+///
+/// struct Shell {
+///     sidebar: Signal<ElementFactory>,
+/// }
+///
+/// impl Shell {
+///     fn show_outline(&mut self) {
+///         self.sidebar
+///             .set(ElementFactory::new(|_, _| div().child("Outline")));
+///     }
+/// }
+///
+/// impl Render for Shell {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         div().flex().child(DynamicSlot::new(self.sidebar))
+///     }
+/// }
+/// ```
+#[derive(IntoElement)]
+pub struct DynamicSlot {
+    factory: ElementFactory,
+}
+
+impl DynamicSlot {
+    /// Create a slot rendering the factory currently held by `factory`.
+    pub fn new(factory: impl Into<ReadOnlySignal<ElementFactory>>) -> Self {
+        Self {
+            factory: factory.into().get(),
+        }
+    }
+}
+
+impl RenderOnce for DynamicSlot {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        self.factory.build(window, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SignalVec;
+    use gpui::{div, TestAppContext};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_display_with_formats_current_value() {
+        let signal = Signal::new(3);
+        let text = signal
+            .display_with(|count| format!("{count} items"))
+            .into_element();
+        assert_eq!(text, "3 items");
+
+        signal.set(4);
+        assert_eq!(signal.read_only().into_element(), "4");
+    }
+
+    #[test]
+    fn test_children_signal_builds_each_item() {
+        let items = SignalVec::new(vec![1, 2, 3]);
+        let rendered = RefCell::new(Vec::new());
+        let _element = div().children_signal(items, |item| {
+            rendered.borrow_mut().push(*item);
+            div()
+        });
+        assert_eq!(*rendered.borrow(), vec![1, 2, 3]);
+    }
+
+    #[derive(Clone, PartialEq)]
+    struct Row {
+        id: usize,
+        label: &'static str,
+    }
+
+    struct List {
+        rows: SignalVec<Row>,
+        renders: Rc<RefCell<HashMap<usize, usize>>>,
+    }
+
+    impl Render for List {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            let renders = self.renders.clone();
+            div().keyed_children_signal(
+                self.rows,
+                |row| row.id,
+                StyleRefinement::default().w_full().h_8(),
+                move |row, _, _| {
+                    *renders.borrow_mut().entry(row.id).or_default() += 1;
+                    div().child(row.label)
+                },
+            )
+        }
+    }
+
+    #[gpui::test]
+    async fn test_keyed_children_rebuild_only_changed_items(cx: &mut TestAppContext) {
+        let renders = Rc::new(RefCell::new(HashMap::new()));
+        let rows: Rc<RefCell<Option<SignalVec<Row>>>> = Rc::new(RefCell::new(None));
+
+        let (_list, cx) = cx.add_window_view({
+            let renders = renders.clone();
+            let rows = rows.clone();
+            move |_, cx| {
+                let list = cx
+                    .create_signal_vec(vec![Row { id: 0, label: "a" }, Row { id: 1, label: "b" }]);
+                *rows.borrow_mut() = Some(list);
+                List {
+                    rows: list,
+                    renders,
+                }
+            }
+        });
+        let rows = rows.borrow().expect("rows signal missing");
+        assert_eq!(renders.borrow()[&0], 1);
+        assert_eq!(renders.borrow()[&1], 1);
+
+        rows.update(|rows| rows[1].label = "B");
+        cx.run_until_parked();
+        assert_eq!(renders.borrow()[&0], 1, "unchanged row reuses its output");
+        assert_eq!(renders.borrow()[&1], 2);
+
+        rows.push(Row { id: 2, label: "c" });
+        cx.run_until_parked();
+        assert_eq!(renders.borrow()[&0], 1);
+        assert_eq!(renders.borrow()[&1], 2);
+        assert_eq!(renders.borrow()[&2], 1);
+    }
+
+    #[gpui::test]
+    async fn test_keyed_child_tracks_its_own_signals(cx: &mut TestAppContext) {
+        let counter = Signal::new(0);
+        let renders = Rc::new(RefCell::new(0));
+
+        struct Single {
+            rows: SignalVec<usize>,
+            counter: Signal<i32>,
+            renders: Rc<RefCell<usize>>,
+        }
+
+        impl Render for Single {
+            fn render(
+                &mut self,
+                _window: &mut Window,
+                _cx: &mut Context<Self>,
+            ) -> impl IntoElement {
+                let (counter, renders) = (self.counter, self.renders.clone());
+                div().keyed_children_signal(
+                    self.rows,
+                    |id| *id,
+                    StyleRefinement::default().w_full().h_8(),
+                    move |_, _, _| {
+                        *renders.borrow_mut() += 1;
+                        div().child(counter.get().to_string())
+                    },
+                )
+            }
+        }
+
+        let (_single, cx) = cx.add_window_view({
+            let renders = renders.clone();
+            move |_, _| Single {
+                rows: SignalVec::new(vec![0]),
+                counter,
+                renders,
+            }
+        });
+        assert_eq!(*renders.borrow(), 1);
+
+        counter.set(1);
+        cx.run_until_parked();
+        assert_eq!(*renders.borrow(), 2);
+    }
+
+    struct Dashboard {
+        data: Signal<Vec<u32>>,
+        title: Signal<&'static str>,
+        builds: Rc<RefCell<usize>>,
+    }
+
+    impl Render for Dashboard {
+        fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            let builds = self.builds.clone();
+            let (data, title) = (self.data, self.title);
+            cx.track_render(|_| {
+                div().child(title.get()).child(
+                    cached_element(data, move |data| {
+                        *builds.borrow_mut() += 1;
+                        div().children(data.iter().map(|value| value.to_string()))
+                    })
+                    .w_full()
+                    .h_8(),
+                )
+            })
+        }
+    }
+
+    #[gpui::test]
+    async fn test_cached_element_rebuilds_on_key_change(cx: &mut TestAppContext) {
+        let builds = Rc::new(RefCell::new(0));
+        let data = Signal::new(vec![1, 2]);
+        let title = Signal::new("a");
+        let (_dashboard, cx) = cx.add_window_view(|_, _| Dashboard {
+            data,
+            title,
+            builds: builds.clone(),
+        });
+        assert_eq!(*builds.borrow(), 1);
+
+        title.set("b");
+        cx.run_until_parked();
+        assert_eq!(*builds.borrow(), 1, "unchanged key reuses the cached build");
+
+        data.update(|data| data.push(3));
+        cx.run_until_parked();
+        assert_eq!(*builds.borrow(), 2);
+    }
+
+    struct Shell {
+        slot: Signal<ElementFactory>,
+    }
+
+    impl Render for Shell {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div().child(DynamicSlot::new(self.slot))
+        }
+    }
+
+    #[gpui::test]
+    async fn test_dynamic_slot_swaps_factory(cx: &mut TestAppContext) {
+        let built = Rc::new(RefCell::new(Vec::new()));
+        let factory = |name: &'static str| {
+            let built = built.clone();
+            ElementFactory::new(move |_, _| {
+                built.borrow_mut().push(name);
+                div()
+            })
+        };
+        let slot: Rc<RefCell<Option<Signal<ElementFactory>>>> = Rc::default();
+        let first = factory("first");
+        let (_shell, cx) = cx.add_window_view({
+            let slot_out = slot.clone();
+            move |_, cx| {
+                let slot = cx.create_signal(first);
+                *slot_out.borrow_mut() = Some(slot);
+                Shell { slot }
+            }
+        });
+        assert_eq!(*built.borrow(), vec!["first"]);
+
+        let slot = slot.borrow().expect("slot signal missing");
+        slot.set(factory("second"));
+        cx.run_until_parked();
+        assert_eq!(*built.borrow(), vec!["first", "second"]);
+    }
+}
@@ -0,0 +1,105 @@
+//! Reporting panics raised inside effects and memos.
+
+use crate::storage::SignalId;
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+type ErrorHook = Rc<dyn Fn(&SignalError)>;
+
+thread_local! {
+    static ERROR_HOOK: RefCell<Option<ErrorHook>> = const { RefCell::new(None) };
+}
+
+/// The kind of computation that panicked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Computation {
+    /// An [`Effect`](crate::Effect).
+    Effect,
+    /// A [`Memo`](crate::Memo).
+    Memo,
+}
+
+/// A panic caught inside an effect or memo, passed to the hook set with
+/// [`on_signal_error`].
+#[derive(Clone, Debug)]
+pub struct SignalError {
+    /// Whether an effect or a memo panicked.
+    pub computation: Computation,
+    /// The name given with `Effect::named` or `Memo::named`, if any.
+    pub name: Option<Rc<str>>,
+    /// The signal the computation's reads are tracked under.
+    pub id: SignalId,
+    /// The panic message.
+    pub message: String,
+}
+
+impl fmt::Display for SignalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let computation = match self.computation {
+            Computation::Effect => "effect",
+            Computation::Memo => "memo",
+        };
+        match &self.name {
+            Some(name) => write!(f, "{computation} `{name}` panicked: {}", self.message),
+            None => write!(f, "{computation} {:?} panicked: {}", self.id, self.message),
+        }
+    }
+}
+
+impl std::error::Error for SignalError {}
+
+/// Catch panics in effects and memos on this thread and pass them to `hook`.
+///
+/// Without a hook a panic propagates to whatever wrote the signal or read the memo.
+/// Either way the tracking state is restored first, so the rest of the graph keeps
+/// working. With a hook, an effect that panicked re-runs when a signal it read before
+/// panicking changes, and a memo keeps its last value until then. A memo that has
+/// never produced a value has nothing to return, so its panic is reported and then
+/// propagates.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui_signals::prelude::*;
+///
+/// gpui_signals::on_signal_error(|error| eprintln!("{error}"));
+///
+/// let items = Signal::new(vec![1, 2, 3]);
+/// let _first = Effect::named("print first", move || {
+///     println!("{}", items.with(|items| items[0]));
+/// });
+/// // Reported as "effect `print first` panicked: ..." instead of unwinding here.
+/// items.set(Vec::new());
+/// ```
+pub fn on_signal_error(hook: impl Fn(&SignalError) + 'static) {
+    let previous = ERROR_HOOK.with(|slot| slot.borrow_mut().replace(Rc::new(hook)));
+    drop(previous);
+}
+
+/// Pass a panic caught in a computation to the hook, returning whether there was one.
+pub(crate) fn report_panic(
+    computation: Computation,
+    name: Option<Rc<str>>,
+    id: SignalId,
+    payload: &(dyn Any + Send),
+) -> bool {
+    let Some(hook) = ERROR_HOOK.with(|slot| slot.borrow().clone()) else {
+        return false;
+    };
+    let message = if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panic with a non-string payload".to_string()
+    };
+    hook(&SignalError {
+        computation,
+        name,
+        id,
+        message,
+    });
+    true
+}
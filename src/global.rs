@@ -1,6 +1,8 @@
-use crate::context::auto_notify;
-use crate::Signal;
-use gpui::{App, Context, Global};
+use crate::context::{auto_notify, dispose_on_release, track_subscription};
+use crate::{ReadOnlySignal, Signal};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use gpui::{App, AsyncApp, Context, Global, Subscription};
 
 struct GlobalSignalContainer<T: 'static> {
     signal: Signal<T>,
@@ -22,6 +24,108 @@ pub trait GlobalSignalContext {
     ///
     /// This must be called from a `Context<V>` to establish the subscription.
     fn use_global<T: 'static>(&mut self) -> Signal<T>;
+
+    /// Access part of a global signal, selected by `select`, and subscribe to changes
+    /// of that part only.
+    ///
+    /// The selection is recomputed on every change to the global but only notifies
+    /// the view when it differs from the previous one, so a view showing the
+    /// theme's accent color doesn't re-render when an unrelated field changes.
+    /// Called on `App`, nothing is subscribed and the selection lives as long as the
+    /// global signal.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    ///
+    /// struct Theme {
+    ///     accent: Rgba,
+    ///     font_size: f32,
+    /// }
+    ///
+    /// struct Badge {
+    ///     accent: ReadOnlySignal<Rgba>,
+    /// }
+    ///
+    /// impl Badge {
+    ///     fn new(cx: &mut Context<Self>) -> Self {
+    ///         Self {
+    ///             accent: cx.use_global_select::<Theme, _>(|theme| theme.accent),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    fn use_global_select<T: 'static, U: 'static + PartialEq>(
+        &mut self,
+        select: impl Fn(&T) -> U + 'static,
+    ) -> ReadOnlySignal<U>;
+
+    /// Call `callback` with the global signal's value after each change, until the
+    /// returned subscription is dropped.
+    ///
+    /// This lets app-level code such as controllers react to a global without an
+    /// entity to host an effect. Calls are made on the foreground after the write,
+    /// with the app context, and several writes before one is made are seen once.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    ///
+    /// #[derive(Clone)]
+    /// struct Locale(String);
+    ///
+    /// fn init(cx: &mut App) -> Subscription {
+    ///     cx.init_global(Locale("en".to_string()));
+    ///     cx.observe_global_signal::<Locale>(|locale, _cx| {
+    ///         println!("switching translations to {}", locale.0);
+    ///     })
+    /// }
+    /// ```
+    fn observe_global_signal<T: 'static + Clone>(
+        &mut self,
+        callback: impl FnMut(&T, &mut App) + 'static,
+    ) -> Subscription;
+}
+
+/// Call `callback` with `signal`'s value after its changes, until the returned
+/// subscription is dropped.
+fn observe<T: 'static + Clone>(
+    signal: Signal<T>,
+    mut callback: impl FnMut(&T, &mut App) + 'static,
+    cx: &mut App,
+) -> Subscription {
+    let (wake, mut woken) = mpsc::unbounded::<()>();
+    signal.subscribe_while(move || wake.unbounded_send(()).is_ok());
+    let task = cx.spawn(async move |cx: &mut AsyncApp| {
+        while woken.next().await.is_some() {
+            while woken.try_recv().is_ok() {}
+            // Cloned first, so the callback can read and write signals, this one too.
+            let Some(value) = signal.try_get() else {
+                break;
+            };
+            if cx.update(|cx| callback(&value, cx)).is_err() {
+                break;
+            }
+        }
+    });
+    Subscription::new(move || drop(task))
+}
+
+/// A signal holding `select` applied to `global`, written only when the selection
+/// changes.
+fn select_global<T: 'static, U: 'static + PartialEq>(
+    global: Signal<T>,
+    select: impl Fn(&T) -> U + 'static,
+) -> Signal<U> {
+    let selected = Signal::new(global.with_untracked(&select));
+    global.subscribe_while(move || {
+        if !selected.is_alive() {
+            return false;
+        }
+        selected.set_if_changed(global.with_untracked(&select));
+        true
+    });
+    selected
 }
 
 impl GlobalSignalContext for App {
@@ -38,6 +142,20 @@ impl GlobalSignalContext for App {
     fn use_global<T: 'static>(&mut self) -> Signal<T> {
         self.global_signal::<T>()
     }
+
+    fn use_global_select<T: 'static, U: 'static + PartialEq>(
+        &mut self,
+        select: impl Fn(&T) -> U + 'static,
+    ) -> ReadOnlySignal<U> {
+        select_global(self.global_signal::<T>(), select).read_only()
+    }
+
+    fn observe_global_signal<T: 'static + Clone>(
+        &mut self,
+        callback: impl FnMut(&T, &mut App) + 'static,
+    ) -> Subscription {
+        observe(self.global_signal::<T>(), callback, self)
+    }
 }
 
 impl<V: 'static> GlobalSignalContext for Context<'_, V> {
@@ -55,8 +173,100 @@ impl<V: 'static> GlobalSignalContext for Context<'_, V> {
         let signal = self.global_signal::<T>();
         if crate::context::subscribe_once(self, &signal) {
             let sub = auto_notify(&signal, self);
-            crate::context::track_subscription(self, sub);
+            track_subscription(self, sub);
         }
         signal
     }
+
+    fn use_global_select<T: 'static, U: 'static + PartialEq>(
+        &mut self,
+        select: impl Fn(&T) -> U + 'static,
+    ) -> ReadOnlySignal<U> {
+        let selected = select_global(self.global_signal::<T>(), select);
+        let subscription = auto_notify(&selected, self);
+        track_subscription(self, subscription);
+        dispose_on_release(self, move || selected.dispose());
+        selected.read_only()
+    }
+
+    fn observe_global_signal<T: 'static + Clone>(
+        &mut self,
+        callback: impl FnMut(&T, &mut App) + 'static,
+    ) -> Subscription {
+        observe(self.global_signal::<T>(), callback, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext, TestAppContext};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct Settings {
+        font_size: f32,
+        vim_mode: bool,
+    }
+
+    struct Editor {
+        font_size: ReadOnlySignal<f32>,
+    }
+
+    #[gpui::test]
+    async fn test_global_select_ignores_other_fields(cx: &mut TestAppContext) {
+        let settings = cx.update(|cx| {
+            cx.init_global(Settings {
+                font_size: 14.0,
+                vim_mode: false,
+            })
+        });
+        let editor = cx.update(|cx| {
+            cx.new(|cx| Editor {
+                font_size: cx.use_global_select::<Settings, _>(|settings| settings.font_size),
+            })
+        });
+        let font_size = editor.read_with(cx, |editor, _| editor.font_size);
+        let version = font_size.version();
+
+        settings.update(|settings| settings.vim_mode = true);
+        assert_eq!(font_size.version(), version);
+
+        settings.update(|settings| settings.font_size = 16.0);
+        assert_eq!(font_size.get(), 16.0);
+        assert_eq!(font_size.version(), version + 1);
+
+        drop(editor);
+        cx.update(|_| {});
+        settings.update(|settings| settings.font_size = 12.0);
+        assert_eq!(font_size.try_get(), None);
+    }
+
+    #[derive(Clone)]
+    struct Locale(&'static str);
+
+    #[gpui::test]
+    async fn test_observe_global_signal_until_dropped(cx: &mut TestAppContext) {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let (locale, subscription) = cx.update(|cx| {
+            let locale = cx.init_global(Locale("en"));
+            let seen = seen.clone();
+            let subscription = cx.observe_global_signal::<Locale>(move |locale, _| {
+                seen.borrow_mut().push(locale.0);
+            });
+            (locale, subscription)
+        });
+
+        locale.set(Locale("de"));
+        locale.set(Locale("fr"));
+        cx.run_until_parked();
+        locale.set(Locale("ja"));
+        cx.run_until_parked();
+        assert_eq!(*seen.borrow(), ["fr", "ja"]);
+
+        drop(subscription);
+        locale.set(Locale("en"));
+        cx.run_until_parked();
+        assert_eq!(*seen.borrow(), ["fr", "ja"]);
+    }
 }
@@ -0,0 +1,174 @@
+//! Carrying signal values across a hot reload.
+//!
+//! Signals registered under a name are written out by [`serialize_state`] before the
+//! reload and read back by [`hydrate_state`] after it, so the user keeps their place
+//! instead of starting over. Values are converted with `Display` and `FromStr`.
+//!
+//! Views are usually recreated after hydration, so values for names not registered
+//! yet are held and applied when a signal registers under that name.
+//!
+//! ```rust
+//! use gpui_signals::{hot_reload, Signal};
+//!
+//! let tab = Signal::new(0usize);
+//! hot_reload::register("sidebar.tab", tab);
+//! tab.set(2);
+//! let state = hot_reload::serialize_state();
+//! tab.dispose();
+//!
+//! // After the reload, the recreated signal picks up where the old one left off.
+//! hot_reload::hydrate_state(&state);
+//! let tab = Signal::new(0usize);
+//! hot_reload::register("sidebar.tab", tab);
+//! assert_eq!(tab.get(), 2);
+//! ```
+
+use crate::Signal;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::rc::Rc;
+use std::str::FromStr;
+
+struct Entry {
+    alive: Box<dyn Fn() -> bool>,
+    save: Box<dyn Fn() -> String>,
+    load: Rc<dyn Fn(&str)>,
+}
+
+thread_local! {
+    static REGISTERED: RefCell<BTreeMap<String, Entry>> = const { RefCell::new(BTreeMap::new()) };
+    static PENDING: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+/// Carry `signal` across reloads under `name`.
+///
+/// If hydrated state holds a value for `name`, the signal is set to it now.
+/// Registering another signal under the same name replaces the first.
+pub fn register<T>(name: impl Into<String>, signal: Signal<T>)
+where
+    T: Display + FromStr + 'static,
+{
+    let name = name.into();
+    let load: Rc<dyn Fn(&str)> = Rc::new(move |text| {
+        // A value the new code can no longer parse is dropped rather than failing the
+        // reload.
+        if let Ok(value) = text.parse() {
+            signal.set(value);
+        }
+    });
+    let entry = Entry {
+        alive: Box::new(move || signal.is_alive()),
+        save: Box::new(move || signal.with_untracked(ToString::to_string)),
+        load: load.clone(),
+    };
+    REGISTERED.with(|registered| registered.borrow_mut().insert(name.clone(), entry));
+    if let Some(text) = PENDING.with(|pending| pending.borrow_mut().remove(&name)) {
+        load(&text);
+    }
+}
+
+/// Write the values of every registered signal that is still alive.
+pub fn serialize_state() -> String {
+    REGISTERED.with(|registered| {
+        let mut registered = registered.borrow_mut();
+        registered.retain(|_, entry| (entry.alive)());
+        let mut state = String::new();
+        for (name, entry) in registered.iter() {
+            state.push_str(&escape(name));
+            state.push('\t');
+            state.push_str(&escape(&(entry.save)()));
+            state.push('\n');
+        }
+        state
+    })
+}
+
+/// Restore values written by [`serialize_state`], now for registered signals that
+/// are alive and on registration for the rest.
+///
+/// Lines that aren't in the expected format are skipped.
+pub fn hydrate_state(state: &str) {
+    let mut loads = Vec::new();
+    for line in state.lines() {
+        let Some((name, value)) = line.split_once('\t') else {
+            continue;
+        };
+        let (name, value) = (unescape(name), unescape(value));
+        let load = REGISTERED.with(|registered| {
+            registered
+                .borrow()
+                .get(&name)
+                .filter(|entry| (entry.alive)())
+                .map(|entry| entry.load.clone())
+        });
+        match load {
+            Some(load) => loads.push((load, value)),
+            None => PENDING.with(|pending| {
+                pending.borrow_mut().insert(name, value);
+            }),
+        }
+    }
+    // Set outside the registry borrow, since subscribers may register signals.
+    for (load, value) in loads {
+        load(&value);
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut characters = text.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            unescaped.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_survives_a_reload() {
+        let draft = Signal::new(String::from("line one\n\tindented \\ done"));
+        let zoom = Signal::new(1.5f32);
+        register("editor.draft", draft);
+        register("editor.zoom", zoom);
+        let state = serialize_state();
+        draft.dispose();
+        zoom.dispose();
+
+        let zoom = Signal::new(1.0f32);
+        register("editor.zoom", zoom);
+        hydrate_state(&state);
+        assert_eq!(zoom.get(), 1.5);
+
+        let draft = Signal::new(String::new());
+        register("editor.draft", draft);
+        assert_eq!(draft.get(), "line one\n\tindented \\ done");
+    }
+}
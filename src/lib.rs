@@ -5,12 +5,35 @@
 //! ## Features
 //!
 //! - **Copy-able handles**: All signal types implement `Copy` for ergonomic use
-//! - **Automatic tracking**: Views automatically subscribe to signals they read
+//! - **Automatic tracking**: Views automatically subscribe to signals they read inside
+//!   `cx.track_render`
 //! - **Computed signals**: Derive reactive state from other signals with `Memo`
 //! - **Memory safe**: Generational arena prevents use-after-free without unsafe code
 //!
+//! ## Cargo features
+//!
+//! - `gpui` (off by default): the GPUI integration — context extensions, globals,
+//!   element impls, and the element helpers. Without it the crate is a headless
+//!   reactive core of [`Signal`], [`Memo`], [`SignalVec`], and [`Effect`], which can be
+//!   unit-tested and reused outside a GPUI app.
+//! - `components` (implies `gpui`): prebuilt signal-bound controls.
+//! - `tokio` (implies `gpui`): `Signal::from_watch`, `Signal::from_broadcast`, and
+//!   `Signal::to_watch` adapters for tokio channels.
+//! - `test-support` (implies `gpui`): `testing::Replay`, which replays a
+//!   [`FlightRecorder`] log against a `TestAppContext`.
+//! - `provenance`: record where each signal was last written in release builds too,
+//!   as debug builds always do. See [`Signal::last_writer`].
+//! - `metrics`: publish counters and gauges about the reactive graph through the
+//!   `metrics` crate facade. See [`telemetry`].
+//! - `serde`: a registry converting type-erased signal values to bytes and back for
+//!   snapshots, persistence, and IPC. See [`serialization`].
+//! - `fuzzy` (implies `gpui`): `search::Matcher::fuzzy`, which ranks items by how
+//!   well the query's characters match in order.
+//!
 //! ## Example
 //!
+//! With the `gpui` feature enabled:
+//!
 //! ```rust,no_run
 //! use gpui::Context;
 //! use gpui_signals::prelude::*;
@@ -48,19 +71,150 @@
 //! }
 //! ```
 
+#[cfg(feature = "tokio")]
+mod channel;
 mod computed;
+#[cfg(feature = "gpui")]
 mod context;
+mod draft;
+#[cfg(feature = "gpui")]
+mod elements;
+mod error;
+#[cfg(feature = "gpui")]
 mod global;
+mod link;
+mod list_navigation;
+mod logger;
+mod macros;
+mod nav_stack;
+#[cfg(feature = "gpui")]
+mod operators;
+#[cfg(feature = "gpui")]
+mod progress;
+mod recorder;
+#[cfg(feature = "gpui")]
+mod region;
+mod resettable;
+mod restricted;
+#[cfg(feature = "gpui")]
+mod resource;
+mod runtime;
+mod scheduler;
 mod signal;
+mod signal_channel;
+mod signal_vec;
 mod storage;
+mod stream;
+#[cfg(feature = "gpui")]
+mod style;
+#[cfg(feature = "gpui")]
+mod sync_signal;
+#[cfg(feature = "gpui")]
+mod task_tracker;
+#[cfg(feature = "gpui")]
+mod text;
+#[cfg(feature = "gpui")]
+mod transition;
+mod validated;
+#[cfg(feature = "gpui")]
+mod window;
+mod write_signal;
 
+#[cfg(feature = "gpui")]
+pub mod commands;
+#[cfg(feature = "components")]
+pub mod components;
+#[cfg(feature = "gpui")]
+pub mod devtools;
+#[cfg(feature = "gpui")]
+pub mod dnd;
+pub mod hot_reload;
+#[cfg(feature = "gpui")]
+pub mod measure;
+#[cfg(feature = "gpui")]
+pub mod menu;
+#[cfg(feature = "gpui")]
+pub mod overlay;
+#[cfg(feature = "gpui")]
+pub mod queries;
+#[cfg(feature = "gpui")]
+pub mod scroll;
+#[cfg(feature = "gpui")]
+pub mod search;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod services;
+#[cfg(feature = "metrics")]
+pub mod telemetry;
+pub mod testing;
+#[cfg(feature = "gpui")]
+pub mod theme;
+#[cfg(feature = "gpui")]
+pub mod time;
 
-pub use computed::Memo;
+pub use computed::{Effect, EffectPriority, Memo, MemoSource, MemoSources};
+pub use draft::DraftSignal;
+pub use error::{on_signal_error, Computation, SignalError};
+pub use link::{link, Link};
+pub use list_navigation::ListNavigation;
+pub use logger::LoggerMiddleware;
+pub use macros::GlobalSignal;
+pub use nav_stack::NavStack;
+pub use recorder::FlightRecorder;
+pub use resettable::ResettableSignal;
+pub use restricted::{AccessError, Permissions, RestrictedSignal};
+pub use runtime::{
+    NotificationWave, SignalRuntime, StaleHandle, StaleHandlePolicy, UpdateDepthExceeded,
+    WaveEntry, WaveEvent,
+};
+#[cfg(feature = "gpui")]
+pub use scheduler::FrameScheduler;
+pub use scheduler::{BatchScheduler, ImmediateScheduler, Notifications, Scheduler};
+pub use signal::{ChangeOrigin, ReadOnlySignal, Signal};
+pub use signal_channel::{signal_channel, signal_inbox, Inbox, InboxSender, SignalSender};
+pub use signal_vec::SignalVec;
+pub use stream::SignalStream;
+pub use validated::{CrossFieldError, CrossFieldValidator, ValidatedSignal};
+pub use write_signal::WriteSignal;
+
+#[cfg(feature = "gpui")]
 pub use context::SignalContext;
+#[cfg(feature = "gpui")]
+pub use elements::{
+    cached_element, CachedElement, DisplayWith, DynamicSlot, ElementFactory, SignalParentElement,
+};
+#[cfg(feature = "gpui")]
 pub use global::GlobalSignalContext;
-pub use signal::{ReadOnlySignal, Signal};
+#[cfg(feature = "gpui")]
+pub use progress::{Progress, ProgressReporter, TaskState};
+#[cfg(feature = "gpui")]
+pub use region::ReactiveRegion;
+#[cfg(feature = "gpui")]
+pub use resource::{ErrorBoundary, ErrorReset, Resource, ResourceState, Suspense};
+#[cfg(feature = "gpui")]
+pub use style::SignalStyled;
+#[cfg(feature = "gpui")]
+pub use sync_signal::SyncSignal;
+#[cfg(feature = "gpui")]
+pub use task_tracker::TaskTracker;
+#[cfg(feature = "gpui")]
+pub use text::TextSignal;
+#[cfg(feature = "gpui")]
+pub use transition::{Transition, TransitionPhase};
+#[cfg(feature = "gpui")]
+pub use window::{Breakpoints, WindowSignalExt};
 
 // Re-export the prelude
 pub mod prelude {
-    pub use crate::{GlobalSignalContext, Memo, ReadOnlySignal, Signal, SignalContext};
+    pub use crate::{
+        DraftSignal, Effect, EffectPriority, ListNavigation, Memo, NavStack, ReadOnlySignal,
+        ResettableSignal, Signal, SignalRuntime, SignalVec, ValidatedSignal, WriteSignal,
+    };
+
+    #[cfg(feature = "gpui")]
+    pub use crate::{
+        ErrorBoundary, GlobalSignalContext, Progress, ReactiveRegion, Resource, ResourceState,
+        SignalContext, SignalParentElement, SignalStyled, Suspense, SyncSignal, TaskState,
+        TaskTracker, TextSignal, Transition, TransitionPhase, WindowSignalExt,
+    };
 }
@@ -0,0 +1,140 @@
+//! Two-way links between signals of different types.
+
+use crate::Signal;
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// A link created by [`link`], kept until [`unlink`](Self::unlink) is called or either
+/// signal is disposed.
+#[derive(Clone, Debug)]
+pub struct Link {
+    active: Rc<Cell<bool>>,
+}
+
+impl Link {
+    /// Stop syncing the two signals.
+    pub fn unlink(&self) {
+        self.active.set(false);
+    }
+
+    /// Whether the signals are still synced.
+    pub fn is_linked(&self) -> bool {
+        self.active.get()
+    }
+}
+
+/// Keep `a` and `b` in sync through conversion functions.
+///
+/// `b` is set from `a` right away; after that a write to either side converts the
+/// new value and writes the other. A conversion returning `None`, such as text that
+/// doesn't parse, leaves the other side unchanged. The write made by the link is
+/// not converted back, so the side just written keeps exactly what it was given:
+/// typing "1.50" doesn't get rewritten to "1.5" under the cursor.
+///
+/// # Examples
+///
+/// ```rust
+/// use gpui_signals::{link, Signal};
+///
+/// let volume = Signal::new(0.5f32);
+/// let text = Signal::new(String::new());
+/// link(
+///     volume,
+///     text,
+///     |volume| Some(format!("{volume:.2}")),
+///     |text| text.parse().ok(),
+/// );
+/// assert_eq!(text.get(), "0.50");
+///
+/// text.set("0.8".to_string());
+/// assert_eq!(volume.get(), 0.8);
+/// text.set("loud".to_string());
+/// assert_eq!(volume.get(), 0.8);
+/// ```
+pub fn link<A: 'static, B: 'static>(
+    a: Signal<A>,
+    b: Signal<B>,
+    to_b: impl Fn(&A) -> Option<B> + 'static,
+    to_a: impl Fn(&B) -> Option<A> + 'static,
+) -> Link {
+    let active = Rc::new(Cell::new(true));
+    // The version each side will have after the link writes it, recorded before the
+    // write so its notification is recognized as an echo whenever it is delivered.
+    let echo_a = Rc::new(Cell::new(None));
+    let echo_b = Rc::new(Cell::new(None));
+
+    let write_b = {
+        let echo_b = echo_b.clone();
+        move || {
+            if let Some(value) = a.with_untracked(&to_b) {
+                echo_b.set(Some(b.version() + 1));
+                b.set(value);
+            }
+        }
+    };
+    write_b();
+
+    a.subscribe_while({
+        let active = active.clone();
+        let echo_a = echo_a.clone();
+        move || {
+            if !active.get() {
+                return false;
+            }
+            if echo_a.take() != Some(a.version()) {
+                write_b();
+            }
+            true
+        }
+    });
+    b.subscribe_while({
+        let active = active.clone();
+        move || {
+            if !active.get() {
+                return false;
+            }
+            if echo_b.take() != Some(b.version()) {
+                if let Some(value) = b.with_untracked(&to_a) {
+                    echo_a.set(Some(a.version() + 1));
+                    a.set(value);
+                }
+            }
+            true
+        }
+    });
+    Link { active }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_syncs_both_ways_without_echo() {
+        let celsius = Signal::new(100.0f64);
+        let fahrenheit = Signal::new(0.0f64);
+        let writes = Rc::new(Cell::new(0));
+        celsius.subscribe({
+            let writes = writes.clone();
+            move || writes.set(writes.get() + 1)
+        });
+        let link = link(
+            celsius,
+            fahrenheit,
+            |celsius| Some(celsius * 9. / 5. + 32.),
+            |fahrenheit| Some((fahrenheit - 32.) * 5. / 9.),
+        );
+        assert_eq!(fahrenheit.get(), 212.);
+
+        fahrenheit.set(32.);
+        assert_eq!(celsius.get(), 0.);
+        assert_eq!(writes.get(), 1);
+        celsius.set(10.);
+        assert_eq!(fahrenheit.get(), 50.);
+        assert_eq!(writes.get(), 2);
+
+        link.unlink();
+        celsius.set(20.);
+        assert_eq!(fahrenheit.get(), 50.);
+    }
+}
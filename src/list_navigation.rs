@@ -0,0 +1,286 @@
+//! Keyboard navigation over a list of items.
+
+use crate::runtime::SignalRuntime;
+use crate::signal::{ReadOnlySignal, Signal};
+use crate::Memo;
+use std::fmt;
+
+/// The highlighted item of a list moved by the arrow keys, shared by pickers, menus,
+/// and palettes.
+///
+/// The items are any signal or memo of a `Vec`, such as filtered search results.
+/// The highlighted index stays within the items when they shrink, and
+/// [`highlighted`](Self::highlighted) is a memo of the item under it, so a view
+/// rendering the highlight updates on every move. [`handle_key`](Self::handle_key)
+/// takes the key of a GPUI key event, so the whole list can be driven from one
+/// `on_key_down` handler.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::prelude::FluentBuilder;
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+///
+/// struct Picker {
+///     navigation: ListNavigation<SharedString>,
+/// }
+///
+/// impl Render for Picker {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         let navigation = self.navigation;
+///         let highlighted = navigation.highlighted_index().get();
+///         div()
+///             .on_key_down(move |event, _, _| {
+///                 navigation.handle_key(&event.keystroke.key);
+///             })
+///             .children(navigation.items().get().into_iter().enumerate().map(
+///                 move |(index, item)| {
+///                     div()
+///                         .when(highlighted == Some(index), |this| this.bg(rgb(0x3b82f6)))
+///                         .child(item)
+///                 },
+///             ))
+///     }
+/// }
+///
+/// fn new_picker(cx: &mut Context<Picker>) -> Picker {
+///     let items = cx.create_signal(vec!["Open".into(), "Save".into(), "Close".into()]);
+///     Picker {
+///         navigation: cx.create_list_navigation(items).wrap_around(true),
+///     }
+/// }
+/// ```
+pub struct ListNavigation<T> {
+    items: ReadOnlySignal<Vec<T>>,
+    cursor: Signal<usize>,
+    highlighted_index: Memo<Option<usize>>,
+    highlighted: Memo<Option<T>>,
+    selected: Signal<Option<T>>,
+    wrap_around: bool,
+}
+
+impl<T> Copy for ListNavigation<T> {}
+
+impl<T> Clone for ListNavigation<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for ListNavigation<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cursor == other.cursor
+    }
+}
+
+impl<T> Eq for ListNavigation<T> {}
+
+impl<T: 'static + Clone> ListNavigation<T> {
+    /// Create a navigation over `items`, highlighting the first.
+    pub fn new(items: impl Into<ReadOnlySignal<Vec<T>>>) -> Self {
+        Self::new_in(items, &SignalRuntime::default())
+    }
+
+    /// Create a navigation over `items` in `runtime`.
+    pub fn new_in(items: impl Into<ReadOnlySignal<Vec<T>>>, runtime: &SignalRuntime) -> Self {
+        let items = items.into();
+        let cursor = Signal::new_in(0, runtime);
+        let highlighted_index = Memo::new_in(move || Self::clamp(items, cursor), runtime);
+        Self::from_parts(
+            items,
+            cursor,
+            highlighted_index,
+            Memo::new_in(move || Self::item_at(items, highlighted_index), runtime),
+            Signal::new_in(None, runtime),
+        )
+    }
+
+    pub(crate) fn from_parts(
+        items: ReadOnlySignal<Vec<T>>,
+        cursor: Signal<usize>,
+        highlighted_index: Memo<Option<usize>>,
+        highlighted: Memo<Option<T>>,
+        selected: Signal<Option<T>>,
+    ) -> Self {
+        Self {
+            items,
+            cursor,
+            highlighted_index,
+            highlighted,
+            selected,
+            wrap_around: false,
+        }
+    }
+
+    /// The cursor limited to the items, or `None` when there are none, tracking the
+    /// reads.
+    pub(crate) fn clamp(items: ReadOnlySignal<Vec<T>>, cursor: Signal<usize>) -> Option<usize> {
+        let len = items.with(Vec::len);
+        (len > 0).then(|| cursor.get().min(len - 1))
+    }
+
+    /// The item at the highlighted index, tracking the reads.
+    pub(crate) fn item_at(
+        items: ReadOnlySignal<Vec<T>>,
+        highlighted_index: Memo<Option<usize>>,
+    ) -> Option<T> {
+        let index = highlighted_index.get()?;
+        items.with(|items| items.get(index).cloned())
+    }
+
+    /// Move from the last item to the first and back instead of stopping at the ends.
+    pub fn wrap_around(mut self, wrap_around: bool) -> Self {
+        self.wrap_around = wrap_around;
+        self
+    }
+
+    /// The items navigated over.
+    pub fn items(&self) -> ReadOnlySignal<Vec<T>> {
+        self.items
+    }
+
+    /// A memo of the highlighted index, or `None` while there are no items.
+    pub fn highlighted_index(&self) -> Memo<Option<usize>> {
+        self.highlighted_index
+    }
+
+    /// A memo of the highlighted item.
+    pub fn highlighted(&self) -> Memo<Option<T>> {
+        self.highlighted
+    }
+
+    /// The item last chosen with [`select`](Self::select).
+    pub fn selected(&self) -> ReadOnlySignal<Option<T>> {
+        self.selected.read_only()
+    }
+
+    /// Highlight the previous item.
+    #[track_caller]
+    pub fn move_up(&self) {
+        self.step(|index, len, wrap_around| match index {
+            0 if wrap_around => Some(len - 1),
+            0 => None,
+            _ => Some(index - 1),
+        });
+    }
+
+    /// Highlight the next item.
+    #[track_caller]
+    pub fn move_down(&self) {
+        self.step(|index, len, wrap_around| match index + 1 {
+            next if next < len => Some(next),
+            _ if wrap_around => Some(0),
+            _ => None,
+        });
+    }
+
+    /// Highlight the first item.
+    #[track_caller]
+    pub fn move_to_first(&self) {
+        self.highlight(0);
+    }
+
+    /// Highlight the last item.
+    #[track_caller]
+    pub fn move_to_last(&self) {
+        self.highlight(usize::MAX);
+    }
+
+    /// Highlight the item at `index`, or the last item if `index` is past it, such
+    /// as when the pointer moves over a row.
+    #[track_caller]
+    pub fn highlight(&self, index: usize) {
+        let len = self.items.with_untracked(Vec::len);
+        self.cursor.set_if_changed(index.min(len.saturating_sub(1)));
+    }
+
+    /// Choose the highlighted item, returning it.
+    #[track_caller]
+    pub fn select(&self) -> Option<T> {
+        let item = self.highlighted.get_untracked()?;
+        self.selected.set(Some(item.clone()));
+        Some(item)
+    }
+
+    /// Apply the key of a GPUI key event: `up`, `down`, `home`, and `end` move the
+    /// highlight and `enter` selects. Returns whether the key was one of these, so
+    /// the handler can stop the event from propagating.
+    #[track_caller]
+    pub fn handle_key(&self, key: &str) -> bool {
+        match key {
+            "up" => self.move_up(),
+            "down" => self.move_down(),
+            "home" => self.move_to_first(),
+            "end" => self.move_to_last(),
+            "enter" => {
+                self.select();
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    #[track_caller]
+    fn step(&self, next: impl FnOnce(usize, usize, bool) -> Option<usize>) {
+        let len = self.items.with_untracked(Vec::len);
+        if len == 0 {
+            return;
+        }
+        let index = self.cursor.get_untracked().min(len - 1);
+        if let Some(index) = next(index, len, self.wrap_around) {
+            self.cursor.set_if_changed(index);
+        }
+    }
+
+    /// Free the navigation's signals and memos. The items are left alone.
+    pub fn dispose(self) {
+        self.highlighted.signal().dispose();
+        self.highlighted_index.signal().dispose();
+        self.selected.dispose();
+        self.cursor.dispose();
+    }
+}
+
+impl<T: 'static + Clone + fmt::Debug> fmt::Debug for ListNavigation<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListNavigation")
+            .field("highlighted", &self.highlighted.get_untracked())
+            .field("wrap_around", &self.wrap_around)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arrow_keys_move_the_highlight_within_the_items() {
+        let items = Signal::new(vec!["open", "save", "close"]);
+        let navigation = ListNavigation::new(items);
+        assert_eq!(navigation.highlighted().get(), Some("open"));
+
+        assert!(navigation.handle_key("up"));
+        assert_eq!(navigation.highlighted_index().get(), Some(0));
+        navigation.move_down();
+        navigation.move_down();
+        navigation.move_down();
+        assert_eq!(navigation.highlighted().get(), Some("close"));
+        assert!(navigation.handle_key("enter"));
+        assert_eq!(navigation.selected().get(), Some("close"));
+        assert!(!navigation.handle_key("a"));
+
+        items.set(vec!["open"]);
+        assert_eq!(navigation.highlighted().get(), Some("open"));
+        items.set(Vec::new());
+        assert_eq!(navigation.highlighted().get(), None);
+        assert_eq!(navigation.select(), None);
+
+        let wrapping = ListNavigation::new(Signal::new(vec![1, 2, 3])).wrap_around(true);
+        wrapping.move_up();
+        assert_eq!(wrapping.highlighted().get(), Some(3));
+        wrapping.move_down();
+        assert_eq!(wrapping.highlighted().get(), Some(1));
+    }
+}
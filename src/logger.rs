@@ -0,0 +1,177 @@
+//! Rate-limited logging of signal changes.
+
+use crate::ReadOnlySignal;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::{Rc, Weak};
+use std::time::{Duration, Instant};
+
+const DEFAULT_MAX_LINES_PER_SECOND: u32 = 20;
+
+type Sink = Box<dyn Fn(&str)>;
+
+/// Logs each change to named signals as one line: the name, the old and new values
+/// formatted with `Debug`, and in debug builds the `set` or `update` call that made
+/// it.
+///
+/// ```text
+/// selection: Some(3) -> None (src/sidebar.rs:42:18)
+/// ```
+///
+/// Lines go to stderr unless a [`sink`](Self::sink) is given. A signal written in a
+/// loop could otherwise drown everything else, so at most
+/// [`max_lines_per_second`](Self::max_lines_per_second) lines are written; the rest
+/// are counted and summarized once the next second starts. Logging stops once the
+/// logger and all its clones are dropped.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::LoggerMiddleware;
+///
+/// let logger = LoggerMiddleware::new().max_lines_per_second(5);
+/// let selection = Signal::new(Some(3usize));
+/// logger.log("selection", selection);
+///
+/// selection.set(None);
+/// // stderr: `selection: Some(3) -> None (src/main.rs:9:11)`
+/// ```
+#[derive(Clone)]
+pub struct LoggerMiddleware {
+    inner: Rc<Logger>,
+}
+
+struct Logger {
+    sink: RefCell<Sink>,
+    max_lines_per_second: Cell<u32>,
+    window_start: Cell<Instant>,
+    lines_in_window: Cell<u32>,
+    suppressed: Cell<u32>,
+}
+
+impl LoggerMiddleware {
+    /// Create a logger writing up to 20 lines per second to stderr.
+    pub fn new() -> Self {
+        Self {
+            inner: Rc::new(Logger {
+                sink: RefCell::new(Box::new(|line| eprintln!("{line}"))),
+                max_lines_per_second: Cell::new(DEFAULT_MAX_LINES_PER_SECOND),
+                window_start: Cell::new(Instant::now()),
+                lines_in_window: Cell::new(0),
+                suppressed: Cell::new(0),
+            }),
+        }
+    }
+
+    /// Write at most `lines` lines per second.
+    pub fn max_lines_per_second(self, lines: u32) -> Self {
+        self.inner.max_lines_per_second.set(lines);
+        self
+    }
+
+    /// Send lines to `sink` instead of stderr, for example to a `log` or `tracing`
+    /// macro.
+    pub fn sink(self, sink: impl Fn(&str) + 'static) -> Self {
+        *self.inner.sink.borrow_mut() = Box::new(sink);
+        self
+    }
+
+    /// Log every change to `signal` under `name`.
+    pub fn log<T: fmt::Debug + 'static>(
+        &self,
+        name: impl Into<String>,
+        signal: impl Into<ReadOnlySignal<T>>,
+    ) {
+        let name = name.into();
+        let signal = signal.into();
+        let previous = RefCell::new(signal.with_untracked(|value| format!("{value:?}")));
+        let version = Cell::new(signal.version());
+        let logger = Rc::downgrade(&self.inner);
+        signal.subscribe_while(move || {
+            let Some(logger) = Weak::upgrade(&logger) else {
+                return false;
+            };
+            // Reading first brings a dirty memo up to date, so its version is current.
+            let current = signal.with_untracked(|value| format!("{value:?}"));
+            let current_version = signal.version();
+            if version.replace(current_version) == current_version {
+                return true;
+            }
+            let old = previous.replace(current.clone());
+            let line = match signal.last_writer() {
+                Some(location) => format!("{name}: {old} -> {current} ({location})"),
+                None => format!("{name}: {old} -> {current}"),
+            };
+            logger.write(&line);
+            true
+        });
+    }
+}
+
+impl Default for LoggerMiddleware {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LoggerMiddleware {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoggerMiddleware")
+            .field(
+                "max_lines_per_second",
+                &self.inner.max_lines_per_second.get(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl Logger {
+    fn write(&self, line: &str) {
+        let now = Instant::now();
+        if now.duration_since(self.window_start.get()) >= Duration::from_secs(1) {
+            self.window_start.set(now);
+            self.lines_in_window.set(0);
+            let suppressed = self.suppressed.replace(0);
+            if suppressed > 0 {
+                (self.sink.borrow())(&format!("... {suppressed} more changes not logged"));
+            }
+        }
+        if self.lines_in_window.get() >= self.max_lines_per_second.get() {
+            self.suppressed.set(self.suppressed.get() + 1);
+            return;
+        }
+        self.lines_in_window.set(self.lines_in_window.get() + 1);
+        (self.sink.borrow())(line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Memo, Signal};
+
+    #[test]
+    fn test_logs_changes_with_location_up_to_the_rate_limit() {
+        let lines = Rc::new(RefCell::new(Vec::new()));
+        let logger = LoggerMiddleware::new().max_lines_per_second(3).sink({
+            let lines = lines.clone();
+            move |line| lines.borrow_mut().push(line.to_string())
+        });
+        let count = Signal::new(0);
+        let doubled = Memo::new(move || count.get() * 2);
+        logger.log("count", count);
+        logger.log("doubled", doubled);
+
+        count.set(1);
+        let line = line!() - 1;
+        count.set(2);
+        count.set(3);
+
+        let lines = lines.borrow();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], format!("count: 0 -> 1 ({}:{line}:15)", file!()));
+        assert_eq!(lines[1], "doubled: 0 -> 2");
+        assert!(lines[2].starts_with("count: 1 -> 2 ("));
+    }
+}
@@ -0,0 +1,210 @@
+//! Declaration macros for signals.
+
+use crate::{ReadOnlySignal, Signal};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+thread_local! {
+    static GLOBAL_SIGNALS: RefCell<HashMap<&'static str, Box<dyn Any>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// A signal declared with [`global_signal!`], created on first use.
+///
+/// Each thread gets its own signal in its default runtime, initialized the first
+/// time the global is accessed there.
+pub struct GlobalSignal<T> {
+    key: &'static str,
+    init: fn() -> T,
+}
+
+impl<T: 'static> GlobalSignal<T> {
+    #[doc(hidden)]
+    pub const fn new(key: &'static str, init: fn() -> T) -> Self {
+        Self { key, init }
+    }
+
+    /// The underlying signal, creating it if this is the first access.
+    pub fn signal(&self) -> Signal<T> {
+        let existing = GLOBAL_SIGNALS.with(|globals| {
+            globals
+                .borrow()
+                .get(self.key)
+                .map(|signal| *signal.downcast_ref::<Signal<T>>().expect("global type"))
+        });
+        if let Some(signal) = existing {
+            return signal;
+        }
+
+        // Initialized outside the borrow, since the initializer may read other globals.
+        let signal = Signal::new((self.init)());
+        GLOBAL_SIGNALS.with(|globals| {
+            globals.borrow_mut().insert(self.key, Box::new(signal));
+        });
+        signal
+    }
+
+    /// Get the current value, tracking the read.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.signal().get()
+    }
+
+    /// Get the current value without tracking the read.
+    pub fn get_untracked(&self) -> T
+    where
+        T: Clone,
+    {
+        self.signal().get_untracked()
+    }
+
+    /// Read the value with a closure, tracking the read.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.signal().with(f)
+    }
+
+    /// Set a new value.
+    pub fn set(&self, value: T) {
+        self.signal().set(value);
+    }
+
+    /// Update the value with a closure.
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        self.signal().update(f);
+    }
+
+    /// Convert the underlying signal to a read-only signal.
+    pub fn read_only(&self) -> ReadOnlySignal<T> {
+        self.signal().read_only()
+    }
+}
+
+impl<T> fmt::Debug for GlobalSignal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("GlobalSignal").field(&self.key).finish()
+    }
+}
+
+/// Declare lazily initialized global signals.
+///
+/// Each declaration becomes a `static` [`GlobalSignal`] whose initializer runs on
+/// first access, so no setup call is needed before reading it.
+///
+/// ```rust,no_run
+/// use gpui_signals::global_signal;
+///
+/// #[derive(Clone, Copy, PartialEq)]
+/// enum Theme {
+///     Light,
+///     Dark,
+/// }
+///
+/// global_signal! {
+///     static THEME: Theme = Theme::Light;
+///     pub static FONT_SIZE: f32 = 14.;
+/// }
+///
+/// THEME.set(Theme::Dark);
+/// assert_eq!(FONT_SIZE.get(), 14.);
+/// ```
+#[macro_export]
+macro_rules! global_signal {
+    ($($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $init:expr;)+) => {
+        $(
+            $(#[$attr])*
+            $vis static $name: $crate::GlobalSignal<$ty> = $crate::GlobalSignal::new(
+                concat!(module_path!(), "::", stringify!($name)),
+                || $init,
+            );
+        )+
+    };
+}
+
+/// Declare several entity signals at once.
+///
+/// Expands to one `let` binding per signal, each created with
+/// [`create_signal`](crate::SignalContext::create_signal).
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::signal;
+///
+/// struct Form {
+///     count: Signal<i32>,
+///     name: Signal<String>,
+/// }
+///
+/// impl Form {
+///     fn new(cx: &mut Context<Self>) -> Self {
+///         signal!(cx, count: i32 = 0, name: String = "".into());
+///         Self { count, name }
+///     }
+/// }
+/// ```
+#[cfg(feature = "gpui")]
+#[macro_export]
+macro_rules! signal {
+    ($cx:expr, $($name:ident: $ty:ty = $init:expr),+ $(,)?) => {
+        $(
+            let $name: $crate::Signal<$ty> = $crate::SignalContext::create_signal($cx, $init);
+        )+
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    thread_local! {
+        static INIT_CALLS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn initial_count() -> i32 {
+        INIT_CALLS.with(|calls| calls.set(calls.get() + 1));
+        3
+    }
+
+    global_signal! {
+        static COUNT: i32 = initial_count();
+        static OTHER: i32 = 3;
+    }
+
+    #[test]
+    fn test_global_signal_initializes_once() {
+        assert_eq!(INIT_CALLS.with(Cell::get), 0);
+        assert_eq!(COUNT.get(), 3);
+        COUNT.update(|count| *count += 1);
+        assert_eq!(COUNT.get(), 4);
+        assert_eq!(COUNT.signal(), COUNT.signal());
+        assert_eq!(INIT_CALLS.with(Cell::get), 1);
+
+        assert_ne!(COUNT.signal(), OTHER.signal());
+        assert_eq!(OTHER.get(), 3);
+    }
+
+    #[cfg(feature = "gpui")]
+    #[gpui::test]
+    async fn test_signal_macro_declares_entity_signals(cx: &mut gpui::TestAppContext) {
+        use crate::Signal;
+        use gpui::AppContext;
+
+        struct Form {
+            count: Signal<i32>,
+            name: Signal<String>,
+        }
+
+        let form = cx.new(|cx| {
+            signal!(cx, count: i32 = 1, name: String = "Ada".into(),);
+            Form { count, name }
+        });
+        form.read_with(cx, |form, _| {
+            assert_eq!(form.count.get(), 1);
+            assert_eq!(form.name.get(), "Ada");
+        });
+    }
+}
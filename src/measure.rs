@@ -0,0 +1,119 @@
+//! Element bounds reported into signals after layout.
+//!
+//! Layout runs after render, so a view can't read where its elements ended up while
+//! building them. An element tagged with [`MeasureExt::measure`] writes its bounds
+//! into a signal once laid out, and memos over that signal, such as a popover's
+//! position or an alignment guide, follow it like any other state. A change of
+//! bounds re-renders the entity on the next frame.
+
+use crate::{Signal, SignalContext};
+use gpui::{canvas, Bounds, Context, ParentElement, Pixels, Styled};
+
+/// Create a signal for the bounds of one element, in window coordinates, owned by
+/// the entity.
+///
+/// The bounds are empty until the element tagged with it has been laid out. The
+/// entity is notified whenever they change.
+pub fn measured_bounds<V: 'static>(cx: &mut Context<V>) -> Signal<Bounds<Pixels>> {
+    cx.create_signal(Bounds::default())
+}
+
+/// Element-builder extension for reporting an element's bounds to a signal.
+pub trait MeasureExt: ParentElement + Sized {
+    /// Write this element's bounds into `bounds` each time it is laid out.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::measure::{measured_bounds, MeasureExt};
+    /// use gpui_signals::prelude::*;
+    ///
+    /// struct Toolbar {
+    ///     button: Signal<Bounds<Pixels>>,
+    ///     menu_origin: Memo<Point<Pixels>>,
+    /// }
+    ///
+    /// impl Toolbar {
+    ///     fn new(cx: &mut Context<Self>) -> Self {
+    ///         let button = measured_bounds(cx);
+    ///         Self {
+    ///             button,
+    ///             menu_origin: cx.create_memo(move || button.get().bottom_left()),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// impl Render for Toolbar {
+    ///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+    ///         let origin = self.menu_origin.get();
+    ///         div()
+    ///             .child(div().child("File").measure(self.button))
+    ///             .child(
+    ///                 div()
+    ///                     .absolute()
+    ///                     .left(origin.x)
+    ///                     .top(origin.y)
+    ///                     .child("Open"),
+    ///             )
+    ///     }
+    /// }
+    /// ```
+    fn measure(self, bounds: Signal<Bounds<Pixels>>) -> Self {
+        // An absolutely positioned child filling the element is laid out with the
+        // element's own bounds, without affecting the layout of its other children.
+        self.child(
+            canvas(
+                move |measured, _, _| {
+                    if bounds.is_alive() {
+                        bounds.set_if_changed(measured);
+                    }
+                },
+                |_, _, _, _| {},
+            )
+            .absolute()
+            .size_full(),
+        )
+    }
+}
+
+impl<E: ParentElement> MeasureExt for E {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Memo;
+    use gpui::{div, px, IntoElement, Render, TestAppContext, Window};
+
+    struct Panel {
+        width: Signal<Pixels>,
+        bounds: Signal<Bounds<Pixels>>,
+    }
+
+    impl Render for Panel {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div().child(
+                div()
+                    .w(self.width.get())
+                    .h(px(20.))
+                    .ml(px(10.))
+                    .measure(self.bounds),
+            )
+        }
+    }
+
+    #[gpui::test]
+    async fn test_measured_bounds_follow_layout(cx: &mut TestAppContext) {
+        let (panel, cx) = cx.add_window_view(|_, cx| Panel {
+            width: cx.create_signal(px(100.)),
+            bounds: measured_bounds(cx),
+        });
+        cx.run_until_parked();
+        let (width, bounds) = panel.read_with(cx, |panel, _| (panel.width, panel.bounds));
+        let right_edge = Memo::new(move || bounds.get().right());
+        assert_eq!(bounds.get().size.width, px(100.));
+        assert_eq!(right_edge.get(), px(110.));
+
+        width.set(px(150.));
+        cx.run_until_parked();
+        assert_eq!(right_edge.get(), px(160.));
+    }
+}
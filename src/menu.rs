@@ -0,0 +1,298 @@
+//! Application menus whose items follow signals.
+//!
+//! GPUI menu items have no enabled or checked state of their own: the platform greys
+//! out an item when no handler is available for its action. A [`SignalMenuItem`]
+//! derives both states from memos instead, and [`bind_menus`] sets the menu bar
+//! again whenever one of them changes.
+
+use crate::{Effect, Memo};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use gpui::{Action, App, Global, Menu, MenuItem, NoAction, SharedString, Task};
+use std::fmt;
+use std::rc::Rc;
+
+/// A menu of items bound to signals, converted to a GPUI [`Menu`] by [`build`](Self::build).
+pub struct SignalMenu {
+    name: SharedString,
+    items: Vec<SignalMenuItem>,
+}
+
+impl SignalMenu {
+    /// Create a menu named `name`.
+    pub fn new(name: impl Into<SharedString>, items: Vec<SignalMenuItem>) -> Self {
+        Self {
+            name: name.into(),
+            items,
+        }
+    }
+
+    /// The menu as it should look for the current signal values.
+    ///
+    /// Disabled items point at [`NoAction`], which nothing handles, so the platform
+    /// shows them as unavailable; checked items get a check mark before their name.
+    /// This doesn't track the reads.
+    pub fn build(&self) -> Menu {
+        Menu {
+            name: self.name.clone(),
+            items: self.items.iter().map(SignalMenuItem::build).collect(),
+        }
+    }
+
+    /// Read every state memo, tracking the reads.
+    fn track(&self) {
+        for item in &self.items {
+            match &item.kind {
+                ItemKind::Separator => {}
+                ItemKind::Submenu(menu) => menu.track(),
+                ItemKind::Action { .. } => {
+                    item.enabled.get();
+                    if let Some(checked) = item.checked {
+                        checked.get();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for SignalMenu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignalMenu")
+            .field("name", &self.name)
+            .field("items", &self.items.len())
+            .finish()
+    }
+}
+
+enum ItemKind {
+    Separator,
+    Submenu(SignalMenu),
+    Action {
+        name: SharedString,
+        action: Box<dyn Action>,
+    },
+}
+
+/// An item of a [`SignalMenu`].
+pub struct SignalMenuItem {
+    kind: ItemKind,
+    enabled: Memo<bool>,
+    checked: Option<Memo<bool>>,
+}
+
+impl SignalMenuItem {
+    /// A separator between items.
+    pub fn separator() -> Self {
+        Self::from_kind(ItemKind::Separator)
+    }
+
+    /// A submenu.
+    pub fn submenu(menu: SignalMenu) -> Self {
+        Self::from_kind(ItemKind::Submenu(menu))
+    }
+
+    /// An always-enabled item dispatching `action`.
+    pub fn action(name: impl Into<SharedString>, action: impl Action) -> Self {
+        Self::from_kind(ItemKind::Action {
+            name: name.into(),
+            action: Box::new(action),
+        })
+    }
+
+    fn from_kind(kind: ItemKind) -> Self {
+        Self {
+            kind,
+            enabled: Memo::new(|| true),
+            checked: None,
+        }
+    }
+
+    /// Derive whether the item can be chosen from signals.
+    ///
+    /// `predicate` is wrapped in a memo, so it is re-evaluated whenever a signal it
+    /// reads changes.
+    pub fn enabled_when(mut self, predicate: impl Fn() -> bool + 'static) -> Self {
+        self.enabled = Memo::new(predicate);
+        self
+    }
+
+    /// Show a check mark before the item's name while `predicate` is true.
+    pub fn checked_when(mut self, predicate: impl Fn() -> bool + 'static) -> Self {
+        self.checked = Some(Memo::new(predicate));
+        self
+    }
+
+    /// A memo tracking whether the item can currently be chosen.
+    pub fn enabled(&self) -> Memo<bool> {
+        self.enabled
+    }
+
+    fn build(&self) -> MenuItem {
+        match &self.kind {
+            ItemKind::Separator => MenuItem::separator(),
+            ItemKind::Submenu(menu) => MenuItem::submenu(menu.build()),
+            ItemKind::Action { name, action } => {
+                let checked = self.checked.is_some_and(|checked| checked.get_untracked());
+                let name = if checked {
+                    SharedString::from(format!("✓ {name}"))
+                } else {
+                    name.clone()
+                };
+                let action = if self.enabled.get_untracked() {
+                    action.boxed_clone()
+                } else {
+                    Box::new(NoAction)
+                };
+                MenuItem::Action {
+                    name,
+                    action,
+                    os_action: None,
+                }
+            }
+        }
+    }
+
+    fn dispose(&self) {
+        self.enabled.signal().dispose();
+        if let Some(checked) = self.checked {
+            checked.signal().dispose();
+        }
+        if let ItemKind::Submenu(menu) = &self.kind {
+            menu.items.iter().for_each(SignalMenuItem::dispose);
+        }
+    }
+}
+
+impl fmt::Debug for SignalMenuItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ItemKind::Separator => f.write_str("Separator"),
+            ItemKind::Submenu(menu) => f.debug_tuple("Submenu").field(menu).finish(),
+            ItemKind::Action { name, action } => f
+                .debug_struct("Action")
+                .field("name", name)
+                .field("action", &action.name())
+                .field("enabled", &self.enabled)
+                .field("checked", &self.checked)
+                .finish(),
+        }
+    }
+}
+
+/// The menus set by the last [`bind_menus`] call, kept alive with the effect and
+/// task that refresh them.
+struct BoundMenus {
+    menus: Rc<Vec<SignalMenu>>,
+    effect: Effect,
+    _task: Task<()>,
+}
+
+impl Global for BoundMenus {}
+
+impl Drop for BoundMenus {
+    fn drop(&mut self) {
+        self.effect.stop();
+        for menu in self.menus.iter() {
+            menu.items.iter().for_each(SignalMenuItem::dispose);
+        }
+    }
+}
+
+/// Set the application's menu bar to `menus` and set it again whenever an item's
+/// enabled or checked state changes.
+///
+/// Calling this again replaces the menus and stops refreshing the previous ones.
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::menu::{bind_menus, SignalMenu, SignalMenuItem};
+/// use gpui_signals::prelude::*;
+///
+/// actions!(editor, [Save, ToggleSidebar]);
+///
+/// fn install_menus(dirty: Memo<bool>, sidebar: Signal<bool>, cx: &mut App) {
+///     bind_menus(
+///         vec![SignalMenu::new(
+///             "File",
+///             vec![
+///                 SignalMenuItem::action("Save", Save).enabled_when(move || dirty.get()),
+///                 SignalMenuItem::separator(),
+///                 SignalMenuItem::action("Sidebar", ToggleSidebar)
+///                     .checked_when(move || sidebar.get()),
+///             ],
+///         )],
+///         cx,
+///     );
+/// }
+/// ```
+pub fn bind_menus(menus: Vec<SignalMenu>, cx: &mut App) {
+    let menus = Rc::new(menus);
+    let (tx, mut rx) = mpsc::unbounded::<()>();
+    // Runs once now, sending the initial menus, then again on every state change.
+    let effect = Effect::new({
+        let menus = menus.clone();
+        move || {
+            menus.iter().for_each(SignalMenu::track);
+            let _ = tx.unbounded_send(());
+        }
+    });
+    let task = cx.spawn({
+        let menus = menus.clone();
+        async move |cx| {
+            while let Some(()) = rx.next().await {
+                let updated =
+                    cx.update(|cx| cx.set_menus(menus.iter().map(SignalMenu::build).collect()));
+                if updated.is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    cx.set_global(BoundMenus {
+        menus,
+        effect,
+        _task: task,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Signal;
+    use gpui::{actions, is_no_action};
+
+    actions!(menu_test, [Save, ToggleSidebar]);
+
+    fn item_state(item: &MenuItem) -> (String, bool) {
+        match item {
+            MenuItem::Action { name, action, .. } => (name.to_string(), !is_no_action(&**action)),
+            _ => panic!("expected an action item"),
+        }
+    }
+
+    #[test]
+    fn test_menu_items_follow_signals() {
+        let dirty = Signal::new(false);
+        let sidebar = Signal::new(true);
+        let menu = SignalMenu::new(
+            "File",
+            vec![
+                SignalMenuItem::action("Save", Save).enabled_when(move || dirty.get()),
+                SignalMenuItem::separator(),
+                SignalMenuItem::action("Sidebar", ToggleSidebar)
+                    .checked_when(move || sidebar.get()),
+            ],
+        );
+
+        let built = menu.build();
+        assert_eq!(item_state(&built.items[0]), ("Save".to_string(), false));
+        assert_eq!(item_state(&built.items[2]), ("✓ Sidebar".to_string(), true));
+
+        dirty.set(true);
+        sidebar.set(false);
+        let built = menu.build();
+        assert_eq!(item_state(&built.items[0]), ("Save".to_string(), true));
+        assert_eq!(item_state(&built.items[2]), ("Sidebar".to_string(), true));
+    }
+}
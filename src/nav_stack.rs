@@ -0,0 +1,216 @@
+//! A reactive stack of navigation entries.
+
+use crate::runtime::SignalRuntime;
+use crate::signal::{ReadOnlySignal, Signal};
+use crate::Memo;
+use std::fmt;
+
+/// A stack of navigation entries for master-detail views, which always keeps its
+/// root entry.
+///
+/// [`current`](Self::current) and [`can_go_back`](Self::can_go_back) are memos, so
+/// a view that renders the top entry and a back button updates on every push and
+/// pop. [`entries`](Self::entries) lists the whole stack for breadcrumbs, and
+/// [`pop_to`](Self::pop_to) jumps back to one of them.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+///
+/// #[derive(Clone, PartialEq)]
+/// enum Page {
+///     Inbox,
+///     Message(u64),
+/// }
+///
+/// struct Mail {
+///     pages: NavStack<Page>,
+/// }
+///
+/// impl Render for Mail {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         let pages = self.pages;
+///         div()
+///             .when(pages.can_go_back().get(), |this| {
+///                 this.child(
+///                     div()
+///                         .child("Back")
+///                         .on_mouse_down(MouseButton::Left, move |_, _, _| {
+///                             pages.pop();
+///                         }),
+///                 )
+///             })
+///             .child(match pages.current().get() {
+///                 Page::Inbox => "Inbox".to_string(),
+///                 Page::Message(id) => format!("Message {id}"),
+///             })
+///     }
+/// }
+///
+/// fn new_mail(cx: &mut Context<Mail>) -> Mail {
+///     Mail {
+///         pages: cx.create_nav_stack(Page::Inbox),
+///     }
+/// }
+/// ```
+pub struct NavStack<T> {
+    entries: Signal<Vec<T>>,
+    current: Memo<T>,
+    can_go_back: Memo<bool>,
+}
+
+impl<T> Copy for NavStack<T> {}
+
+impl<T> Clone for NavStack<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for NavStack<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl<T> Eq for NavStack<T> {}
+
+impl<T: 'static + Clone> NavStack<T> {
+    /// Create a stack holding only `root`.
+    pub fn new(root: T) -> Self {
+        Self::new_in(root, &SignalRuntime::default())
+    }
+
+    /// Create a stack holding only `root` in `runtime`.
+    pub fn new_in(root: T, runtime: &SignalRuntime) -> Self {
+        let entries = Signal::new_in(vec![root], runtime);
+        Self::from_parts(
+            entries,
+            Memo::new_in(move || Self::top(entries), runtime),
+            Memo::new_in(move || entries.with(|entries| entries.len() > 1), runtime),
+        )
+    }
+
+    pub(crate) fn from_parts(
+        entries: Signal<Vec<T>>,
+        current: Memo<T>,
+        can_go_back: Memo<bool>,
+    ) -> Self {
+        Self {
+            entries,
+            current,
+            can_go_back,
+        }
+    }
+
+    /// The last entry of `entries`, tracking the read.
+    pub(crate) fn top(entries: Signal<Vec<T>>) -> T {
+        entries.with(|entries| {
+            entries
+                .last()
+                .cloned()
+                .expect("a navigation stack keeps its root")
+        })
+    }
+
+    /// A memo of the entry on top of the stack.
+    pub fn current(&self) -> Memo<T> {
+        self.current
+    }
+
+    /// A memo tracking whether there is an entry below the current one.
+    pub fn can_go_back(&self) -> Memo<bool> {
+        self.can_go_back
+    }
+
+    /// Every entry, root first.
+    pub fn entries(&self) -> ReadOnlySignal<Vec<T>> {
+        self.entries.read_only()
+    }
+
+    /// The number of entries, including the root.
+    pub fn depth(&self) -> usize {
+        self.entries.with(Vec::len)
+    }
+
+    /// Navigate to `entry`.
+    #[track_caller]
+    pub fn push(&self, entry: T) {
+        self.entries.update(|entries| entries.push(entry));
+    }
+
+    /// Go back one entry, returning the one removed, or `None` at the root.
+    #[track_caller]
+    pub fn pop(&self) -> Option<T> {
+        if self.entries.with_untracked(|entries| entries.len() <= 1) {
+            return None;
+        }
+        self.entries.update_with(Vec::pop).flatten()
+    }
+
+    /// Swap the current entry for `entry`, such as when a detail view switches to
+    /// another item without growing the history.
+    #[track_caller]
+    pub fn replace(&self, entry: T) {
+        self.entries.update(|entries| {
+            *entries
+                .last_mut()
+                .expect("a navigation stack keeps its root") = entry;
+        });
+    }
+
+    /// Go back to the entry at `index`, where the root is 0, removing every entry
+    /// above it. Does nothing if `index` is already the current entry or past it.
+    #[track_caller]
+    pub fn pop_to(&self, index: usize) {
+        if self
+            .entries
+            .with_untracked(|entries| index + 1 >= entries.len())
+        {
+            return;
+        }
+        self.entries.update(|entries| entries.truncate(index + 1));
+    }
+
+    /// Free the entries and memos.
+    pub fn dispose(self) {
+        self.current.signal().dispose();
+        self.can_go_back.signal().dispose();
+        self.entries.dispose();
+    }
+}
+
+impl<T: 'static + Clone + fmt::Debug> fmt::Debug for NavStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NavStack")
+            .field("entries", &self.entries.get_untracked())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_pop_and_replace_keep_the_root() {
+        let pages = NavStack::new("inbox");
+        assert!(!pages.can_go_back().get());
+        assert_eq!(pages.pop(), None);
+
+        pages.push("thread");
+        pages.push("message 1");
+        pages.replace("message 2");
+        assert_eq!(pages.current().get(), "message 2");
+        assert!(pages.can_go_back().get());
+        assert_eq!(pages.pop(), Some("message 2"));
+        assert_eq!(pages.current().get(), "thread");
+
+        pages.push("message 3");
+        pages.pop_to(0);
+        assert_eq!(pages.entries().get(), ["inbox"]);
+        assert!(!pages.can_go_back().get());
+    }
+}
@@ -0,0 +1,387 @@
+//! Signals derived from the sequence of another signal's values.
+//!
+//! Unlike a memo, which only sees the values present when it is read, these follow
+//! every write to their source. Each derived signal is owned by the entity whose
+//! context created it: its subscription to the source, and any task driving it, are
+//! cleaned up when the entity is released.
+
+use crate::context::{dispose_on_release, track_subscription};
+use crate::{Memo, ReadOnlySignal, Signal};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use gpui::{Context, Subscription};
+use std::time::Duration;
+
+impl<T: Clone + 'static> Signal<T> {
+    /// A signal following this one at most `max_per_second` times a second.
+    ///
+    /// See [`ReadOnlySignal::rate_limited`].
+    pub fn rate_limited<V: 'static>(
+        &self,
+        max_per_second: u32,
+        cx: &mut Context<V>,
+    ) -> ReadOnlySignal<T> {
+        self.read_only().rate_limited(max_per_second, cx)
+    }
+
+    /// A signal taking this one's value each time `trigger` changes.
+    ///
+    /// See [`ReadOnlySignal::sample_on`].
+    pub fn sample_on<U: 'static, V: 'static>(
+        &self,
+        trigger: impl Into<ReadOnlySignal<U>>,
+        cx: &mut Context<V>,
+    ) -> ReadOnlySignal<T> {
+        self.read_only().sample_on(trigger, cx)
+    }
+
+    /// A signal of this one's previous and current values.
+    ///
+    /// See [`ReadOnlySignal::pairwise`].
+    pub fn pairwise<V: 'static>(&self, cx: &mut Context<V>) -> ReadOnlySignal<(Option<T>, T)> {
+        self.read_only().pairwise(cx)
+    }
+
+    /// A signal folding each of this one's values into an accumulator.
+    ///
+    /// See [`ReadOnlySignal::scan`].
+    pub fn scan<A: 'static, V: 'static>(
+        &self,
+        initial: A,
+        fold: impl Fn(&A, &T) -> A + 'static,
+        cx: &mut Context<V>,
+    ) -> ReadOnlySignal<A> {
+        self.read_only().scan(initial, fold, cx)
+    }
+}
+
+impl<T: Clone + 'static> ReadOnlySignal<T> {
+    /// A signal following this one at most `max_per_second` times a second, for
+    /// views reading a source that changes far more often than they can usefully
+    /// re-render, such as a level meter or a tailed log.
+    ///
+    /// The first change is passed on at once. Changes arriving within the following
+    /// interval are held back, and only the latest of them is passed on when it
+    /// ends, so the derived signal always settles on the source's value.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    ///
+    /// struct Meter {
+    ///     level: ReadOnlySignal<f32>,
+    /// }
+    ///
+    /// impl Meter {
+    ///     fn new(input: Signal<f32>, cx: &mut Context<Self>) -> Self {
+    ///         Self {
+    ///             level: input.rate_limited(30, cx),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// impl Render for Meter {
+    ///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+    ///         div().w(relative(self.level.get())).h(px(4.)).bg(rgb(0x22c55e))
+    ///     }
+    /// }
+    /// ```
+    pub fn rate_limited<V: 'static>(
+        &self,
+        max_per_second: u32,
+        cx: &mut Context<V>,
+    ) -> ReadOnlySignal<T> {
+        let source = *self;
+        let interval = Duration::from_secs(1) / max_per_second.max(1);
+        let limited = Signal::new(source.get_untracked());
+        let (wake, mut woken) = mpsc::unbounded();
+        source.subscribe_while(move || wake.unbounded_send(()).is_ok());
+        let task = cx.spawn(async move |_, cx| {
+            while woken.next().await.is_some() {
+                // Changes made while waiting collapse into one write of the latest value.
+                while woken.try_recv().is_ok() {}
+                if !source.is_alive() || !limited.is_alive() {
+                    break;
+                }
+                limited.set(source.get_untracked());
+                cx.background_executor().timer(interval).await;
+            }
+        });
+        track_subscription(cx, Subscription::new(move || drop(task)));
+        dispose_on_release(cx, move || limited.dispose());
+        limited.read_only()
+    }
+
+    /// A signal taking this one's value each time `trigger` changes, and holding it
+    /// in between, such as a draft captured when a commit counter is bumped.
+    ///
+    /// Starts with the current value. Changes to this signal alone are not passed on.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    ///
+    /// struct Composer {
+    ///     draft: Signal<String>,
+    ///     sends: Signal<u32>,
+    ///     sent: ReadOnlySignal<String>,
+    /// }
+    ///
+    /// impl Composer {
+    ///     fn new(cx: &mut Context<Self>) -> Self {
+    ///         let draft = cx.create_signal(String::new());
+    ///         let sends = cx.create_signal(0);
+    ///         Self {
+    ///             draft,
+    ///             sends,
+    ///             sent: draft.sample_on(sends, cx),
+    ///         }
+    ///     }
+    ///
+    ///     fn send(&mut self) {
+    ///         self.sends.update(|sends| *sends += 1);
+    ///     }
+    /// }
+    /// ```
+    pub fn sample_on<U: 'static, V: 'static>(
+        &self,
+        trigger: impl Into<ReadOnlySignal<U>>,
+        cx: &mut Context<V>,
+    ) -> ReadOnlySignal<T> {
+        let source = *self;
+        let sampled = Signal::new(source.get_untracked());
+        trigger.into().subscribe_while(move || {
+            if !source.is_alive() || !sampled.is_alive() {
+                return false;
+            }
+            sampled.set(source.get_untracked());
+            true
+        });
+        dispose_on_release(cx, move || sampled.dispose());
+        sampled.read_only()
+    }
+
+    /// A signal of this one's previous and current values, for showing which way a
+    /// value moved or by how much without a second signal to keep in step.
+    ///
+    /// The previous value is `None` until the first change.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    ///
+    /// struct Ticker {
+    ///     price: ReadOnlySignal<(Option<f64>, f64)>,
+    /// }
+    ///
+    /// impl Render for Ticker {
+    ///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+    ///         let (previous, current) = self.price.get();
+    ///         let arrow = match previous {
+    ///             Some(previous) if current > previous => "▲",
+    ///             Some(previous) if current < previous => "▼",
+    ///             _ => "",
+    ///         };
+    ///         div().child(format!("{current:.2} {arrow}"))
+    ///     }
+    /// }
+    ///
+    /// fn new_ticker(quote: Signal<f64>, cx: &mut Context<Ticker>) -> Ticker {
+    ///     Ticker {
+    ///         price: quote.pairwise(cx),
+    ///     }
+    /// }
+    /// ```
+    pub fn pairwise<V: 'static>(&self, cx: &mut Context<V>) -> ReadOnlySignal<(Option<T>, T)> {
+        let source = *self;
+        let pairs = Signal::new((None, source.get_untracked()));
+        source.subscribe_while(move || {
+            if !pairs.is_alive() {
+                return false;
+            }
+            let current = source.get_untracked();
+            pairs.update(|(previous, latest)| {
+                *previous = Some(std::mem::replace(latest, current));
+            });
+            true
+        });
+        dispose_on_release(cx, move || pairs.dispose());
+        pairs.read_only()
+    }
+
+    /// A signal folding each of this one's values into an accumulator, such as a
+    /// running total or a moving average.
+    ///
+    /// The current value is folded into `initial` at once, and every later value as
+    /// it is written, so the result covers each write even when nothing reads it in
+    /// between.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    ///
+    /// struct Downloads {
+    ///     chunk: Signal<u64>,
+    ///     received: ReadOnlySignal<u64>,
+    /// }
+    ///
+    /// impl Downloads {
+    ///     fn new(cx: &mut Context<Self>) -> Self {
+    ///         let chunk = cx.create_signal(0);
+    ///         Self {
+    ///             chunk,
+    ///             received: chunk.scan(0, |total, bytes| total + bytes, cx),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn scan<A: 'static, V: 'static>(
+        &self,
+        initial: A,
+        fold: impl Fn(&A, &T) -> A + 'static,
+        cx: &mut Context<V>,
+    ) -> ReadOnlySignal<A> {
+        let source = *self;
+        let accumulated = Signal::new(source.with_untracked(|value| fold(&initial, value)));
+        source.subscribe_while(move || {
+            if !accumulated.is_alive() {
+                return false;
+            }
+            let next =
+                source.with_untracked(|value| accumulated.with_untracked(|acc| fold(acc, value)));
+            accumulated.set(next);
+            true
+        });
+        dispose_on_release(cx, move || accumulated.dispose());
+        accumulated.read_only()
+    }
+}
+
+impl<T: Clone + 'static> Memo<T> {
+    /// A signal following the computed value at most `max_per_second` times a second.
+    ///
+    /// See [`ReadOnlySignal::rate_limited`].
+    pub fn rate_limited<V: 'static>(
+        &self,
+        max_per_second: u32,
+        cx: &mut Context<V>,
+    ) -> ReadOnlySignal<T> {
+        self.signal().rate_limited(max_per_second, cx)
+    }
+
+    /// A signal taking the computed value each time `trigger` changes.
+    ///
+    /// See [`ReadOnlySignal::sample_on`].
+    pub fn sample_on<U: 'static, V: 'static>(
+        &self,
+        trigger: impl Into<ReadOnlySignal<U>>,
+        cx: &mut Context<V>,
+    ) -> ReadOnlySignal<T> {
+        self.signal().sample_on(trigger, cx)
+    }
+
+    /// A signal of the previous and current computed values.
+    ///
+    /// See [`ReadOnlySignal::pairwise`].
+    pub fn pairwise<V: 'static>(&self, cx: &mut Context<V>) -> ReadOnlySignal<(Option<T>, T)> {
+        self.signal().pairwise(cx)
+    }
+
+    /// A signal folding each computed value into an accumulator.
+    ///
+    /// See [`ReadOnlySignal::scan`].
+    pub fn scan<A: 'static, V: 'static>(
+        &self,
+        initial: A,
+        fold: impl Fn(&A, &T) -> A + 'static,
+        cx: &mut Context<V>,
+    ) -> ReadOnlySignal<A> {
+        self.signal().scan(initial, fold, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext, TestAppContext};
+
+    struct Owner;
+
+    #[gpui::test]
+    async fn test_rate_limited_passes_on_the_latest_value_per_interval(cx: &mut TestAppContext) {
+        let source = Signal::new(0);
+        let owner = cx.new(|_| Owner);
+        let limited = owner.update(cx, |_, cx| source.rate_limited(10, cx));
+
+        source.set(1);
+        cx.run_until_parked();
+        assert_eq!(limited.get(), 1);
+
+        source.set(2);
+        source.set(3);
+        cx.run_until_parked();
+        assert_eq!(limited.get(), 1);
+        cx.executor().advance_clock(Duration::from_millis(100));
+        assert_eq!(limited.get(), 3);
+
+        cx.executor().advance_clock(Duration::from_millis(100));
+        source.set(4);
+        cx.run_until_parked();
+        assert_eq!(limited.get(), 4);
+
+        drop(owner);
+        cx.update(|_| {});
+        assert!(!limited.is_alive());
+    }
+
+    #[gpui::test]
+    fn test_sample_on_takes_the_value_when_the_trigger_fires(cx: &mut TestAppContext) {
+        let draft = Signal::new("hel");
+        let sends = Signal::new(0);
+        let owner = cx.new(|_| Owner);
+        let sent = owner.update(cx, |_, cx| draft.sample_on(sends, cx));
+        assert_eq!(sent.get(), "hel");
+
+        draft.set("hello");
+        assert_eq!(sent.get(), "hel");
+        sends.set(1);
+        assert_eq!(sent.get(), "hello");
+        draft.set("");
+        assert_eq!(sent.get(), "hello");
+
+        drop(owner);
+        cx.update(|_| {});
+        assert!(!sent.is_alive());
+        sends.set(2);
+    }
+
+    #[gpui::test]
+    fn test_pairwise_pairs_each_value_with_the_one_before(cx: &mut TestAppContext) {
+        let price = Signal::new(10);
+        let owner = cx.new(|_| Owner);
+        let pairs = owner.update(cx, |_, cx| price.pairwise(cx));
+        assert_eq!(pairs.get(), (None, 10));
+
+        price.set(12);
+        assert_eq!(pairs.get(), (Some(10), 12));
+        price.set(9);
+        assert_eq!(pairs.get(), (Some(12), 9));
+    }
+
+    #[gpui::test]
+    fn test_scan_folds_every_write_until_the_owner_is_released(cx: &mut TestAppContext) {
+        let chunk = Signal::new(5);
+        let owner = cx.new(|_| Owner);
+        let total = owner.update(cx, |_, cx| chunk.scan(0, |total, bytes| total + bytes, cx));
+        assert_eq!(total.get(), 5);
+
+        chunk.set(3);
+        chunk.set(3);
+        assert_eq!(total.get(), 11);
+
+        drop(owner);
+        cx.update(|_| {});
+        assert!(!total.is_alive());
+        chunk.set(1);
+    }
+}
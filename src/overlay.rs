@@ -0,0 +1,178 @@
+//! Popovers and tooltips positioned against a measured element.
+//!
+//! An [`anchored_overlay`] reads its anchor's bounds from a signal, usually one
+//! filled by [`MeasureExt::measure`](crate::measure::MeasureExt::measure), so it
+//! follows the anchor when scrolling or a resize moves it without any bookkeeping in
+//! the view.
+
+use crate::ReadOnlySignal;
+use gpui::{
+    anchored, deferred, div, point, prelude::*, px, AnyElement, App, Bounds, Corner, Pixels, Point,
+    Window,
+};
+
+/// Which side of the anchor an [`AnchoredOverlay`] opens on.
+///
+/// When the overlay would overflow the window on that side, it opens on the
+/// opposite one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Placement {
+    /// Under the anchor, aligned to its left edge.
+    #[default]
+    Below,
+    /// Over the anchor, aligned to its left edge.
+    Above,
+    /// Left of the anchor, aligned to its top edge.
+    Before,
+    /// Right of the anchor, aligned to its top edge.
+    After,
+}
+
+impl Placement {
+    /// The point on `anchor` the overlay attaches to, `gap` away from it, and the
+    /// overlay's corner placed there.
+    fn attach(self, anchor: Bounds<Pixels>, gap: Pixels) -> (Point<Pixels>, Corner) {
+        match self {
+            Self::Below => (anchor.bottom_left() + point(px(0.), gap), Corner::TopLeft),
+            Self::Above => (anchor.origin - point(px(0.), gap), Corner::BottomLeft),
+            Self::Before => (anchor.origin - point(gap, px(0.)), Corner::TopRight),
+            Self::After => (anchor.top_right() + point(gap, px(0.)), Corner::TopLeft),
+        }
+    }
+}
+
+/// Create an overlay showing `content` next to the bounds in `target`.
+///
+/// The overlay is painted above the rest of the window, and nothing is shown until
+/// the anchor has been measured.
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::measure::{measured_bounds, MeasureExt};
+/// use gpui_signals::overlay::{anchored_overlay, Placement};
+/// use gpui_signals::prelude::*;
+///
+/// struct Toolbar {
+///     button: Signal<Bounds<Pixels>>,
+///     open: Signal<bool>,
+/// }
+///
+/// impl Toolbar {
+///     fn new(cx: &mut Context<Self>) -> Self {
+///         Self {
+///             button: measured_bounds(cx),
+///             open: cx.create_signal(true),
+///         }
+///     }
+/// }
+///
+/// impl Render for Toolbar {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         div()
+///             .child(div().child("Share").measure(self.button))
+///             .children(self.open.get().then(|| {
+///                 anchored_overlay(self.button, div().child("Copy link"))
+///                     .placement(Placement::Above)
+///                     .gap(px(4.))
+///             }))
+///     }
+/// }
+/// ```
+pub fn anchored_overlay(
+    target: impl Into<ReadOnlySignal<Bounds<Pixels>>>,
+    content: impl IntoElement,
+) -> AnchoredOverlay {
+    AnchoredOverlay {
+        target: target.into(),
+        placement: Placement::default(),
+        gap: px(0.),
+        content: content.into_any_element(),
+    }
+}
+
+/// An overlay positioned against a measured element. See [`anchored_overlay`].
+#[derive(IntoElement)]
+pub struct AnchoredOverlay {
+    target: ReadOnlySignal<Bounds<Pixels>>,
+    placement: Placement,
+    gap: Pixels,
+    content: AnyElement,
+}
+
+impl AnchoredOverlay {
+    /// Open on `placement`'s side of the anchor.
+    pub fn placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Leave `gap` between the anchor and the overlay.
+    pub fn gap(mut self, gap: Pixels) -> Self {
+        self.gap = gap;
+        self
+    }
+}
+
+impl RenderOnce for AnchoredOverlay {
+    fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        let target = self.target.get();
+        if target == Bounds::default() {
+            return div().into_any_element();
+        }
+        let (position, corner) = self.placement.attach(target, self.gap);
+        deferred(
+            anchored()
+                .position(position)
+                .anchor(corner)
+                .child(self.content),
+        )
+        .into_any_element()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::measure::{measured_bounds, MeasureExt};
+    use crate::{Signal, SignalContext};
+    use gpui::{Context, Render, TestAppContext};
+
+    struct Toolbar {
+        indent: Signal<Pixels>,
+        button: Signal<Bounds<Pixels>>,
+        popover: Signal<Bounds<Pixels>>,
+    }
+
+    impl Render for Toolbar {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .pl(self.indent.get())
+                .pt(px(10.))
+                .child(div().w(px(60.)).h(px(20.)).measure(self.button))
+                .child(
+                    anchored_overlay(
+                        self.button,
+                        div().w(px(40.)).h(px(30.)).measure(self.popover),
+                    )
+                    .gap(px(4.)),
+                )
+        }
+    }
+
+    #[gpui::test]
+    async fn test_overlay_follows_its_anchor(cx: &mut TestAppContext) {
+        let (toolbar, cx) = cx.add_window_view(|_, cx| Toolbar {
+            indent: cx.create_signal(px(10.)),
+            button: measured_bounds(cx),
+            popover: measured_bounds(cx),
+        });
+        cx.run_until_parked();
+        let (indent, popover) =
+            toolbar.read_with(cx, |toolbar, _| (toolbar.indent, toolbar.popover));
+        assert_eq!(popover.get().origin, point(px(10.), px(34.)));
+
+        indent.set(px(50.));
+        cx.run_until_parked();
+        assert_eq!(popover.get().origin, point(px(50.), px(34.)));
+    }
+}
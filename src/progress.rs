@@ -0,0 +1,207 @@
+//! Progress of long-running tasks, reportable from background threads.
+
+use crate::context::track_subscription;
+use crate::{Signal, SignalContext};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use gpui::{Context, SharedString, Subscription};
+
+/// Where a task tracked by a [`Progress`] is in its lifecycle.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum TaskState {
+    /// The task has not started.
+    #[default]
+    Idle,
+    /// The task is running.
+    Running,
+    /// The task completed.
+    Finished,
+    /// The task failed with the given message.
+    Failed(SharedString),
+}
+
+impl TaskState {
+    /// Whether the task is running.
+    pub fn is_running(&self) -> bool {
+        matches!(self, Self::Running)
+    }
+}
+
+enum ProgressUpdate {
+    Fraction(f32),
+    Message(SharedString),
+    State(TaskState),
+}
+
+/// The completed fraction, status message, and state of a long-running task.
+///
+/// The signals live on the foreground thread and are read by views as usual. A task
+/// on another thread reports through a [`ProgressReporter`], whose updates are
+/// applied on the foreground thread in the order they were sent.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+///
+/// struct Import {
+///     progress: Progress,
+/// }
+///
+/// impl Import {
+///     fn new(cx: &mut Context<Self>) -> Self {
+///         let progress = Progress::new(cx);
+///         let reporter = progress.reporter();
+///         cx.background_spawn(async move {
+///             reporter.start();
+///             for row in 0..1000 {
+///                 reporter.set_fraction(row as f32 / 1000.);
+///             }
+///             reporter.finish();
+///         })
+///         .detach();
+///         Self { progress }
+///     }
+/// }
+///
+/// impl Render for Import {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         div().child(self.progress.message())
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Progress {
+    fraction: Signal<f32>,
+    message: Signal<SharedString>,
+    state: Signal<TaskState>,
+    sender: mpsc::UnboundedSender<ProgressUpdate>,
+}
+
+impl Progress {
+    /// Create progress signals owned by the entity, starting idle at zero.
+    pub fn new<V: 'static>(cx: &mut Context<V>) -> Self {
+        let fraction = cx.create_signal(0.);
+        let message = cx.create_signal(SharedString::default());
+        let state = cx.create_signal(TaskState::Idle);
+        let (sender, mut receiver) = mpsc::unbounded::<ProgressUpdate>();
+        let task = cx.spawn(async move |_, _| {
+            while let Some(update) = receiver.next().await {
+                match update {
+                    ProgressUpdate::Fraction(value) => fraction.set_if_changed(value),
+                    ProgressUpdate::Message(text) => message.set_if_changed(text),
+                    ProgressUpdate::State(value) => state.set_if_changed(value),
+                };
+            }
+        });
+        track_subscription(cx, Subscription::new(move || drop(task)));
+        Self {
+            fraction,
+            message,
+            state,
+            sender,
+        }
+    }
+
+    /// The completed fraction, from 0 to 1.
+    pub fn fraction(&self) -> Signal<f32> {
+        self.fraction
+    }
+
+    /// The latest status message.
+    pub fn message(&self) -> Signal<SharedString> {
+        self.message
+    }
+
+    /// The task's state.
+    pub fn state(&self) -> Signal<TaskState> {
+        self.state
+    }
+
+    /// A handle for reporting progress from any thread.
+    pub fn reporter(&self) -> ProgressReporter {
+        ProgressReporter {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+/// Reports to a [`Progress`] from any thread.
+///
+/// Updates are applied on the foreground thread once its executor next runs. Updates
+/// sent after the owning entity is released are dropped.
+#[derive(Clone)]
+pub struct ProgressReporter {
+    sender: mpsc::UnboundedSender<ProgressUpdate>,
+}
+
+impl ProgressReporter {
+    /// Mark the task running from zero.
+    pub fn start(&self) {
+        self.send(ProgressUpdate::Fraction(0.));
+        self.send(ProgressUpdate::State(TaskState::Running));
+    }
+
+    /// Set the completed fraction, clamped to between 0 and 1.
+    pub fn set_fraction(&self, fraction: f32) {
+        self.send(ProgressUpdate::Fraction(fraction.clamp(0., 1.)));
+    }
+
+    /// Set the status message.
+    pub fn set_message(&self, message: impl Into<SharedString>) {
+        self.send(ProgressUpdate::Message(message.into()));
+    }
+
+    /// Mark the task finished, with the fraction at 1.
+    pub fn finish(&self) {
+        self.send(ProgressUpdate::Fraction(1.));
+        self.send(ProgressUpdate::State(TaskState::Finished));
+    }
+
+    /// Mark the task failed with `message`, keeping the fraction it reached.
+    pub fn fail(&self, message: impl Into<SharedString>) {
+        self.send(ProgressUpdate::State(TaskState::Failed(message.into())));
+    }
+
+    fn send(&self, update: ProgressUpdate) {
+        let _ = self.sender.unbounded_send(update);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext, TestAppContext};
+
+    struct Import {
+        progress: Progress,
+    }
+
+    #[gpui::test]
+    async fn test_report_progress_from_another_thread(cx: &mut TestAppContext) {
+        let import = cx.new(|cx| Import {
+            progress: Progress::new(cx),
+        });
+        let progress = import.read_with(cx, |import, _| import.progress.clone());
+
+        let reporter = progress.reporter();
+        std::thread::spawn(move || {
+            reporter.start();
+            reporter.set_fraction(1.5);
+            reporter.set_message("Importing rows");
+            reporter.fail("disk full");
+        })
+        .join()
+        .unwrap();
+        assert_eq!(progress.state().get(), TaskState::Idle);
+
+        cx.run_until_parked();
+        assert_eq!(progress.fraction().get(), 1.);
+        assert_eq!(progress.message().get(), "Importing rows");
+        assert_eq!(
+            progress.state().get(),
+            TaskState::Failed("disk full".into())
+        );
+    }
+}
@@ -0,0 +1,558 @@
+//! Cached async queries refetched by invalidation tags.
+//!
+//! A [`Query`] is a [`Resource`] that keeps its last successful value while it
+//! refetches and carries a list of tags. After a write that affects some data,
+//! [`invalidate_tag`] refetches every live query with that tag, wherever it was
+//! created, so views don't need to know which other views show the same data.
+//! A [`Mutation`] does that invalidation itself once its write succeeds.
+
+use crate::context::{dispose_on_release, track_subscription};
+use crate::{Memo, ReadOnlySignal, Resource, ResourceState, Signal, SignalContext};
+use futures::channel::{mpsc, oneshot};
+use futures::StreamExt;
+use gpui::{App, AsyncApp, Context, SharedString, Subscription};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::rc::Rc;
+
+type Refetch = Rc<dyn Fn(&mut App)>;
+
+thread_local! {
+    static QUERIES: RefCell<HashMap<u64, (Vec<SharedString>, Refetch)>> =
+        RefCell::new(HashMap::new());
+    static NEXT_QUERY: Cell<u64> = const { Cell::new(0) };
+}
+
+/// An async value loaded by a fetcher, cached between fetches and refetched when one
+/// of its tags is invalidated.
+///
+/// Queries are created with `cx.create_query(tags, fetch)`, which starts the first
+/// fetch immediately. [`state`](Self::state) goes back to `Loading` on every refetch,
+/// while [`data`](Self::data) keeps the last successful value, so a list can stay
+/// on screen with a spinner instead of flashing empty.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::queries::{self, Query};
+///
+/// struct TodoList {
+///     todos: Query<Vec<String>, String>,
+/// }
+///
+/// impl TodoList {
+///     fn new(cx: &mut Context<Self>) -> Self {
+///         Self {
+///             todos: cx.create_query(["todos"], |_cx: AsyncApp| async move {
+///                 Ok(vec!["Write docs".to_string()])
+///             }),
+///         }
+///     }
+///
+///     fn todo_added(&mut self, cx: &mut Context<Self>) {
+///         queries::invalidate_tag("todos", cx);
+///     }
+/// }
+///
+/// impl Render for TodoList {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         let todos = self.todos.data().get().unwrap_or_default();
+///         div().children(todos)
+///     }
+/// }
+/// ```
+pub struct Query<T, E> {
+    resource: Resource<T, E>,
+    data: Signal<Option<T>>,
+}
+
+impl<T, E> Copy for Query<T, E> {}
+
+impl<T, E> Clone for Query<T, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, E> PartialEq for Query<T, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.resource == other.resource
+    }
+}
+
+impl<T, E> Eq for Query<T, E> {}
+
+impl<T: Clone + 'static, E: 'static> Query<T, E> {
+    /// Create a query owned by the entity of `cx`, registered under `tags`.
+    pub(crate) fn new<V: 'static, Fut>(
+        tags: Vec<SharedString>,
+        fetch: impl Fn(AsyncApp) -> Fut + 'static,
+        cx: &mut Context<V>,
+    ) -> Self
+    where
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        let data = cx.create_signal(None);
+        let resource = cx.create_resource(fetch);
+        let state = resource.state();
+        state.subscribe(move || {
+            if let Some(value) = state.with_untracked(|state| state.value().cloned()) {
+                data.set(Some(value));
+            }
+        });
+
+        let id = NEXT_QUERY.replace(NEXT_QUERY.get() + 1);
+        let refetch: Refetch = Rc::new(move |cx| resource.refetch(cx));
+        QUERIES.with(|queries| queries.borrow_mut().insert(id, (tags, refetch)));
+        dispose_on_release(cx, move || {
+            let removed = QUERIES.with(|queries| queries.borrow_mut().remove(&id));
+            drop(removed);
+        });
+
+        Self { resource, data }
+    }
+
+    /// The signal holding the state of the latest fetch.
+    pub fn state(&self) -> ReadOnlySignal<ResourceState<T, E>> {
+        self.resource.state()
+    }
+
+    /// The value of the last successful fetch, kept while refetching and after a
+    /// failed fetch.
+    pub fn data(&self) -> ReadOnlySignal<Option<T>> {
+        self.data.read_only()
+    }
+
+    /// Whether a fetch is in flight.
+    pub fn is_fetching(&self) -> bool {
+        self.resource.is_loading()
+    }
+
+    /// Run the fetcher again.
+    pub fn refetch(&self, cx: &mut App) {
+        self.resource.refetch(cx);
+    }
+}
+
+impl<T: fmt::Debug + Clone + 'static, E: fmt::Debug + Clone + 'static> fmt::Debug for Query<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Query")
+            .field("state", &self.resource.state().get_untracked())
+            .field("data", &self.data.get_untracked())
+            .finish()
+    }
+}
+
+type Runner<A> = Rc<dyn Fn(A, &mut App) -> oneshot::Receiver<()>>;
+
+#[derive(Clone)]
+struct Offline {
+    online: ReadOnlySignal<bool>,
+    wake: mpsc::UnboundedSender<()>,
+}
+
+/// An async write that refetches the queries it affects once it succeeds.
+///
+/// Mutations are created with `cx.create_mutation(mutate)` and started with
+/// [`run`](Self::run). Tags given to [`invalidates`](Self::invalidates) are passed
+/// to [`invalidate_tag`] after every successful run, so a view that adds a todo only
+/// declares that todos changed.
+///
+/// With [`queue_offline`](Self::queue_offline), runs started while a network-status
+/// signal reports offline are queued instead and replayed in order once it reports
+/// online again. The queue is a signal, so it can be saved and handed back to
+/// [`restore_queue`](Self::restore_queue) on the next launch.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::queries::Mutation;
+///
+/// struct NewTodo {
+///     add: Mutation<String, String>,
+/// }
+///
+/// impl NewTodo {
+///     fn new(cx: &mut Context<Self>) -> Self {
+///         let add = cx.create_mutation(|title: String, _cx: AsyncApp| async move {
+///             println!("saving {title}");
+///             Ok::<_, String>(())
+///         });
+///         Self {
+///             add: add.invalidates(["todos"]),
+///         }
+///     }
+///
+///     fn submit(&mut self, title: String, cx: &mut Context<Self>) {
+///         self.add.run(title, cx);
+///     }
+/// }
+/// ```
+pub struct Mutation<A, E> {
+    runner: Signal<Runner<A>>,
+    pending: Signal<usize>,
+    last_error: Signal<Option<E>>,
+    tags: Signal<Vec<SharedString>>,
+    offline: Signal<Option<Offline>>,
+    queue: Signal<Vec<A>>,
+    queued_count: Memo<usize>,
+}
+
+impl<A, E> Copy for Mutation<A, E> {}
+
+impl<A, E> Clone for Mutation<A, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A, E> PartialEq for Mutation<A, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.runner == other.runner
+    }
+}
+
+impl<A, E> Eq for Mutation<A, E> {}
+
+impl<A: 'static, E: 'static> Mutation<A, E> {
+    /// Create a mutation owned by the entity of `cx`.
+    pub(crate) fn new<V: 'static, T, Fut>(
+        mutate: impl Fn(A, AsyncApp) -> Fut + 'static,
+        cx: &mut Context<V>,
+    ) -> Self
+    where
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        let pending = cx.create_signal(0);
+        let last_error = cx.create_signal(None);
+        let tags: Signal<Vec<SharedString>> = cx.create_signal(Vec::new());
+        let owner = Rc::new(());
+        let runner: Runner<A> = Rc::new(move |args, cx| {
+            let owner = Rc::downgrade(&owner);
+            let mutation = mutate(args, cx.to_async());
+            let (done, finished) = oneshot::channel();
+            pending.update(|count| *count += 1);
+            // Detached rather than cancelled on release, so a write that already
+            // started isn't abandoned halfway; only the bookkeeping is skipped.
+            cx.spawn(async move |cx| {
+                let result = mutation.await;
+                if owner.upgrade().is_none() {
+                    return;
+                }
+                pending.update(|count| *count -= 1);
+                match result {
+                    Ok(_) => {
+                        last_error.set(None);
+                        let tags = tags.get_untracked();
+                        cx.update(|cx| {
+                            for tag in &tags {
+                                invalidate_tag(tag, cx);
+                            }
+                        })
+                        .ok();
+                    }
+                    Err(error) => last_error.set(Some(error)),
+                }
+                done.send(()).ok();
+            })
+            .detach();
+            finished
+        });
+        let runner = cx.create_signal(runner);
+        let queue: Signal<Vec<A>> = cx.create_signal(Vec::new());
+
+        Self {
+            runner,
+            pending,
+            last_error,
+            tags,
+            offline: cx.create_signal(None),
+            queue,
+            queued_count: cx.create_memo(move || queue.with(Vec::len)),
+        }
+    }
+
+    /// Set the tags to invalidate after each successful run.
+    pub fn invalidates(self, tags: impl IntoIterator<Item = impl Into<SharedString>>) -> Self {
+        self.tags.set(tags.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Queue runs while `online` is false, and replay them in order once it is true.
+    ///
+    /// Replayed runs go one at a time, each starting after the previous one finished,
+    /// and replay stops early if `online` turns false again. A replayed run that
+    /// fails is reported through [`last_error`](Self::last_error) and not retried.
+    pub fn queue_offline<V: 'static>(
+        self,
+        online: impl Into<ReadOnlySignal<bool>>,
+        cx: &mut Context<V>,
+    ) -> Self {
+        let online = online.into();
+        let (wake, mut woken) = mpsc::unbounded();
+        let task = cx.spawn(async move |_, cx| {
+            while let Some(()) = woken.next().await {
+                self.replay(cx).await;
+            }
+        });
+        track_subscription(cx, Subscription::new(move || drop(task)));
+        online.subscribe_while({
+            let wake = wake.clone();
+            move || {
+                if online.get_untracked() {
+                    wake.unbounded_send(()).is_ok()
+                } else {
+                    !wake.is_closed()
+                }
+            }
+        });
+        self.offline.set(Some(Offline { online, wake }));
+        self.wake_if_online();
+        self
+    }
+
+    /// Start the mutation with `args`.
+    ///
+    /// Runs started while another is pending proceed concurrently. With an offline
+    /// queue, the run is queued instead while offline or while earlier runs are still
+    /// waiting to be replayed.
+    pub fn run(&self, args: A, cx: &mut App) {
+        if !self.is_online() || self.queue.with_untracked(|queue| !queue.is_empty()) {
+            self.queue.update(|queue| queue.push(args));
+            return;
+        }
+        drop(self.start(args, cx));
+    }
+
+    /// The number of queued runs, for a badge such as "3 changes waiting to sync".
+    pub fn queued_count(&self) -> Memo<usize> {
+        self.queued_count
+    }
+
+    /// The arguments of the queued runs, oldest first, to save across launches.
+    pub fn queued(&self) -> ReadOnlySignal<Vec<A>> {
+        self.queue.read_only()
+    }
+
+    /// Put runs saved from an earlier [`queued`](Self::queued) ahead of any queued
+    /// since, replaying them if online.
+    pub fn restore_queue(&self, queued: Vec<A>) {
+        self.queue.update(|queue| {
+            queue.splice(0..0, queued);
+        });
+        self.wake_if_online();
+    }
+
+    /// Whether a run is in flight.
+    pub fn is_pending(&self) -> bool {
+        self.pending.get() > 0
+    }
+
+    /// The signal holding the error of the last completed run, cleared when a run
+    /// succeeds.
+    pub fn last_error(&self) -> ReadOnlySignal<Option<E>> {
+        self.last_error.read_only()
+    }
+
+    fn start(&self, args: A, cx: &mut App) -> oneshot::Receiver<()> {
+        let runner = self.runner.with_untracked(Rc::clone);
+        runner(args, cx)
+    }
+
+    fn is_online(&self) -> bool {
+        let online = self
+            .offline
+            .with_untracked(|offline| offline.as_ref().map(|offline| offline.online));
+        online.is_none_or(|online| online.get_untracked())
+    }
+
+    fn wake_if_online(&self) {
+        let offline = self.offline.get_untracked();
+        if let Some(offline) = offline.filter(|offline| offline.online.get_untracked()) {
+            offline.wake.unbounded_send(()).ok();
+        }
+    }
+
+    async fn replay(self, cx: &mut AsyncApp) {
+        while self.is_online() {
+            let next = self
+                .queue
+                .update_with(|queue| (!queue.is_empty()).then(|| queue.remove(0)));
+            let Some(Some(args)) = next else {
+                break;
+            };
+            let Ok(finished) = cx.update(|cx| self.start(args, cx)) else {
+                break;
+            };
+            finished.await.ok();
+        }
+    }
+}
+
+impl<A, E: fmt::Debug + Clone + 'static> fmt::Debug for Mutation<A, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mutation")
+            .field("pending", &self.pending.get_untracked())
+            .field("last_error", &self.last_error.get_untracked())
+            .field("queued", &self.queued_count.get_untracked())
+            .finish()
+    }
+}
+
+/// Refetch every live query tagged with `tag`.
+pub fn invalidate_tag(tag: &str, cx: &mut App) {
+    // Collected first, since a refetch may create or release queries.
+    let refetches: Vec<Refetch> = QUERIES.with(|queries| {
+        queries
+            .borrow()
+            .values()
+            .filter(|(tags, _)| tags.iter().any(|candidate| candidate == tag))
+            .map(|(_, refetch)| refetch.clone())
+            .collect()
+    });
+    for refetch in refetches {
+        refetch(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{div, AppContext, IntoElement, Render, TestAppContext, Window};
+
+    struct Lists {
+        todos: Query<usize, String>,
+        users: Query<usize, String>,
+    }
+
+    impl Render for Lists {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div()
+        }
+    }
+
+    struct Editor {
+        todos: Query<usize, String>,
+        save: Mutation<bool, String>,
+    }
+
+    impl Render for Editor {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div()
+        }
+    }
+
+    #[gpui::test]
+    async fn test_mutation_invalidates_tags_on_success(cx: &mut TestAppContext) {
+        let fetches = Rc::new(Cell::new(0));
+        let editor = cx.new(|cx| {
+            let fetches = fetches.clone();
+            Editor {
+                todos: cx.create_query(["todos"], move |_| {
+                    fetches.set(fetches.get() + 1);
+                    let count = fetches.get();
+                    async move { Ok(count) }
+                }),
+                save: cx
+                    .create_mutation(|succeed: bool, _| async move {
+                        succeed.then_some(()).ok_or_else(|| "offline".to_string())
+                    })
+                    .invalidates(["todos"]),
+            }
+        });
+        let (todos, save) = editor.read_with(cx, |editor, _| (editor.todos, editor.save));
+        cx.run_until_parked();
+
+        cx.update(|cx| save.run(false, cx));
+        assert!(save.is_pending());
+        cx.run_until_parked();
+        assert!(!save.is_pending());
+        assert_eq!(save.last_error().get(), Some("offline".to_string()));
+        assert_eq!(todos.data().get(), Some(1));
+
+        cx.update(|cx| save.run(true, cx));
+        cx.run_until_parked();
+        assert_eq!(save.last_error().get(), None);
+        assert_eq!(todos.data().get(), Some(2));
+    }
+
+    struct Outbox {
+        send: Mutation<u32, String>,
+    }
+
+    impl Render for Outbox {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div()
+        }
+    }
+
+    #[gpui::test]
+    async fn test_offline_runs_replay_in_order(cx: &mut TestAppContext) {
+        let online = Signal::new(false);
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        let outbox = cx.new(|cx| {
+            let sent = sent.clone();
+            let send = cx.create_mutation(move |message: u32, _| {
+                sent.borrow_mut().push(message);
+                async move { Ok::<_, String>(()) }
+            });
+            Outbox {
+                send: send.queue_offline(online, cx),
+            }
+        });
+        let send = outbox.read_with(cx, |outbox, _| outbox.send);
+
+        cx.update(|cx| {
+            send.run(2, cx);
+            send.run(3, cx);
+        });
+        send.restore_queue(vec![1]);
+        cx.run_until_parked();
+        assert_eq!(send.queued_count().get(), 3);
+        assert!(sent.borrow().is_empty());
+
+        online.set(true);
+        cx.run_until_parked();
+        assert_eq!(*sent.borrow(), [1, 2, 3]);
+        assert_eq!(send.queued_count().get(), 0);
+    }
+
+    #[gpui::test]
+    async fn test_invalidate_tag_refetches_tagged_queries(cx: &mut TestAppContext) {
+        let fetches = Rc::new(Cell::new(0));
+        let lists = cx.new(|cx| {
+            let counter = |fetches: Rc<Cell<usize>>| {
+                move |_| {
+                    fetches.set(fetches.get() + 1);
+                    let count = fetches.get();
+                    async move { Ok(count) }
+                }
+            };
+            Lists {
+                todos: cx.create_query(["todos", "home"], counter(fetches.clone())),
+                users: cx.create_query(["users"], counter(fetches.clone())),
+            }
+        });
+        let (todos, users) = lists.read_with(cx, |lists, _| (lists.todos, lists.users));
+        cx.run_until_parked();
+        let before = (todos.data().get(), users.data().get());
+
+        cx.update(|cx| invalidate_tag("todos", cx));
+        assert!(todos.is_fetching());
+        assert_eq!(todos.data().get(), before.0);
+        cx.run_until_parked();
+        assert_eq!(todos.data().get(), Some(3));
+        assert_eq!(users.data().get(), before.1);
+
+        drop(lists);
+        cx.update(|_| {});
+        cx.update(|cx| invalidate_tag("todos", cx));
+        assert_eq!(fetches.get(), 3);
+    }
+}
@@ -0,0 +1,189 @@
+//! A flight recorder that logs signal writes to disk.
+
+use crate::ReadOnlySignal;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024;
+const DEFAULT_MAX_FILES: usize = 3;
+
+/// Appends the writes of named signals to a rotating log file.
+///
+/// Each write is logged as a line holding the time in milliseconds since the Unix
+/// epoch, the signal's name, and its new value formatted with `Debug`. Lines go
+/// straight to the file without buffering, so the log is intact up to the last write
+/// before a crash. When the file would grow past its size limit it is renamed to
+/// `<path>.1`, older logs shift to `<path>.2` and so on, and the oldest is deleted.
+///
+/// Recording stops once the recorder and all its clones are dropped. Errors writing
+/// the log are ignored so recording never disrupts the app.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::FlightRecorder;
+///
+/// let recorder = FlightRecorder::new("state.log")?.max_file_size(256 * 1024);
+/// let selection = Signal::new(Some(3usize));
+/// recorder.record("selection", selection);
+///
+/// selection.set(None);
+/// // state.log now ends with a line like `1767225600000 selection = None`.
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct FlightRecorder {
+    log: Rc<Log>,
+}
+
+struct Log {
+    path: PathBuf,
+    file: RefCell<File>,
+    len: Cell<u64>,
+    max_file_size: Cell<u64>,
+    max_files: Cell<usize>,
+}
+
+impl FlightRecorder {
+    /// Open a recorder appending to the log at `path`.
+    ///
+    /// An existing log is continued, so the entries leading up to a crash survive
+    /// the restart. Logs rotate at 1 MiB, keeping 3 older files.
+    pub fn new(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            log: Rc::new(Log {
+                path,
+                file: RefCell::new(file),
+                len: Cell::new(len),
+                max_file_size: Cell::new(DEFAULT_MAX_FILE_SIZE),
+                max_files: Cell::new(DEFAULT_MAX_FILES),
+            }),
+        })
+    }
+
+    /// Rotate the log once it would grow past `bytes`.
+    pub fn max_file_size(self, bytes: u64) -> Self {
+        self.log.max_file_size.set(bytes);
+        self
+    }
+
+    /// Keep `count` rotated logs besides the current one.
+    pub fn max_files(self, count: usize) -> Self {
+        self.log.max_files.set(count);
+        self
+    }
+
+    /// Log every write to `signal` under `name`.
+    pub fn record<T: fmt::Debug + 'static>(
+        &self,
+        name: impl Into<String>,
+        signal: impl Into<ReadOnlySignal<T>>,
+    ) {
+        let name = name.into();
+        let signal = signal.into();
+        let log = Rc::downgrade(&self.log);
+        signal.subscribe_while(move || {
+            let Some(log) = Weak::upgrade(&log) else {
+                return false;
+            };
+            let entry = signal.with_untracked(|value| format!("{name} = {value:?}"));
+            let _ = log.append(&entry);
+            true
+        });
+    }
+
+    /// The path of the current log.
+    pub fn path(&self) -> &Path {
+        &self.log.path
+    }
+}
+
+impl fmt::Debug for FlightRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FlightRecorder")
+            .field("path", &self.log.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Log {
+    fn append(&self, entry: &str) -> io::Result<()> {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis());
+        let line = format!("{millis} {entry}\n");
+        let line_len = line.len() as u64;
+        if self.len.get() > 0 && self.len.get() + line_len > self.max_file_size.get() {
+            self.rotate()?;
+        }
+        self.file.borrow_mut().write_all(line.as_bytes())?;
+        self.len.set(self.len.get() + line_len);
+        Ok(())
+    }
+
+    fn rotate(&self) -> io::Result<()> {
+        let max_files = self.max_files.get();
+        if max_files > 0 {
+            let _ = fs::remove_file(self.rotated_path(max_files));
+            for index in (1..max_files).rev() {
+                let from = self.rotated_path(index);
+                if from.exists() {
+                    fs::rename(from, self.rotated_path(index + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        *self.file.borrow_mut() = File::create(&self.path)?;
+        self.len.set(0);
+        Ok(())
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        path.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Signal;
+
+    #[test]
+    fn test_records_writes_and_rotates() {
+        let directory = std::env::temp_dir().join(format!("gpui_signals_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("flight.log");
+        let recorder = FlightRecorder::new(&path)
+            .unwrap()
+            .max_file_size(64)
+            .max_files(1);
+
+        let count = Signal::new(0);
+        recorder.record("count", count);
+        for value in 1..=6 {
+            count.set(value);
+        }
+
+        let current = fs::read_to_string(&path).unwrap();
+        let rotated = fs::read_to_string(directory.join("flight.log.1")).unwrap();
+        assert!(current.ends_with("count = 6\n"));
+        assert!(rotated.contains("count = 3\n"));
+        assert!(!rotated.contains("count = 1\n"));
+
+        drop(recorder);
+        count.set(7);
+        assert!(!fs::read_to_string(&path).unwrap().contains("count = 7"));
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
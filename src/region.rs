@@ -0,0 +1,167 @@
+//! Regions of a view that re-render independently of the rest of the view.
+
+use crate::SignalContext;
+use gpui::{
+    AnyElement, AnyView, App, AppContext, Context, ElementId, Entity, IntoElement, Render,
+    RenderOnce, StyleRefinement, Styled, Window,
+};
+use std::rc::Rc;
+
+type RegionRender = Rc<dyn Fn(&mut Window, &mut App) -> AnyElement>;
+
+/// An element whose subtree re-renders on its own when the signals it reads change.
+///
+/// Signals read inside the render closure subscribe only this region, and its output
+/// is cached between frames. A change therefore re-renders just this subtree instead
+/// of the whole entity, which keeps large views responsive. Sibling regions that did
+/// not change reuse their previous layout and paint.
+///
+/// Because output is cached, the closure should read anything that can change through
+/// signals or memos rather than capturing plain values. Style methods such as
+/// `size_full()` apply to the region's root and are needed to lay out cached content.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+///
+/// struct Dashboard {
+///     count: Signal<i32>,
+/// }
+///
+/// impl Render for Dashboard {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         let count = self.count;
+///         div().child(
+///             ReactiveRegion::new("count", move |_, _| div().child(count.get().to_string()))
+///                 .w_full(),
+///         )
+///     }
+/// }
+/// ```
+#[derive(IntoElement)]
+pub struct ReactiveRegion {
+    id: ElementId,
+    style: StyleRefinement,
+    render: RegionRender,
+}
+
+impl ReactiveRegion {
+    /// Create a region identified by `id` that renders with `render`.
+    ///
+    /// The id must be unique among its siblings so the region keeps its state
+    /// across frames.
+    pub fn new<E: IntoElement>(
+        id: impl Into<ElementId>,
+        render: impl Fn(&mut Window, &mut App) -> E + 'static,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            style: StyleRefinement::default(),
+            render: Rc::new(move |window, cx| render(window, cx).into_any_element()),
+        }
+    }
+}
+
+impl Styled for ReactiveRegion {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for ReactiveRegion {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let render = self.render;
+        // Keep the region entity in element state rather than `use_keyed_state`, which
+        // would notify the parent view whenever the region changes.
+        let view = window.with_global_id(self.id, |global_id, window| {
+            window.with_element_state(global_id, |state: Option<Entity<RegionView>>, _| {
+                let view = match state {
+                    Some(view) => {
+                        view.update(cx, |view, _| view.render = render);
+                        view
+                    }
+                    None => cx.new(|_| RegionView { render }),
+                };
+                (view.clone(), view)
+            })
+        });
+        AnyView::from(view).cached(self.style)
+    }
+}
+
+struct RegionView {
+    render: RegionRender,
+}
+
+impl Render for RegionView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let render = self.render.clone();
+        cx.track_render(|cx| render(window, cx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Signal;
+    use gpui::{div, ParentElement, TestAppContext};
+    use std::cell::Cell;
+
+    struct Parent {
+        left: Signal<i32>,
+        right: Signal<i32>,
+        left_renders: Rc<Cell<usize>>,
+        right_renders: Rc<Cell<usize>>,
+    }
+
+    impl Render for Parent {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            let (left, left_renders) = (self.left, self.left_renders.clone());
+            let (right, right_renders) = (self.right, self.right_renders.clone());
+            div()
+                .child(
+                    ReactiveRegion::new("left", move |_, _| {
+                        left_renders.set(left_renders.get() + 1);
+                        div().child(left.get().to_string())
+                    })
+                    .size_full(),
+                )
+                .child(
+                    ReactiveRegion::new("right", move |_, _| {
+                        right_renders.set(right_renders.get() + 1);
+                        div().child(right.get().to_string())
+                    })
+                    .size_full(),
+                )
+        }
+    }
+
+    #[gpui::test]
+    async fn test_region_rerenders_independently(cx: &mut TestAppContext) {
+        let left = Signal::new(0);
+        let right = Signal::new(0);
+        let left_renders = Rc::new(Cell::new(0));
+        let right_renders = Rc::new(Cell::new(0));
+
+        let (_parent, cx) = cx.add_window_view(|_, _| Parent {
+            left,
+            right,
+            left_renders: left_renders.clone(),
+            right_renders: right_renders.clone(),
+        });
+        assert_eq!(left_renders.get(), 1);
+        assert_eq!(right_renders.get(), 1);
+
+        left.set(1);
+        cx.run_until_parked();
+        assert_eq!(left_renders.get(), 2);
+        assert_eq!(right_renders.get(), 1, "unchanged region reuses its output");
+
+        right.set(1);
+        cx.run_until_parked();
+        assert_eq!(left_renders.get(), 2);
+        assert_eq!(right_renders.get(), 2);
+    }
+}
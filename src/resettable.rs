@@ -0,0 +1,193 @@
+//! Signals that remember a default value to reset to.
+
+use crate::runtime::SignalRuntime;
+use crate::signal::{ReadOnlySignal, Signal};
+use crate::Memo;
+use std::fmt;
+
+/// A signal with a default value, which it can be reset to and is compared against.
+///
+/// Settings panels and filter bars use this to show a "modified" marker and a reset
+/// button per field without keeping the defaults around separately.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+///
+/// struct FilterBar {
+///     query: ResettableSignal<String>,
+/// }
+///
+/// impl Render for FilterBar {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         let query = self.query;
+///         div()
+///             .child(query.value())
+///             .when(query.is_modified().get(), |this| {
+///                 this.child(
+///                     div()
+///                         .child("Clear")
+///                         .on_mouse_down(MouseButton::Left, move |_, _, _| query.reset()),
+///                 )
+///             })
+///     }
+/// }
+///
+/// fn new_filter_bar(cx: &mut Context<FilterBar>) -> FilterBar {
+///     FilterBar {
+///         query: cx.create_signal_with_reset(String::new()),
+///     }
+/// }
+/// ```
+pub struct ResettableSignal<T> {
+    value: Signal<T>,
+    default: Signal<T>,
+    modified: Memo<bool>,
+}
+
+impl<T> Copy for ResettableSignal<T> {}
+
+impl<T> Clone for ResettableSignal<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for ResettableSignal<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T> Eq for ResettableSignal<T> {}
+
+impl<T: 'static + Clone + PartialEq> ResettableSignal<T> {
+    /// Create a signal starting at `default`.
+    pub fn new(default: T) -> Self {
+        Self::new_in(default, &SignalRuntime::default())
+    }
+
+    /// Create a signal starting at `default` in `runtime`.
+    pub fn new_in(default: T, runtime: &SignalRuntime) -> Self {
+        let value = Signal::new_in(default.clone(), runtime);
+        let default = Signal::new_in(default, runtime);
+        Self::from_parts(
+            value,
+            default,
+            Memo::new_in(move || Self::differs(value, default), runtime),
+        )
+    }
+
+    pub(crate) fn from_parts(value: Signal<T>, default: Signal<T>, modified: Memo<bool>) -> Self {
+        Self {
+            value,
+            default,
+            modified,
+        }
+    }
+
+    /// Whether `value` differs from `default`, tracking both reads.
+    pub(crate) fn differs(value: Signal<T>, default: Signal<T>) -> bool {
+        // Cloned first, since a signal can't be read while another one's value is
+        // borrowed.
+        let default = default.get();
+        value.with(|value| *value != default)
+    }
+
+    /// The current value.
+    pub fn value(&self) -> ReadOnlySignal<T> {
+        self.value.read_only()
+    }
+
+    /// A memo tracking whether the value differs from the default.
+    pub fn is_modified(&self) -> Memo<bool> {
+        self.modified
+    }
+
+    /// The default value.
+    pub fn default_value(&self) -> T {
+        self.default.get_untracked()
+    }
+
+    /// Get the current value, tracking the read.
+    pub fn get(&self) -> T {
+        self.value.get()
+    }
+
+    /// Get the current value without tracking the read.
+    pub fn get_untracked(&self) -> T {
+        self.value.get_untracked()
+    }
+
+    /// Read the value with a closure, tracking the read.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.value.with(f)
+    }
+
+    /// Set a new value.
+    #[track_caller]
+    pub fn set(&self, value: T) {
+        self.value.set(value);
+    }
+
+    /// Update the value with a closure.
+    #[track_caller]
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        self.value.update(f);
+    }
+
+    /// Set the value back to the default, if it differs.
+    #[track_caller]
+    pub fn reset(&self) {
+        let default = self.default.get_untracked();
+        self.value.set_if_changed(default);
+    }
+
+    /// Change the default, for example after saving settings, so the current value is
+    /// no longer reported as modified.
+    pub fn set_default(&self, default: T) {
+        self.default.set(default);
+    }
+
+    /// Free the value, default, and memo.
+    pub fn dispose(self) {
+        self.modified.signal().dispose();
+        self.value.dispose();
+        self.default.dispose();
+    }
+}
+
+impl<T: 'static + Clone + PartialEq + fmt::Debug> fmt::Debug for ResettableSignal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResettableSignal")
+            .field("value", &self.value.get_untracked())
+            .field("default", &self.default.get_untracked())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_restores_default_and_clears_modified() {
+        let font_size = ResettableSignal::new(14);
+        assert!(!font_size.is_modified().get());
+
+        font_size.set(18);
+        assert!(font_size.is_modified().get());
+        font_size.reset();
+        assert_eq!(font_size.get(), 14);
+        assert!(!font_size.is_modified().get());
+
+        font_size.set(16);
+        font_size.set_default(16);
+        assert!(!font_size.is_modified().get());
+        font_size.set(14);
+        font_size.reset();
+        assert_eq!(font_size.get(), 16);
+    }
+}
@@ -0,0 +1,672 @@
+//! Async resources and the elements that render them.
+
+use crate::storage::{with_observer, with_signal_storage, SignalId};
+use crate::{ReadOnlySignal, Signal};
+use gpui::{
+    div, prelude::*, rgb, AnyElement, App, AsyncApp, Div, ElementId, Refineable, SharedString,
+    StyleRefinement, Task, Window,
+};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::rc::Rc;
+
+type Fetcher = Rc<dyn Fn(&mut App)>;
+
+/// The state of a [`Resource`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResourceState<T, E> {
+    /// A fetch is in flight.
+    Loading,
+    /// The last fetch succeeded.
+    Ready(T),
+    /// The last fetch failed.
+    Failed(E),
+}
+
+impl<T, E> ResourceState<T, E> {
+    /// Whether a fetch is in flight.
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Self::Loading)
+    }
+
+    /// The fetched value, if the last fetch succeeded.
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Self::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The error, if the last fetch failed.
+    pub fn error(&self) -> Option<&E> {
+        match self {
+            Self::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// A value loaded by an async fetcher, exposed as a signal.
+///
+/// Resources are created with `cx.create_resource(fetch)`, which starts the first
+/// fetch immediately. The state goes back to [`ResourceState::Loading`] on every
+/// [`refetch`](Self::refetch), and a refetch cancels any fetch still in flight.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+/// use std::time::Duration;
+///
+/// struct Profile {
+///     user: Resource<String, String>,
+/// }
+///
+/// impl Profile {
+///     fn new(cx: &mut Context<Self>) -> Self {
+///         Self {
+///             user: cx.create_resource(|cx: AsyncApp| async move {
+///                 cx.background_executor().timer(Duration::from_secs(1)).await;
+///                 Ok("Alice".to_string())
+///             }),
+///         }
+///     }
+/// }
+/// ```
+pub struct Resource<T, E> {
+    state: Signal<ResourceState<T, E>>,
+    fetcher: Signal<Fetcher>,
+}
+
+impl<T, E> Copy for Resource<T, E> {}
+
+impl<T, E> Clone for Resource<T, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, E> PartialEq for Resource<T, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.state == other.state
+    }
+}
+
+impl<T, E> Eq for Resource<T, E> {}
+
+impl<T: 'static, E: 'static> Resource<T, E> {
+    /// Create a resource that stores results in `state` and start fetching.
+    pub(crate) fn new<Fut>(
+        state: Signal<ResourceState<T, E>>,
+        fetch: impl Fn(AsyncApp) -> Fut + 'static,
+        cx: &mut App,
+    ) -> Self
+    where
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        let in_flight: Rc<RefCell<Option<Task<()>>>> = Rc::default();
+        let fetcher: Fetcher = Rc::new(move |cx: &mut App| {
+            state.set(ResourceState::Loading);
+            let fetch = fetch(cx.to_async());
+            // Replacing the previous task drops it, which cancels a stale fetch.
+            *in_flight.borrow_mut() = Some(cx.spawn(async move |_| {
+                state.set(match fetch.await {
+                    Ok(value) => ResourceState::Ready(value),
+                    Err(error) => ResourceState::Failed(error),
+                });
+            }));
+        });
+        let resource = Self {
+            state,
+            fetcher: Signal::new(fetcher),
+        };
+        resource.refetch(cx);
+        resource
+    }
+
+    /// Free the state and fetcher signals, cancelling an in-flight fetch.
+    pub(crate) fn dispose(self) {
+        self.state.dispose();
+        self.fetcher.dispose();
+    }
+
+    /// The signal holding the resource's state.
+    pub fn state(&self) -> ReadOnlySignal<ResourceState<T, E>> {
+        self.state.read_only()
+    }
+
+    /// Get the current state.
+    pub fn get(&self) -> ResourceState<T, E>
+    where
+        T: Clone,
+        E: Clone,
+    {
+        self.state.get()
+    }
+
+    /// Read the current state with a closure.
+    pub fn with<R>(&self, f: impl FnOnce(&ResourceState<T, E>) -> R) -> R {
+        self.state.with(f)
+    }
+
+    /// Whether a fetch is in flight.
+    pub fn is_loading(&self) -> bool {
+        self.state.with(ResourceState::is_loading)
+    }
+
+    /// Run the fetcher again.
+    pub fn refetch(&self, cx: &mut App) {
+        let fetcher = self.fetcher.with_untracked(Rc::clone);
+        fetcher(cx);
+    }
+}
+
+impl<T: fmt::Debug + Clone + 'static, E: fmt::Debug + Clone + 'static> fmt::Debug
+    for Resource<T, E>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Resource")
+            .field("state", &self.state.get_untracked())
+            .finish()
+    }
+}
+
+enum SuspenseStatus {
+    Loading,
+    Ready,
+    Failed(SharedString),
+}
+
+type StatusCheck = Box<dyn Fn() -> SuspenseStatus>;
+type ContentRender = Box<dyn FnOnce(&mut Window, &mut App) -> AnyElement>;
+type ErrorRender = Box<dyn FnOnce(SharedString, &mut Window, &mut App) -> AnyElement>;
+
+/// Renders a fallback until every resource it waits on is ready.
+///
+/// While any resource is loading, the fallback is shown. Once all are ready, the
+/// content closure runs, so it can read resource values without handling the loading
+/// case. If a resource failed and an error view was given, the error view is shown
+/// with the first error; otherwise the content renders and can inspect the errors.
+///
+/// The closures run after the enclosing render returns, but reads inside them are
+/// still tracked by the scope the `Suspense` was created in.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+///
+/// fn profile(user: Resource<String, String>) -> impl IntoElement {
+///     Suspense::new(move |_, _| {
+///         div().child(user.with(|state| state.value().cloned().unwrap_or_default()))
+///     })
+///     .resource(user)
+///     .fallback(div().child("Loading..."))
+///     .error(|message, _, _| div().child(message))
+/// }
+/// ```
+#[derive(IntoElement)]
+pub struct Suspense {
+    observer: Option<SignalId>,
+    resources: Vec<StatusCheck>,
+    content: ContentRender,
+    fallback: Option<AnyElement>,
+    error: Option<ErrorRender>,
+    style: StyleRefinement,
+}
+
+impl Suspense {
+    /// Create a suspense boundary that renders `content` once its resources are ready.
+    pub fn new<E: IntoElement>(content: impl FnOnce(&mut Window, &mut App) -> E + 'static) -> Self {
+        Self {
+            observer: with_signal_storage(|storage| storage.observer()),
+            resources: Vec::new(),
+            content: Box::new(move |window, cx| content(window, cx).into_any_element()),
+            fallback: None,
+            error: None,
+            style: StyleRefinement::default(),
+        }
+    }
+
+    /// Wait for `resource` before rendering the content.
+    pub fn resource<T: 'static, E: fmt::Display + 'static>(
+        mut self,
+        resource: Resource<T, E>,
+    ) -> Self {
+        self.resources.push(Box::new(move || {
+            resource.with(|state| match state {
+                ResourceState::Loading => SuspenseStatus::Loading,
+                ResourceState::Ready(_) => SuspenseStatus::Ready,
+                ResourceState::Failed(error) => SuspenseStatus::Failed(error.to_string().into()),
+            })
+        }));
+        self
+    }
+
+    /// The element shown while any resource is loading.
+    pub fn fallback(mut self, fallback: impl IntoElement) -> Self {
+        self.fallback = Some(fallback.into_any_element());
+        self
+    }
+
+    /// The view shown when a resource failed, given the first error message.
+    pub fn error<E: IntoElement>(
+        mut self,
+        render: impl FnOnce(SharedString, &mut Window, &mut App) -> E + 'static,
+    ) -> Self {
+        self.error = Some(Box::new(move |message, window, cx| {
+            render(message, window, cx).into_any_element()
+        }));
+        self
+    }
+}
+
+impl Styled for Suspense {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for Suspense {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        with_observer(self.observer, || self.render_content(window, cx))
+    }
+}
+
+impl Suspense {
+    fn render_content(self, window: &mut Window, cx: &mut App) -> Div {
+        let mut loading = false;
+        let mut first_error = None;
+        // Check every resource, not just up to the first pending one, so the
+        // enclosing scope subscribes to all of them.
+        for status in self.resources.iter().map(|check| check()) {
+            match status {
+                SuspenseStatus::Loading => loading = true,
+                SuspenseStatus::Failed(message) => {
+                    first_error.get_or_insert(message);
+                }
+                SuspenseStatus::Ready => {}
+            }
+        }
+
+        let mut root = div();
+        root.style().refine(&self.style);
+        if loading {
+            root.children(self.fallback)
+        } else if let (Some(message), Some(error)) = (first_error, self.error) {
+            root.child(error(message, window, cx))
+        } else {
+            root.child((self.content)(window, cx))
+        }
+    }
+}
+
+type FailureCheck = Box<dyn Fn() -> Option<SharedString>>;
+type FallbackRender =
+    Box<dyn FnOnce(SharedString, ErrorReset, &mut Window, &mut App) -> AnyElement>;
+
+/// Clears an [`ErrorBoundary`]'s error so its content renders again.
+#[derive(Clone)]
+pub struct ErrorReset {
+    error: Signal<Option<SharedString>>,
+    refetch: Rc<Vec<Fetcher>>,
+}
+
+impl ErrorReset {
+    /// Refetch the failed resources and render the content again.
+    pub fn reset(&self, cx: &mut App) {
+        for refetch in self.refetch.iter() {
+            refetch(cx);
+        }
+        self.error.set_if_changed(None);
+    }
+}
+
+/// Shows a fallback instead of its content when the content fails.
+///
+/// A failure is either a panic while building the content, including one raised by a
+/// memo or signal read during that build, or a failed resource passed to
+/// [`resource`](Self::resource). Panics raised later, such as in event handlers or in
+/// memos recomputed outside of rendering, are not caught. Once a panic is caught the
+/// boundary keeps showing the fallback until [`ErrorReset::reset`] is called, so the
+/// rest of the window keeps working.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+///
+/// fn feed(posts: Resource<Vec<String>, String>) -> impl IntoElement {
+///     ErrorBoundary::new("feed", move |_, _| {
+///         div().children(posts.with(|state| state.value().cloned().unwrap_or_default()))
+///     })
+///     .resource(posts)
+///     .fallback(|message, reset, _, _| {
+///         div()
+///             .child(message)
+///             .child(div().child("Retry").on_mouse_down(MouseButton::Left, move |_, _, cx| {
+///                 reset.reset(cx)
+///             }))
+///     })
+/// }
+/// ```
+#[derive(IntoElement)]
+pub struct ErrorBoundary {
+    observer: Option<SignalId>,
+    id: ElementId,
+    content: ContentRender,
+    resources: Vec<(FailureCheck, Fetcher)>,
+    fallback: Option<FallbackRender>,
+    style: StyleRefinement,
+}
+
+impl ErrorBoundary {
+    /// Create a boundary identified by `id` around `content`.
+    ///
+    /// The id must be unique among its siblings so a caught error survives re-renders.
+    pub fn new<E: IntoElement>(
+        id: impl Into<ElementId>,
+        content: impl FnOnce(&mut Window, &mut App) -> E + 'static,
+    ) -> Self {
+        Self {
+            observer: with_signal_storage(|storage| storage.observer()),
+            id: id.into(),
+            content: Box::new(move |window, cx| content(window, cx).into_any_element()),
+            resources: Vec::new(),
+            fallback: None,
+            style: StyleRefinement::default(),
+        }
+    }
+
+    /// Treat a failure of `resource` as an error of this boundary.
+    ///
+    /// Resetting the boundary refetches the resource.
+    pub fn resource<T: 'static, E: fmt::Display + 'static>(
+        mut self,
+        resource: Resource<T, E>,
+    ) -> Self {
+        let failure: FailureCheck = Box::new(move || {
+            resource.with(|state| state.error().map(|error| error.to_string().into()))
+        });
+        let refetch: Fetcher = Rc::new(move |cx| resource.refetch(cx));
+        self.resources.push((failure, refetch));
+        self
+    }
+
+    /// The view shown instead of the content, given the error message and a reset handle.
+    pub fn fallback<E: IntoElement>(
+        mut self,
+        render: impl FnOnce(SharedString, ErrorReset, &mut Window, &mut App) -> E + 'static,
+    ) -> Self {
+        self.fallback = Some(Box::new(move |message, reset, window, cx| {
+            render(message, reset, window, cx).into_any_element()
+        }));
+        self
+    }
+}
+
+impl Styled for ErrorBoundary {
+    fn style(&mut self) -> &mut StyleRefinement {
+        &mut self.style
+    }
+}
+
+impl RenderOnce for ErrorBoundary {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        with_observer(self.observer, || self.render_content(window, cx))
+    }
+}
+
+impl ErrorBoundary {
+    fn render_content(self, window: &mut Window, cx: &mut App) -> Div {
+        let error = window.with_global_id(self.id, |global_id, window| {
+            window.with_element_state(
+                global_id,
+                |state: Option<Signal<Option<SharedString>>>, _| {
+                    let error = state.unwrap_or_else(|| Signal::new(None));
+                    (error, error)
+                },
+            )
+        });
+
+        let mut refetch = Vec::new();
+        let mut message = error.get();
+        for (failure, fetcher) in &self.resources {
+            if let Some(failure) = failure() {
+                message.get_or_insert(failure);
+                refetch.push(fetcher.clone());
+            }
+        }
+
+        if message.is_none() {
+            let content = self.content;
+            let (observer, read_only) =
+                with_signal_storage(|storage| (storage.observer(), storage.is_read_only()));
+            match panic::catch_unwind(AssertUnwindSafe(|| content(window, cx))) {
+                Ok(content) => {
+                    let mut root = div();
+                    root.style().refine(&self.style);
+                    return root.child(content);
+                }
+                Err(payload) => {
+                    // A panic inside a memo computation skips restoring the tracking
+                    // state, which would otherwise leak into everything after it.
+                    with_signal_storage(|storage| {
+                        storage.set_observer(observer);
+                        storage.set_read_only(read_only);
+                    });
+                    let caught = panic_message(payload.as_ref());
+                    error.set(Some(caught.clone()));
+                    message = Some(caught);
+                }
+            }
+        }
+
+        let message = message.unwrap_or_default();
+        let reset = ErrorReset {
+            error,
+            refetch: Rc::new(refetch),
+        };
+        let mut root = div();
+        root.style().refine(&self.style);
+        match self.fallback {
+            Some(fallback) => root.child(fallback(message, reset, window, cx)),
+            None => root.text_color(rgb(0xff6b6b)).child(message),
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> SharedString {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        SharedString::new(*message)
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone().into()
+    } else {
+        "Something went wrong".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SignalContext;
+    use futures::channel::oneshot;
+    use gpui::{AppContext, Context, Render, TestAppContext};
+
+    struct Loader {
+        resource: Resource<i32, String>,
+    }
+
+    #[gpui::test]
+    async fn test_resource_resolves_and_refetches(cx: &mut TestAppContext) {
+        let senders = Rc::new(RefCell::new(Vec::new()));
+        let loader = cx.new(|cx| {
+            let senders = senders.clone();
+            Loader {
+                resource: cx.create_resource(move |_| {
+                    let (sender, receiver) = oneshot::channel::<Result<i32, String>>();
+                    senders.borrow_mut().push(sender);
+                    async move { receiver.await.unwrap_or_else(|_| Err("cancelled".into())) }
+                }),
+            }
+        });
+        let resource = loader.read_with(cx, |loader, _| loader.resource);
+        assert!(resource.is_loading());
+
+        senders.borrow_mut().remove(0).send(Ok(1)).ok();
+        cx.run_until_parked();
+        assert_eq!(resource.get(), ResourceState::Ready(1));
+
+        cx.update(|cx| resource.refetch(cx));
+        assert!(resource.is_loading());
+        senders
+            .borrow_mut()
+            .remove(0)
+            .send(Err("offline".into()))
+            .ok();
+        cx.run_until_parked();
+        assert_eq!(resource.get(), ResourceState::Failed("offline".into()));
+    }
+
+    #[gpui::test]
+    async fn test_refetch_cancels_stale_fetch(cx: &mut TestAppContext) {
+        let senders = Rc::new(RefCell::new(Vec::new()));
+        let loader = cx.new(|cx| {
+            let senders = senders.clone();
+            Loader {
+                resource: cx.create_resource(move |_| {
+                    let (sender, receiver) = oneshot::channel::<Result<i32, String>>();
+                    senders.borrow_mut().push(sender);
+                    async move { receiver.await.unwrap_or_else(|_| Err("cancelled".into())) }
+                }),
+            }
+        });
+        let resource = loader.read_with(cx, |loader, _| loader.resource);
+        cx.update(|cx| resource.refetch(cx));
+
+        let (stale, fresh) = {
+            let mut senders = senders.borrow_mut();
+            let fresh = senders.pop().expect("fresh sender");
+            (senders.pop().expect("stale sender"), fresh)
+        };
+        fresh.send(Ok(2)).ok();
+        stale.send(Ok(1)).ok();
+        cx.run_until_parked();
+        assert_eq!(resource.get(), ResourceState::Ready(2));
+    }
+
+    struct Page {
+        resource: Resource<i32, String>,
+        rendered: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Render for Page {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            let (content, error) = (self.rendered.clone(), self.rendered.clone());
+            let resource = self.resource;
+            Suspense::new(move |_, _| {
+                content
+                    .borrow_mut()
+                    .push(format!("ready {:?}", resource.get().value()));
+                div()
+            })
+            .resource(resource)
+            .fallback(div().child("Loading..."))
+            .error(move |message, _, _| {
+                error.borrow_mut().push(format!("error {message}"));
+                div()
+            })
+        }
+    }
+
+    #[gpui::test]
+    async fn test_suspense_waits_for_resources(cx: &mut TestAppContext) {
+        let senders = Rc::new(RefCell::new(Vec::new()));
+        let rendered = Rc::new(RefCell::new(Vec::new()));
+        let (page, cx) = cx.add_window_view({
+            let (senders, rendered) = (senders.clone(), rendered.clone());
+            move |_, cx| Page {
+                resource: cx.create_resource(move |_| {
+                    let (sender, receiver) = oneshot::channel::<Result<i32, String>>();
+                    senders.borrow_mut().push(sender);
+                    async move { receiver.await.unwrap_or_else(|_| Err("cancelled".into())) }
+                }),
+                rendered,
+            }
+        });
+        assert!(rendered.borrow().is_empty());
+
+        senders.borrow_mut().remove(0).send(Ok(7)).ok();
+        cx.run_until_parked();
+        assert_eq!(*rendered.borrow(), vec!["ready Some(7)"]);
+
+        let resource = page.read_with(cx, |page, _| page.resource);
+        cx.update(|_, cx| resource.refetch(cx));
+        senders
+            .borrow_mut()
+            .remove(0)
+            .send(Err("offline".into()))
+            .ok();
+        cx.run_until_parked();
+        assert_eq!(*rendered.borrow(), vec!["ready Some(7)", "error offline"]);
+    }
+
+    struct Guarded {
+        broken: Signal<bool>,
+    }
+
+    impl Render for Guarded {
+        fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            let broken = self.broken;
+            cx.track_render(|_| {
+                ErrorBoundary::new("guarded", move |_, _| {
+                    if broken.get() {
+                        panic!("render failed");
+                    }
+                    div()
+                })
+                .fallback(move |message, reset, _, _| {
+                    LAST_FALLBACK.with(|last| *last.borrow_mut() = Some((message, reset)));
+                    div()
+                })
+            })
+        }
+    }
+
+    thread_local! {
+        static LAST_FALLBACK: RefCell<Option<(SharedString, ErrorReset)>> = const { RefCell::new(None) };
+    }
+
+    #[gpui::test]
+    async fn test_error_boundary_catches_render_panic(cx: &mut TestAppContext) {
+        let broken = Signal::new(false);
+        let (guarded, cx) = cx.add_window_view(|_, _| Guarded { broken });
+        assert!(LAST_FALLBACK.with(|last| last.borrow().is_none()));
+
+        broken.set(true);
+        cx.run_until_parked();
+        let (message, reset) = LAST_FALLBACK
+            .with(|last| last.borrow_mut().take())
+            .expect("fallback not rendered");
+        assert_eq!(message, "render failed");
+
+        // The caught error sticks until reset, even once the cause is gone. The
+        // fallback doesn't read `broken`, so re-render by hand.
+        broken.set(false);
+        guarded.update(cx, |_, cx| cx.notify());
+        cx.run_until_parked();
+        assert!(LAST_FALLBACK
+            .with(|last| last.borrow_mut().take())
+            .is_some());
+
+        cx.update(|_, cx| reset.reset(cx));
+        cx.run_until_parked();
+        assert!(LAST_FALLBACK.with(|last| last.borrow().is_none()));
+    }
+}
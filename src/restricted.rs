@@ -0,0 +1,259 @@
+//! Capability-limited signal handles for code the app doesn't trust, such as plugins.
+
+use crate::signal::Signal;
+use crate::storage::{CapabilityId, SignalId};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// What a [`RestrictedSignal`] may do with its signal.
+///
+/// # Examples
+///
+/// ```rust
+/// use gpui_signals::{Permissions, Signal};
+/// use std::time::Duration;
+///
+/// let status = Signal::new(String::from("idle"));
+/// let plugin_handle =
+///     status.restricted(Permissions::write_only().max_writes(10, Duration::from_secs(1)));
+///
+/// plugin_handle.set("indexing".to_string()).unwrap();
+/// assert!(plugin_handle.get().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Permissions {
+    read: bool,
+    write: bool,
+    rate_limit: Option<(u32, Duration)>,
+}
+
+impl Permissions {
+    /// Reads only.
+    pub fn read_only() -> Self {
+        Self {
+            read: true,
+            write: false,
+            rate_limit: None,
+        }
+    }
+
+    /// Writes only, such as a plugin reporting progress it has no business reading
+    /// back.
+    pub fn write_only() -> Self {
+        Self {
+            read: false,
+            ..Self::read_write()
+        }
+    }
+
+    /// Reads and writes.
+    pub fn read_write() -> Self {
+        Self {
+            read: true,
+            write: true,
+            rate_limit: None,
+        }
+    }
+
+    /// Allow at most `count` writes in each `period`, rejecting the rest.
+    pub fn max_writes(self, count: u32, period: Duration) -> Self {
+        Self {
+            rate_limit: Some((count, period)),
+            ..self
+        }
+    }
+}
+
+/// Why a [`RestrictedSignal`] operation was refused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessError {
+    /// The handle was revoked, or its signal disposed.
+    Revoked,
+    /// The handle may not read the signal.
+    NotReadable,
+    /// The handle may not write the signal.
+    NotWritable,
+    /// The handle's write rate limit was reached.
+    RateLimited,
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Revoked => "the signal handle was revoked",
+            Self::NotReadable => "the signal handle is not allowed to read",
+            Self::NotWritable => "the signal handle is not allowed to write",
+            Self::RateLimited => "the signal handle's write rate limit was reached",
+        })
+    }
+}
+
+impl std::error::Error for AccessError {}
+
+/// A grant of permissions on one signal, kept in the signal's storage.
+pub(crate) struct Capability {
+    signal: SignalId,
+    permissions: Permissions,
+    /// When the current rate limit period started, and the writes made in it.
+    period: Option<(Instant, u32)>,
+}
+
+impl Capability {
+    pub(crate) fn new(signal: SignalId, permissions: Permissions) -> Self {
+        Self {
+            signal,
+            permissions,
+            period: None,
+        }
+    }
+
+    pub(crate) fn signal(&self) -> SignalId {
+        self.signal
+    }
+
+    /// Check a read, or check and count a write.
+    pub(crate) fn check(&mut self, write: bool) -> Result<(), AccessError> {
+        if !write {
+            return if self.permissions.read {
+                Ok(())
+            } else {
+                Err(AccessError::NotReadable)
+            };
+        }
+        if !self.permissions.write {
+            return Err(AccessError::NotWritable);
+        }
+        let Some((max_writes, length)) = self.permissions.rate_limit else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        let (start, writes) = self.period.get_or_insert((now, 0));
+        if now.duration_since(*start) >= length {
+            *start = now;
+            *writes = 0;
+        }
+        if *writes >= max_writes {
+            return Err(AccessError::RateLimited);
+        }
+        *writes += 1;
+        Ok(())
+    }
+}
+
+/// A handle to a signal that can only do what its [`Permissions`] allow, created with
+/// [`Signal::restricted`].
+///
+/// The permissions live in the signal's storage and are checked there on every
+/// operation, so copies of the handle share them and the owner can
+/// [`revoke`](Self::revoke) every copy at once. The underlying [`Signal`] is never
+/// exposed, so code holding only this handle can't get around the checks.
+///
+/// Since handles are `Copy`, dropping them doesn't free the grant: it stays in the
+/// storage until `revoke` is called or the signal is disposed. Revoke a handle once
+/// the code it was given to is done with it, for example when unloading a plugin,
+/// or a long-lived signal handed out again and again keeps every grant it made.
+pub struct RestrictedSignal<T> {
+    signal: Signal<T>,
+    capability: CapabilityId,
+}
+
+impl<T> Copy for RestrictedSignal<T> {}
+
+impl<T> Clone for RestrictedSignal<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for RestrictedSignal<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.capability == other.capability
+    }
+}
+
+impl<T> Eq for RestrictedSignal<T> {}
+
+impl<T: 'static> RestrictedSignal<T> {
+    pub(crate) fn new(signal: Signal<T>, permissions: Permissions) -> Self {
+        let capability = signal.with_storage(|storage| storage.grant(signal.id(), permissions));
+        Self { signal, capability }
+    }
+
+    fn check(&self, write: bool) -> Result<(), AccessError> {
+        self.signal
+            .with_storage(|storage| storage.check_access(self.capability, write))
+    }
+
+    /// Get the current value, tracking the read.
+    pub fn get(&self) -> Result<T, AccessError>
+    where
+        T: Clone,
+    {
+        self.check(false)?;
+        Ok(self.signal.get())
+    }
+
+    /// Read the value with a closure, tracking the read.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, AccessError> {
+        self.check(false)?;
+        Ok(self.signal.with(f))
+    }
+
+    /// Set a new value.
+    #[track_caller]
+    pub fn set(&self, value: T) -> Result<(), AccessError> {
+        self.check(true)?;
+        self.signal.set(value);
+        Ok(())
+    }
+
+    /// Update the value with a closure.
+    #[track_caller]
+    pub fn update(&self, f: impl FnOnce(&mut T)) -> Result<(), AccessError> {
+        self.check(true)?;
+        self.signal.update(f);
+        Ok(())
+    }
+
+    /// Withdraw the permissions of this handle and every copy of it.
+    pub fn revoke(&self) {
+        self.signal
+            .with_storage(|storage| storage.revoke(self.capability));
+    }
+}
+
+impl<T> fmt::Debug for RestrictedSignal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RestrictedSignal")
+            .field("capability", &self.capability)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permissions_are_enforced_until_revoked() {
+        let volume = Signal::new(5);
+        let reader = volume.restricted(Permissions::read_only());
+        let writer =
+            volume.restricted(Permissions::write_only().max_writes(2, Duration::from_secs(60)));
+
+        assert_eq!(reader.get(), Ok(5));
+        assert_eq!(reader.set(0), Err(AccessError::NotWritable));
+        assert_eq!(writer.get(), Err(AccessError::NotReadable));
+
+        assert_eq!(writer.set(6), Ok(()));
+        assert_eq!(writer.update(|volume| *volume += 1), Ok(()));
+        assert_eq!(writer.set(11), Err(AccessError::RateLimited));
+        assert_eq!(volume.get(), 7);
+
+        let copy = reader;
+        reader.revoke();
+        assert_eq!(copy.get(), Err(AccessError::Revoked));
+        volume.dispose();
+        assert_eq!(writer.set(1), Err(AccessError::Revoked));
+    }
+}
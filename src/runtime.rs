@@ -0,0 +1,561 @@
+//! Independent signal runtimes.
+
+use crate::scheduler::Scheduler;
+use crate::storage::{default_runtime, RuntimeId, RuntimeStorage, SignalId, SignalStorage};
+use std::error::Error;
+use std::fmt;
+use std::panic::Location;
+use std::rc::Rc;
+
+/// An isolated signal graph with its own storage.
+///
+/// Signals, memos, and effects created in one runtime never track or notify those of
+/// another, so an app and an embedded preview, or tests running side by side, don't
+/// interfere. [`SignalRuntime::default`] is the thread's default runtime, which
+/// `Signal::new`, `cx.create_signal`, and the other constructors without a runtime
+/// argument use; the GPUI integration only tracks reads in that runtime.
+///
+/// The storage is freed when the last clone of a runtime created with
+/// [`new`](Self::new) is dropped. Using a signal from a dropped runtime panics.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui_signals::prelude::*;
+///
+/// let preview = SignalRuntime::new();
+/// let count = Signal::new_in(1, &preview);
+/// let doubled = Memo::new_in(move || count.get() * 2, &preview);
+/// count.set(2);
+/// assert_eq!(doubled.get(), 4);
+/// ```
+#[derive(Clone)]
+pub struct SignalRuntime {
+    storage: Rc<RuntimeStorage>,
+}
+
+impl SignalRuntime {
+    /// Create a runtime with empty storage.
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create a runtime with room for `capacity` signals, memos, and effects before
+    /// its storage grows.
+    ///
+    /// Memos and effects each take a slot of their own besides the signals they read.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            storage: RuntimeStorage::new(capacity),
+        }
+    }
+
+    /// The number of signals, memos, and effects the storage holds before it grows.
+    pub fn capacity(&self) -> usize {
+        self.with_storage(|storage| storage.capacity())
+    }
+
+    /// The number of signals, memos, and effects that have not been disposed.
+    pub fn signal_count(&self) -> usize {
+        self.with_storage(|storage| storage.len())
+    }
+
+    /// Release memory the runtime holds for disposed signals and removed
+    /// subscriptions.
+    ///
+    /// Worth calling after closing something that created many short-lived signals,
+    /// such as a dialog or a tab. Slots of disposed signals stay allocated for reuse,
+    /// so the storage keeps its capacity; the values themselves are freed when each
+    /// signal is disposed.
+    pub fn shrink_to_fit(&self) {
+        self.with_storage(|storage| storage.shrink_to_fit());
+    }
+
+    /// Choose when the notifications produced by writes in this runtime are delivered.
+    ///
+    /// Dependent memos are still marked dirty by each write; the scheduler only
+    /// decides when subscribers and effects run. The default
+    /// [`ImmediateScheduler`](crate::ImmediateScheduler) runs them before the write
+    /// returns.
+    pub fn set_scheduler(&self, scheduler: impl Scheduler + 'static) {
+        let previous = self.with_storage(|storage| storage.replace_scheduler(Rc::new(scheduler)));
+        drop(previous);
+    }
+
+    /// Choose what happens when a handle to a disposed signal is used in this runtime.
+    pub fn set_stale_handle_policy(&self, policy: StaleHandlePolicy) {
+        self.with_storage(|storage| storage.set_stale_handle_policy(policy));
+    }
+
+    /// Call `handler` for each stale handle access under [`StaleHandlePolicy::Error`].
+    pub fn on_stale_handle(&self, handler: impl Fn(&StaleHandle) + 'static) {
+        let previous =
+            self.with_storage(|storage| storage.replace_stale_handle_handler(Rc::new(handler)));
+        drop(previous);
+    }
+
+    /// Limit how deeply writes made while delivering notifications may nest.
+    ///
+    /// Subscribers that write signals other subscribers watch can notify each other
+    /// back and forth without end, since unlike effects they don't skip
+    /// notifications that arrive while they run. Past `depth`
+    /// nested deliveries the innermost write's notifications are dropped, unwinding
+    /// the cycle, and an [`UpdateDepthExceeded`] naming the chain of signals is
+    /// passed to the handler set with
+    /// [`on_update_depth_exceeded`](Self::on_update_depth_exceeded), or logged as an
+    /// error through the `log` crate without one. The default is 100.
+    ///
+    /// Only deliveries that nest count, as under the default
+    /// [`ImmediateScheduler`](crate::ImmediateScheduler).
+    pub fn set_max_update_depth(&self, depth: usize) {
+        self.with_storage(|storage| storage.set_max_update_depth(depth));
+    }
+
+    /// Call `handler` when writes nest past the
+    /// [maximum update depth](Self::set_max_update_depth), instead of logging an
+    /// error through the `log` crate.
+    pub fn on_update_depth_exceeded(&self, handler: impl Fn(&UpdateDepthExceeded) + 'static) {
+        let previous =
+            self.with_storage(|storage| storage.replace_update_depth_handler(Rc::new(handler)));
+        drop(previous);
+    }
+
+    /// Call `handler` with each notification wave: a write made outside any
+    /// delivery, and everything its notifications caused, once all of it finishes.
+    ///
+    /// Waves are only recorded while a handler is set, so this is a debugging aid
+    /// for answering why a view re-rendered or an effect ran. Waves follow nested
+    /// deliveries, as under the default [`ImmediateScheduler`](crate::ImmediateScheduler);
+    /// with a deferred scheduler, each write's wave ends before its effects run.
+    ///
+    /// ```rust,no_run
+    /// use gpui_signals::prelude::*;
+    ///
+    /// let runtime = SignalRuntime::default();
+    /// if cfg!(debug_assertions) {
+    ///     runtime.on_notification_wave(|wave| eprintln!("{wave}"));
+    /// }
+    /// ```
+    pub fn on_notification_wave(&self, handler: impl Fn(&NotificationWave) + 'static) {
+        let previous =
+            self.with_storage(|storage| storage.replace_wave_handler(Some(Rc::new(handler))));
+        drop(previous);
+    }
+
+    /// Stop recording notification waves, removing the handler set with
+    /// [`on_notification_wave`](Self::on_notification_wave).
+    pub fn stop_notification_waves(&self) {
+        let previous = self.with_storage(|storage| storage.replace_wave_handler(None));
+        drop(previous);
+    }
+
+    pub(crate) fn id(&self) -> RuntimeId {
+        self.storage.id()
+    }
+
+    pub(crate) fn with_storage<R>(&self, f: impl FnOnce(&mut SignalStorage) -> R) -> R {
+        self.storage.with(f)
+    }
+}
+
+impl Default for SignalRuntime {
+    /// This thread's default runtime.
+    fn default() -> Self {
+        Self {
+            storage: default_runtime(),
+        }
+    }
+}
+
+impl PartialEq for SignalRuntime {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.storage, &other.storage)
+    }
+}
+
+impl Eq for SignalRuntime {}
+
+impl fmt::Debug for SignalRuntime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SignalRuntime").field(&self.id()).finish()
+    }
+}
+
+/// What a runtime does when a signal handle is used after its signal was disposed.
+///
+/// Writes, subscriptions, reads of metadata such as
+/// [`version`](crate::Signal::version), and reads through
+/// [`get_or_default`](crate::Signal::get_or_default) and
+/// [`with_or_default`](crate::Signal::with_or_default) follow the policy. Reads like
+/// `get` and `with` have no value to fall back on and panic under every policy; use
+/// `try_get` or the `_or_default` reads where a handle may outlive its signal.
+///
+/// Debug builds default to [`Panic`](Self::Panic), so a stale handle is caught where
+/// it is used; release builds default to [`Error`](Self::Error). To keep carrying on
+/// in release builds while still hearing about it:
+///
+/// ```rust,no_run
+/// use gpui_signals::{SignalRuntime, StaleHandlePolicy};
+///
+/// if !cfg!(debug_assertions) {
+///     SignalRuntime::default().set_stale_handle_policy(StaleHandlePolicy::LogAndDefault);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StaleHandlePolicy {
+    /// Panic, naming the signal and the caller. The default in debug builds.
+    Panic,
+    /// Log a warning through the [`log`](https://docs.rs/log) crate and skip the
+    /// access: writes are dropped, `set_if_changed` reports no change, subscriptions
+    /// are not registered, `version` reads as 0, and the `_or_default` reads see
+    /// `T::default()`.
+    LogAndDefault,
+    /// Skip the access as [`LogAndDefault`](Self::LogAndDefault) does, and pass a
+    /// [`StaleHandle`] to the handler set with
+    /// [`SignalRuntime::on_stale_handle`], if any. The default in release builds,
+    /// which without a handler leaves stale writes as silent no-ops.
+    Error,
+}
+
+impl Default for StaleHandlePolicy {
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            Self::Panic
+        } else {
+            Self::Error
+        }
+    }
+}
+
+/// A use of a signal handle after its signal was disposed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StaleHandle {
+    /// The disposed signal.
+    pub signal: SignalId,
+    /// Where the handle was used.
+    pub location: &'static Location<'static>,
+}
+
+impl fmt::Display for StaleHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "signal {:?} was used at {} after it was disposed",
+            self.signal, self.location
+        )
+    }
+}
+
+impl Error for StaleHandle {}
+
+/// Writes that nested past a runtime's
+/// [maximum update depth](SignalRuntime::set_max_update_depth), whose notifications
+/// were dropped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpdateDepthExceeded {
+    /// The signals whose writes were being delivered, outermost first, ending with
+    /// the write that was dropped. A cycle shows up as the same signals repeating.
+    pub chain: Vec<SignalId>,
+}
+
+impl fmt::Display for UpdateDepthExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "writes nested more than {} deep, likely a cycle between subscribers; dropped \
+             the notifications of the last write in the chain ",
+            self.chain.len().saturating_sub(1)
+        )?;
+        for (index, signal) in self.chain.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" -> ")?;
+            }
+            write!(f, "{signal:?}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for UpdateDepthExceeded {}
+
+/// A write and the work its notifications caused, passed to the handler set with
+/// [`SignalRuntime::on_notification_wave`].
+///
+/// Displayed as one event per line, each indented under the event that caused it:
+///
+/// ```text
+/// write to SignalId(1v1) at src/editor.rs:42:15
+///   memo SignalId(2v1) marked dirty
+///   effect SignalId(3v1) ran
+///     memo SignalId(2v1) recomputed
+///     write to SignalId(4v1) at src/editor.rs:57:9
+///   entity 7 notified
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotificationWave {
+    /// The events in the order they happened, starting with the write.
+    pub entries: Vec<WaveEntry>,
+}
+
+impl fmt::Display for NotificationWave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, entry) in self.entries.iter().enumerate() {
+            if index > 0 {
+                f.write_str("\n")?;
+            }
+            write!(f, "{:indent$}{}", "", entry.event, indent = entry.depth * 2)?;
+        }
+        Ok(())
+    }
+}
+
+/// One event of a [`NotificationWave`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WaveEntry {
+    /// How many causes deep the event is: 0 for the write that started the wave,
+    /// one more than the event that caused it otherwise.
+    pub depth: usize,
+    /// What happened.
+    pub event: WaveEvent,
+}
+
+/// Something that happened during a [`NotificationWave`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WaveEvent {
+    /// A signal was written, or a memo invalidated by hand.
+    Write {
+        /// The signal written.
+        signal: SignalId,
+        /// Where it was last written from, as reported by
+        /// [`Signal::last_writer`](crate::Signal::last_writer).
+        location: Option<&'static Location<'static>>,
+    },
+    /// A memo depending on the write was marked dirty, to recompute on its next read.
+    MemoInvalidated(SignalId),
+    /// A dirty memo recomputed because something read it.
+    MemoRecomputed(SignalId),
+    /// An effect depending on the write ran.
+    EffectRun(SignalId),
+    /// An entity watching the write was notified to re-render.
+    #[cfg(feature = "gpui")]
+    EntityNotified(gpui::EntityId),
+}
+
+impl fmt::Display for WaveEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Write {
+                signal,
+                location: Some(location),
+            } => write!(f, "write to {signal:?} at {location}"),
+            Self::Write {
+                signal,
+                location: None,
+            } => write!(f, "write to {signal:?}"),
+            Self::MemoInvalidated(memo) => write!(f, "memo {memo:?} marked dirty"),
+            Self::MemoRecomputed(memo) => write!(f, "memo {memo:?} recomputed"),
+            Self::EffectRun(effect) => write!(f, "effect {effect:?} ran"),
+            #[cfg(feature = "gpui")]
+            Self::EntityNotified(entity) => write!(f, "entity {entity} notified"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Effect, Memo, Signal};
+    use std::cell::{Cell, RefCell};
+
+    #[test]
+    fn test_runtimes_are_isolated() {
+        let first = SignalRuntime::new();
+        let second = SignalRuntime::new();
+        let a = Signal::new_in(1, &first);
+        let b = Signal::new_in(10, &second);
+        assert_eq!(a.id(), b.id());
+        assert_ne!(a, b);
+        assert_ne!(first, second);
+        assert_eq!(SignalRuntime::default(), SignalRuntime::default());
+
+        let runs = Rc::new(Cell::new(0));
+        let _effect = Effect::new_in(
+            {
+                let runs = runs.clone();
+                move || {
+                    a.get();
+                    runs.set(runs.get() + 1);
+                }
+            },
+            &first,
+        );
+        let sum = Memo::new_in(move || a.get() + b.get_untracked(), &first);
+        let initial_runs = runs.get();
+
+        b.set(20);
+        assert_eq!(runs.get(), initial_runs);
+        assert_eq!(sum.get(), 11);
+
+        a.set(2);
+        assert_eq!(runs.get(), initial_runs + 1);
+        assert_eq!(sum.get(), 22);
+    }
+
+    #[test]
+    fn test_capacity_and_shrink_to_fit() {
+        let runtime = SignalRuntime::with_capacity(64);
+        assert!(runtime.capacity() >= 64);
+
+        let notified = Rc::new(Cell::new(0));
+        let signals: Vec<_> = (0..32)
+            .map(|value| Signal::new_in(value, &runtime))
+            .collect();
+        for signal in &signals {
+            let notified = notified.clone();
+            signal.subscribe(move || notified.set(notified.get() + 1));
+        }
+        let kept = signals[0];
+        let doubled = Memo::new_in(move || kept.get() * 2, &runtime);
+        let count = runtime.signal_count();
+        for signal in &signals[1..] {
+            signal.dispose();
+        }
+        assert_eq!(runtime.signal_count(), count - 31);
+
+        runtime.shrink_to_fit();
+        kept.set(5);
+        assert_eq!(notified.get(), 1);
+        assert_eq!(doubled.get(), 10);
+        assert!(runtime.capacity() >= 64);
+    }
+
+    #[test]
+    fn test_stale_handle_policy() {
+        let runtime = SignalRuntime::new();
+        let signal = Signal::new_in(1, &runtime);
+        signal.dispose();
+        assert_eq!(StaleHandlePolicy::default(), StaleHandlePolicy::Panic);
+        runtime.set_stale_handle_policy(StaleHandlePolicy::Error);
+
+        let reported = Rc::new(Cell::new(0));
+        runtime.on_stale_handle({
+            let reported = reported.clone();
+            move |error| {
+                assert_eq!(error.signal, signal.id());
+                reported.set(reported.get() + 1);
+            }
+        });
+        signal.set(2);
+        assert!(!signal.set_if_changed(3));
+        assert_eq!(signal.update_with(|value| *value), None);
+        assert_eq!(signal.get_or_default(), 0);
+        signal.subscribe_once(|| {});
+        signal.subscribe_when(|_| true, || {});
+        assert_eq!(reported.get(), 6);
+
+        runtime.set_stale_handle_policy(StaleHandlePolicy::LogAndDefault);
+        signal.set(2);
+        assert_eq!(signal.version(), 0);
+        assert_eq!(signal.with_or_default(|value| *value + 1), 1);
+        assert_eq!(reported.get(), 6);
+
+        runtime.set_stale_handle_policy(StaleHandlePolicy::Panic);
+        let panic = std::panic::catch_unwind(|| signal.set(2)).unwrap_err();
+        assert!(panic
+            .downcast_ref::<String>()
+            .unwrap()
+            .contains("after it was disposed"));
+    }
+
+    #[test]
+    fn test_max_update_depth_breaks_subscriber_cycles() {
+        let runtime = SignalRuntime::new();
+        runtime.set_max_update_depth(10);
+        let reported = Rc::new(RefCell::new(Vec::new()));
+        runtime.on_update_depth_exceeded({
+            let reported = reported.clone();
+            move |error| reported.borrow_mut().push(error.chain.clone())
+        });
+
+        let ping = Signal::new_in(0, &runtime);
+        let pong = Signal::new_in(0, &runtime);
+        ping.subscribe(move || pong.set(ping.get_untracked() + 1));
+        pong.subscribe(move || ping.set(pong.get_untracked() + 1));
+        ping.set(1);
+
+        let reported = reported.borrow();
+        assert_eq!(reported.len(), 1);
+        let chain = &reported[0];
+        assert_eq!(chain.len(), 11);
+        assert!(chain.windows(2).all(|pair| pair[0] != pair[1]));
+        assert!(chain
+            .iter()
+            .all(|signal| *signal == ping.id() || *signal == pong.id()));
+
+        // The depth unwinds with the cycle, so later writes deliver normally.
+        let count = Signal::new_in(0, &runtime);
+        let runs = Rc::new(Cell::new(0));
+        let _effect = Effect::new_in(
+            {
+                let runs = runs.clone();
+                move || {
+                    count.get();
+                    runs.set(runs.get() + 1);
+                }
+            },
+            &runtime,
+        );
+        count.set(1);
+        assert_eq!(runs.get(), 2);
+    }
+
+    #[test]
+    fn test_notification_waves_nest_under_their_causes() {
+        let runtime = SignalRuntime::new();
+        let count = Signal::new_in(1, &runtime);
+        let doubled = Memo::new_in(move || count.get() * 2, &runtime);
+        let total = Signal::new_in(0, &runtime);
+        let _effect = Effect::new_in(move || total.set(doubled.get()), &runtime);
+        let waves = Rc::new(RefCell::new(Vec::new()));
+        runtime.on_notification_wave({
+            let waves = waves.clone();
+            move |wave| waves.borrow_mut().push(wave.clone())
+        });
+
+        count.set(2);
+        let waves = waves.take();
+        assert_eq!(waves.len(), 1);
+        let shape: Vec<_> = waves[0]
+            .entries
+            .iter()
+            .map(|entry| (entry.depth, entry.event.clone()))
+            .collect();
+        assert!(matches!(
+            shape.as_slice(),
+            [
+                (0, WaveEvent::Write { signal: written, .. }),
+                (1, WaveEvent::MemoInvalidated(invalidated)),
+                (1, WaveEvent::EffectRun(_)),
+                (2, WaveEvent::MemoRecomputed(recomputed)),
+                (2, WaveEvent::Write { signal: nested, .. }),
+            ] if *written == count.id()
+                && *invalidated == doubled.signal().id()
+                && *recomputed == doubled.signal().id()
+                && *nested == total.id()
+        ));
+        assert!(waves[0].to_string().contains("\n    memo "));
+
+        runtime.stop_notification_waves();
+        count.set(3);
+        assert_eq!(total.get(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "Signal runtime was dropped")]
+    fn test_dropped_runtime_frees_signals() {
+        let runtime = SignalRuntime::new();
+        let signal = Signal::new_in(1, &runtime);
+        drop(runtime);
+        signal.get();
+    }
+}
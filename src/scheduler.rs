@@ -0,0 +1,210 @@
+//! Pluggable delivery of the notifications a write produces.
+
+use crate::storage::Subscriber;
+#[cfg(feature = "gpui")]
+use futures::channel::mpsc;
+#[cfg(feature = "gpui")]
+use futures::StreamExt;
+#[cfg(feature = "gpui")]
+use gpui::{App, Task, Window};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// The subscriber and effect callbacks produced by one write, in the order they
+/// should run.
+///
+/// Dependent memos are already marked dirty by the time a scheduler receives these,
+/// so reads made before delivery see up-to-date values.
+pub struct Notifications {
+    callbacks: Vec<Subscriber>,
+}
+
+impl Notifications {
+    pub(crate) fn new(callbacks: Vec<Subscriber>) -> Self {
+        Self { callbacks }
+    }
+
+    /// Run the callbacks.
+    pub fn deliver(self) {
+        for callback in self.callbacks {
+            callback();
+        }
+    }
+}
+
+/// Decides when a runtime delivers the notifications produced by its writes.
+///
+/// Set one with [`SignalRuntime::set_scheduler`](crate::SignalRuntime::set_scheduler).
+/// The default is [`ImmediateScheduler`].
+pub trait Scheduler {
+    /// Deliver `notifications` now or later.
+    fn schedule(&self, notifications: Notifications);
+}
+
+/// Delivers notifications before the write returns.
+///
+/// This gives synchronous semantics, which keeps tests simple.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ImmediateScheduler;
+
+impl Scheduler for ImmediateScheduler {
+    fn schedule(&self, notifications: Notifications) {
+        notifications.deliver();
+    }
+}
+
+/// Holds notifications until [`flush`](Self::flush) is called.
+///
+/// A callback notified by several writes in the batch runs once, so code that
+/// applies many writes at a time can flush at the end instead of re-running effects
+/// after each one.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::BatchScheduler;
+///
+/// let runtime = SignalRuntime::new();
+/// let batch = BatchScheduler::new();
+/// runtime.set_scheduler(batch.clone());
+///
+/// let rows = Signal::new_in(0, &runtime);
+/// let _log = Effect::new_in(move || println!("{} rows", rows.get()), &runtime);
+/// for _ in 0..100 {
+///     rows.update(|rows| *rows += 1);
+/// }
+/// batch.flush();
+/// ```
+#[derive(Clone, Default)]
+pub struct BatchScheduler {
+    pending: Rc<RefCell<Vec<Subscriber>>>,
+}
+
+impl BatchScheduler {
+    /// Create a scheduler with nothing pending.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deliver everything scheduled since the last flush, including notifications
+    /// scheduled by the callbacks it runs.
+    pub fn flush(&self) {
+        loop {
+            let pending = self.pending.take();
+            if pending.is_empty() {
+                break;
+            }
+            let mut seen = HashSet::with_capacity(pending.len());
+            let callbacks: Vec<Subscriber> = pending
+                .into_iter()
+                .filter(|callback| seen.insert(Rc::as_ptr(callback).cast::<()>()))
+                .collect();
+            Notifications::new(callbacks).deliver();
+        }
+    }
+
+    /// Whether no notifications are waiting for a flush.
+    pub fn is_empty(&self) -> bool {
+        self.pending.borrow().is_empty()
+    }
+}
+
+impl Scheduler for BatchScheduler {
+    fn schedule(&self, notifications: Notifications) {
+        self.pending.borrow_mut().extend(notifications.callbacks);
+    }
+}
+
+/// Delivers notifications right after the window's next frame.
+///
+/// Writes made while handling one event are delivered together, once per frame,
+/// which keeps effects aligned with rendering. Once the window closes,
+/// notifications are no longer delivered.
+#[cfg(feature = "gpui")]
+pub struct FrameScheduler {
+    batch: BatchScheduler,
+    wake: mpsc::UnboundedSender<()>,
+    _task: Task<()>,
+}
+
+#[cfg(feature = "gpui")]
+impl FrameScheduler {
+    /// Create a scheduler that delivers after frames of `window`.
+    pub fn new(window: &Window, cx: &App) -> Self {
+        let batch = BatchScheduler::new();
+        let (wake, mut woken) = mpsc::unbounded::<()>();
+        let task = window.spawn(cx, {
+            let batch = batch.clone();
+            async move |cx| {
+                while woken.next().await.is_some() {
+                    let batch = batch.clone();
+                    let scheduled = cx.update(|window, _| {
+                        window.on_next_frame(move |_, _| batch.flush());
+                        window.refresh();
+                    });
+                    if scheduled.is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Self {
+            batch,
+            wake,
+            _task: task,
+        }
+    }
+}
+
+#[cfg(feature = "gpui")]
+impl Scheduler for FrameScheduler {
+    fn schedule(&self, notifications: Notifications) {
+        let was_empty = self.batch.is_empty();
+        self.batch.schedule(notifications);
+        if was_empty {
+            let _ = self.wake.unbounded_send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Effect, Memo, Signal, SignalRuntime};
+    use std::cell::Cell;
+
+    #[test]
+    fn test_batch_scheduler_runs_effects_once_on_flush() {
+        let runtime = SignalRuntime::new();
+        let batch = BatchScheduler::new();
+        runtime.set_scheduler(batch.clone());
+
+        let count = Signal::new_in(0, &runtime);
+        let doubled = Memo::new_in(move || count.get() * 2, &runtime);
+        let runs = Rc::new(Cell::new(0));
+        let _effect = Effect::new_in(
+            {
+                let runs = runs.clone();
+                move || {
+                    doubled.get();
+                    runs.set(runs.get() + 1);
+                }
+            },
+            &runtime,
+        );
+        assert_eq!(runs.get(), 1);
+
+        for value in 1..=5 {
+            count.set(value);
+        }
+        assert_eq!(doubled.get(), 10);
+        assert_eq!(runs.get(), 1);
+        assert!(!batch.is_empty());
+
+        batch.flush();
+        assert_eq!(runs.get(), 2);
+        assert!(batch.is_empty());
+    }
+}
@@ -0,0 +1,124 @@
+//! Scroll positions mirrored in signals.
+//!
+//! A [`ScrollSignal`] pairs a GPUI [`ScrollHandle`] with a `Signal<Point<Pixels>>`.
+//! Setting the signal scrolls the element, and scrolling the element updates the
+//! signal, so scroll restore and scroll-linked state can be written against signals.
+
+use crate::{Signal, SignalContext};
+use gpui::{Context, Pixels, Point, ScrollHandle, StatefulInteractiveElement};
+
+/// A scroll handle whose offset is kept in sync with a signal.
+///
+/// The offset follows GPUI's convention: it is the distance from the top left of the
+/// container to the top left of its content, so it becomes more negative as the
+/// content scrolls down or right.
+#[derive(Clone)]
+pub struct ScrollSignal {
+    handle: ScrollHandle,
+    offset: Signal<Point<Pixels>>,
+}
+
+impl ScrollSignal {
+    /// The scroll handle to attach to a scrollable element.
+    ///
+    /// Prefer [`ScrollSignalExt::track_scroll_signal`], which also reports scrolling
+    /// back to the signal.
+    pub fn handle(&self) -> &ScrollHandle {
+        &self.handle
+    }
+
+    /// The signal holding the current scroll offset.
+    pub fn offset(&self) -> Signal<Point<Pixels>> {
+        self.offset
+    }
+
+    /// Scroll to `offset`.
+    pub fn scroll_to(&self, offset: Point<Pixels>) {
+        self.offset.set_if_changed(offset);
+    }
+
+    /// Copy the handle's offset into the signal.
+    ///
+    /// Scroll wheel events are synced automatically. Call this after scrolling the
+    /// handle directly, for example with `handle().scroll_to_item(index)`, once the
+    /// next frame has laid out the new position.
+    pub fn sync(&self) {
+        self.offset.set_if_changed(self.handle.offset());
+    }
+}
+
+/// Create a [`ScrollSignal`] owned by the entity.
+///
+/// The entity is notified whenever the offset signal changes.
+pub fn scroll_signal<V: 'static>(cx: &mut Context<V>) -> ScrollSignal {
+    let handle = ScrollHandle::new();
+    let offset = cx.create_signal(handle.offset());
+    offset.subscribe({
+        let handle = handle.clone();
+        move || {
+            let offset = offset.get_untracked();
+            if handle.offset() != offset {
+                handle.set_offset(offset);
+            }
+        }
+    });
+    ScrollSignal { handle, offset }
+}
+
+/// Element-builder extension for binding a scrollable element to a [`ScrollSignal`].
+pub trait ScrollSignalExt: StatefulInteractiveElement {
+    /// Track scrolling of this element with `scroll`.
+    fn track_scroll_signal(self, scroll: &ScrollSignal) -> Self {
+        let scroll = scroll.clone();
+        // The element applies the wheel delta in its own listener, so read the
+        // offset after the event has been dispatched.
+        let handle = scroll.handle.clone();
+        self.track_scroll(&handle).on_scroll_wheel(move |_, _, cx| {
+            let scroll = scroll.clone();
+            cx.defer(move |_| scroll.sync());
+        })
+    }
+}
+
+impl<E: StatefulInteractiveElement> ScrollSignalExt for E {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{point, px, AppContext, TestAppContext};
+
+    struct Scroller {
+        scroll: ScrollSignal,
+    }
+
+    #[gpui::test]
+    async fn test_signal_drives_handle(cx: &TestAppContext) {
+        let (_scroller, scroll) = cx.update(|cx| {
+            let scroller = cx.new(|cx| Scroller {
+                scroll: scroll_signal(cx),
+            });
+            let scroll = scroller.read(cx).scroll.clone();
+            (scroller, scroll)
+        });
+
+        scroll.scroll_to(point(px(0.), px(-120.)));
+        assert_eq!(scroll.handle().offset(), point(px(0.), px(-120.)));
+    }
+
+    #[gpui::test]
+    async fn test_sync_copies_handle_offset(cx: &TestAppContext) {
+        let (_scroller, scroll) = cx.update(|cx| {
+            let scroller = cx.new(|cx| Scroller {
+                scroll: scroll_signal(cx),
+            });
+            let scroll = scroller.read(cx).scroll.clone();
+            (scroller, scroll)
+        });
+
+        scroll.handle().set_offset(point(px(-10.), px(-40.)));
+        assert_eq!(scroll.offset().get(), point(px(0.), px(0.)));
+
+        scroll.sync();
+        assert_eq!(scroll.offset().get(), point(px(-10.), px(-40.)));
+    }
+}
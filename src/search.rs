@@ -0,0 +1,462 @@
+//! State for searchable lists: a query, its debounced copy, and the matching items.
+//!
+//! Typing updates [`SearchState::query`] on every keystroke, while the results are
+//! derived from [`SearchState::debounced_query`], which follows the query once it has
+//! been still for the debounce delay. Filtering a large collection therefore runs
+//! once per pause rather than once per key.
+//!
+//! While the query only grows, as it does when typing, matchers that narrow as the
+//! query grows score just the items that matched the shorter query.
+
+use crate::context::track_subscription;
+use crate::{Memo, ReadOnlySignal, Signal, SignalContext};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use gpui::{Context, Subscription};
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+
+type Score<T> = Rc<dyn Fn(&T, &str) -> Option<i64>>;
+
+/// How a [`SearchState`] decides which items match a query, and in what order.
+///
+/// The query passed to a matcher is trimmed and never empty; an empty query matches
+/// every item in source order without calling the matcher.
+pub struct Matcher<T> {
+    score: Score<T>,
+    sorted: bool,
+    narrowing: bool,
+}
+
+impl<T> Clone for Matcher<T> {
+    fn clone(&self) -> Self {
+        Self {
+            score: self.score.clone(),
+            sorted: self.sorted,
+            narrowing: self.narrowing,
+        }
+    }
+}
+
+impl<T: 'static> Matcher<T> {
+    /// Match items whose text contains the query, ignoring case, in source order.
+    pub fn contains<S: AsRef<str>>(text: impl Fn(&T) -> S + 'static) -> Self {
+        Self::filter(move |item, query| {
+            text(item)
+                .as_ref()
+                .to_lowercase()
+                .contains(&query.to_lowercase())
+        })
+        .narrowing()
+    }
+
+    /// Match items whose text contains the query's characters in order, ignoring
+    /// case, best first. See [`fuzzy_score`] for how matches are ranked.
+    #[cfg(feature = "fuzzy")]
+    pub fn fuzzy<S: AsRef<str>>(text: impl Fn(&T) -> S + 'static) -> Self {
+        Self::scored(move |item, query| fuzzy_score(text(item).as_ref(), query)).narrowing()
+    }
+
+    /// Match items `predicate` accepts, in source order.
+    pub fn filter(predicate: impl Fn(&T, &str) -> bool + 'static) -> Self {
+        Self {
+            score: Rc::new(move |item, query| predicate(item, query).then_some(0)),
+            sorted: false,
+            narrowing: false,
+        }
+    }
+
+    /// Match items `score` gives a score, highest first; items with equal scores keep
+    /// their source order.
+    pub fn scored(score: impl Fn(&T, &str) -> Option<i64> + 'static) -> Self {
+        Self {
+            score: Rc::new(score),
+            sorted: true,
+            narrowing: false,
+        }
+    }
+
+    /// Declare that every item matching a query also matches each shorter query it
+    /// starts with, so a search only rescores the previous matches as the query
+    /// grows.
+    pub fn narrowing(mut self) -> Self {
+        self.narrowing = true;
+        self
+    }
+
+    /// The indices of the items matching `query`, in the order to show them.
+    pub fn matches(&self, items: &[T], query: &str) -> Vec<usize> {
+        self.score_items(items, 0..items.len(), query.trim())
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    fn score_items(
+        &self,
+        items: &[T],
+        candidates: impl Iterator<Item = usize>,
+        query: &str,
+    ) -> Vec<(usize, i64)> {
+        if query.is_empty() {
+            return candidates.map(|index| (index, 0)).collect();
+        }
+        let mut scored: Vec<(usize, i64)> = candidates
+            .filter_map(|index| (self.score)(&items[index], query).map(|score| (index, score)))
+            .collect();
+        if self.sorted {
+            // Equal scores stay in source order, whatever order the candidates came in.
+            scored.sort_by_key(|&(index, score)| (std::cmp::Reverse(score), index));
+        }
+        scored
+    }
+
+    /// Scores for `query`, reusing `previous` when the query only grew since.
+    fn rescore(
+        &self,
+        items: &[T],
+        version: u64,
+        query: String,
+        previous: Option<&Scores>,
+    ) -> Scores {
+        let scored = match previous {
+            Some(previous)
+                if self.narrowing
+                    && previous.source_version == version
+                    && query.starts_with(&previous.query) =>
+            {
+                let mut candidates: Vec<usize> =
+                    previous.scored.iter().map(|&(index, _)| index).collect();
+                candidates.sort_unstable();
+                self.score_items(items, candidates.into_iter(), &query)
+            }
+            _ => self.score_items(items, 0..items.len(), &query),
+        };
+        Scores {
+            query,
+            source_version: version,
+            scored,
+        }
+    }
+}
+
+/// The scored matches for one query over one version of the source.
+#[derive(Clone)]
+struct Scores {
+    query: String,
+    source_version: u64,
+    scored: Vec<(usize, i64)>,
+}
+
+/// Score `text` against `query` when the query's characters appear in it in order,
+/// ignoring case, or `None` when they don't.
+///
+/// Higher is better: matches at the start of a word or right after the previous
+/// matched character score more, and characters skipped before a match score less,
+/// so `"nr"` ranks `"new_request"` above `"inner"`.
+#[cfg(feature = "fuzzy")]
+pub fn fuzzy_score(text: &str, query: &str) -> Option<i64> {
+    const MATCH: i64 = 16;
+    const WORD_START: i64 = 24;
+    const CONSECUTIVE: i64 = 16;
+    const SKIPPED: i64 = 1;
+
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+    for character in text.chars() {
+        let Some(&wanted) = query.peek() else {
+            break;
+        };
+        let word_start = match previous {
+            None => true,
+            Some(previous) => {
+                !previous.is_alphanumeric() || (previous.is_lowercase() && character.is_uppercase())
+            }
+        };
+        if character.to_lowercase().eq(std::iter::once(wanted)) {
+            query.next();
+            score += MATCH;
+            if word_start {
+                score += WORD_START;
+            }
+            if previous_matched {
+                score += CONSECUTIVE;
+            }
+            previous_matched = true;
+        } else {
+            score -= SKIPPED;
+            previous_matched = false;
+        }
+        previous = Some(character);
+    }
+    query.peek().is_none().then_some(score)
+}
+
+impl<T> fmt::Debug for Matcher<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Matcher")
+            .field("sorted", &self.sorted)
+            .field("narrowing", &self.narrowing)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A search over a collection signal, owned by an entity.
+///
+/// [`results`](Self::results) is a memo, so it can feed a
+/// [`ListNavigation`](crate::ListNavigation) or a list directly.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::search::{Matcher, SearchState};
+/// use std::time::Duration;
+///
+/// struct FilePicker {
+///     search: SearchState<SharedString>,
+/// }
+///
+/// impl FilePicker {
+///     fn new(files: Signal<Vec<SharedString>>, cx: &mut Context<Self>) -> Self {
+///         let matcher = Matcher::contains(|file: &SharedString| file.clone());
+///         Self {
+///             search: SearchState::new(files, matcher, Duration::from_millis(150), cx),
+///         }
+///     }
+/// }
+///
+/// impl Render for FilePicker {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         div()
+///             .child(format!("{} files", self.search.match_count().get()))
+///             .children(self.search.results().get())
+///     }
+/// }
+/// ```
+pub struct SearchState<T> {
+    query: Signal<String>,
+    debounced_query: Signal<String>,
+    matches: Memo<Vec<usize>>,
+    results: Memo<Vec<T>>,
+    match_count: Memo<usize>,
+}
+
+impl<T> Copy for SearchState<T> {}
+
+impl<T> Clone for SearchState<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for SearchState<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.query == other.query
+    }
+}
+
+impl<T> Eq for SearchState<T> {}
+
+impl<T: 'static + Clone> SearchState<T> {
+    /// Search `source` with `matcher`, following the query once it has been still for
+    /// `debounce`.
+    ///
+    /// The entity is notified when the query or the results change, and the search
+    /// is cleaned up when it is released.
+    pub fn new<V: 'static>(
+        source: impl Into<ReadOnlySignal<Vec<T>>>,
+        matcher: Matcher<T>,
+        debounce: Duration,
+        cx: &mut Context<V>,
+    ) -> Self {
+        let source = source.into();
+        let query = cx.create_signal(String::new());
+        let debounced_query = cx.create_signal(String::new());
+
+        let (wake, mut woken) = mpsc::unbounded();
+        query.subscribe_while(move || wake.unbounded_send(()).is_ok());
+        let task = cx.spawn(async move |_, cx| {
+            while woken.next().await.is_some() {
+                // Wait until a whole delay passes without another keystroke.
+                loop {
+                    cx.background_executor().timer(debounce).await;
+                    if woken.try_recv().is_err() {
+                        break;
+                    }
+                    while woken.try_recv().is_ok() {}
+                }
+                if query.is_alive() {
+                    debounced_query.set_if_changed(query.get_untracked());
+                }
+            }
+        });
+        track_subscription(cx, Subscription::new(move || drop(task)));
+
+        let scores = cx.create_memo_with_prev(move |previous: Option<&Scores>| {
+            let query = debounced_query.with(|query| query.trim().to_string());
+            // The version is read inside the read, after a memo source has refreshed.
+            source.with(|items| matcher.rescore(items, source.version(), query, previous))
+        });
+        let matches = cx.create_memo(move || {
+            scores.with(|scores| {
+                scores
+                    .scored
+                    .iter()
+                    .map(|&(index, _)| index)
+                    .collect::<Vec<_>>()
+            })
+        });
+        let results = cx.create_memo(move || {
+            let matches = matches.get();
+            source.with(|items| matches.iter().map(|&index| items[index].clone()).collect())
+        });
+        let match_count = cx.create_memo(move || matches.with(Vec::len));
+        Self {
+            query,
+            debounced_query,
+            matches,
+            results,
+            match_count,
+        }
+    }
+
+    /// The query as typed.
+    pub fn query(&self) -> ReadOnlySignal<String> {
+        self.query.read_only()
+    }
+
+    /// Replace the query; the results follow after the debounce delay.
+    #[track_caller]
+    pub fn set_query(&self, query: impl Into<String>) {
+        self.query.set_if_changed(query.into());
+    }
+
+    /// Clear the query and show every item at once, without waiting for the delay.
+    #[track_caller]
+    pub fn clear(&self) {
+        self.query.set_if_changed(String::new());
+        self.debounced_query.set_if_changed(String::new());
+    }
+
+    /// The query the results were computed from.
+    pub fn debounced_query(&self) -> ReadOnlySignal<String> {
+        self.debounced_query.read_only()
+    }
+
+    /// A memo of the matching items' indices in the source, in display order.
+    pub fn matches(&self) -> Memo<Vec<usize>> {
+        self.matches
+    }
+
+    /// A memo of the matching items, in display order.
+    pub fn results(&self) -> Memo<Vec<T>> {
+        self.results
+    }
+
+    /// A memo of the number of matching items.
+    pub fn match_count(&self) -> Memo<usize> {
+        self.match_count
+    }
+}
+
+impl<T: 'static> fmt::Debug for SearchState<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SearchState")
+            .field("query", &self.query.get_untracked())
+            .field("debounced_query", &self.debounced_query.get_untracked())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{div, AppContext, IntoElement, Render, TestAppContext, Window};
+
+    struct Picker {
+        search: SearchState<&'static str>,
+    }
+
+    impl Render for Picker {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div()
+        }
+    }
+
+    #[gpui::test]
+    async fn test_results_follow_the_query_after_a_pause(cx: &mut TestAppContext) {
+        let view = cx.new(|cx| {
+            let files = cx.create_signal(vec!["main.rs", "lib.rs", "README.md"]);
+            Picker {
+                search: SearchState::new(
+                    files,
+                    Matcher::contains(|file: &&str| *file),
+                    Duration::from_millis(100),
+                    cx,
+                ),
+            }
+        });
+        let search = view.read_with(cx, |view, _| view.search);
+        assert_eq!(search.match_count().get(), 3);
+
+        search.set_query("r");
+        cx.executor().advance_clock(Duration::from_millis(60));
+        search.set_query("RS");
+        cx.executor().advance_clock(Duration::from_millis(60));
+        assert_eq!(search.debounced_query().get(), "");
+        cx.executor().advance_clock(Duration::from_millis(100));
+        assert_eq!(search.results().get(), ["main.rs", "lib.rs"]);
+        assert_eq!(search.matches().get(), [0, 1]);
+
+        search.clear();
+        assert_eq!(search.match_count().get(), 3);
+
+        let shortest_first = Matcher::scored(|file: &&str, query| {
+            file.contains(query).then(|| -(file.len() as i64))
+        });
+        assert_eq!(
+            shortest_first.matches(&["main.rs", "lib.rs", "a.md"], " rs "),
+            [1, 0]
+        );
+    }
+
+    #[cfg(feature = "fuzzy")]
+    #[gpui::test]
+    async fn test_fuzzy_matches_rescore_only_previous_matches(cx: &mut TestAppContext) {
+        use std::cell::Cell;
+
+        let scored = Rc::new(Cell::new(0));
+        let view = cx.new(|cx| {
+            let files = cx.create_signal(vec!["inner", "new_request", "main", "nav"]);
+            let scored = scored.clone();
+            let matcher = Matcher::fuzzy(move |file: &&str| {
+                scored.set(scored.get() + 1);
+                *file
+            });
+            Picker {
+                search: SearchState::new(files, matcher, Duration::from_millis(100), cx),
+            }
+        });
+        let search = view.read_with(cx, |view, _| view.search);
+
+        search.set_query("n");
+        cx.executor().advance_clock(Duration::from_millis(100));
+        assert_eq!(search.match_count().get(), 4);
+        assert_eq!(scored.take(), 4);
+
+        search.set_query("nr");
+        cx.executor().advance_clock(Duration::from_millis(100));
+        assert_eq!(search.results().get(), ["new_request", "inner"]);
+        assert_eq!(scored.take(), 4);
+
+        search.set_query("nre");
+        cx.executor().advance_clock(Duration::from_millis(100));
+        assert_eq!(search.results().get(), ["new_request"]);
+        assert_eq!(scored.take(), 2);
+    }
+}
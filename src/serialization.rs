@@ -0,0 +1,215 @@
+//! One registry for turning type-erased signal values into bytes and back.
+//!
+//! Snapshots, persistence, recorders, and IPC all hold values whose type they don't
+//! know statically. Registering a type once with [`register_serde`] lets each of them
+//! encode a `&dyn Any` of that type with [`to_bytes`] and decode it with
+//! [`from_bytes`], rather than each asking for its own conversion. Values are
+//! encoded as JSON.
+//!
+//! ```rust
+//! use gpui_signals::serialization::{self, SerializableSignal};
+//! use gpui_signals::Signal;
+//!
+//! serialization::register_serde::<Vec<String>>();
+//!
+//! let tabs = Signal::new(vec!["main.rs".to_string()]);
+//! let saved = SerializableSignal::from(tabs).to_bytes()?;
+//!
+//! let restored = Signal::new(Vec::<String>::new());
+//! SerializableSignal::from(restored).load_bytes(&saved)?;
+//! assert_eq!(restored.get(), ["main.rs"]);
+//! # Ok::<(), serialization::SerializationError>(())
+//! ```
+
+use crate::signal::Signal;
+use crate::storage::SignalId;
+use parking_lot::RwLock;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::any::{Any, TypeId};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::rc::Rc;
+
+struct Codec {
+    encode: fn(&dyn Any) -> serde_json::Result<Vec<u8>>,
+    decode: fn(&[u8]) -> serde_json::Result<Box<dyn Any>>,
+}
+
+static CODECS: RwLock<BTreeMap<TypeId, Codec>> = RwLock::new(BTreeMap::new());
+
+/// Let values of type `T` be converted with [`to_bytes`] and [`from_bytes`].
+///
+/// Registering a type again has no further effect.
+pub fn register_serde<T: Serialize + DeserializeOwned + 'static>() {
+    CODECS.write().insert(
+        TypeId::of::<T>(),
+        Codec {
+            encode: encode::<T>,
+            decode: decode::<T>,
+        },
+    );
+}
+
+/// Whether values with the type `type_id` have been registered.
+pub fn is_registered(type_id: TypeId) -> bool {
+    CODECS.read().contains_key(&type_id)
+}
+
+/// Encode `value` with the codec registered for its type.
+pub fn to_bytes(value: &dyn Any) -> Result<Vec<u8>, SerializationError> {
+    let encode = CODECS
+        .read()
+        .get(&value.type_id())
+        .map(|codec| codec.encode)
+        .ok_or(SerializationError::NotRegistered)?;
+    encode(value).map_err(|error| SerializationError::Format(error.to_string()))
+}
+
+/// Decode bytes written by [`to_bytes`] as a value with the type `type_id`.
+pub fn from_bytes(type_id: TypeId, bytes: &[u8]) -> Result<Box<dyn Any>, SerializationError> {
+    let decode = CODECS
+        .read()
+        .get(&type_id)
+        .map(|codec| codec.decode)
+        .ok_or(SerializationError::NotRegistered)?;
+    decode(bytes).map_err(|error| SerializationError::Format(error.to_string()))
+}
+
+fn encode<T: Serialize + 'static>(value: &dyn Any) -> serde_json::Result<Vec<u8>> {
+    let value = value
+        .downcast_ref::<T>()
+        .expect("codecs are looked up by the value's type");
+    serde_json::to_vec(value)
+}
+
+fn decode<T: DeserializeOwned + 'static>(bytes: &[u8]) -> serde_json::Result<Box<dyn Any>> {
+    Ok(Box::new(serde_json::from_slice::<T>(bytes)?))
+}
+
+/// Why a value couldn't be converted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SerializationError {
+    /// The value's type was not registered with [`register_serde`].
+    NotRegistered,
+    /// The value couldn't be encoded, or the bytes didn't decode as the type.
+    Format(String),
+    /// The signal was disposed.
+    Disposed,
+}
+
+impl fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotRegistered => f.write_str("the value's type is not registered"),
+            Self::Format(error) => write!(f, "the value could not be converted: {error}"),
+            Self::Disposed => f.write_str("the signal was disposed"),
+        }
+    }
+}
+
+impl std::error::Error for SerializationError {}
+
+/// A signal of any type, converted through the registry.
+///
+/// Code that keeps signals of many types together, such as a store persisting a
+/// window's state, holds these instead of the typed handles. Converting fails with
+/// [`SerializationError::NotRegistered`] unless the signal's value type was
+/// registered.
+#[derive(Clone)]
+pub struct SerializableSignal {
+    id: SignalId,
+    type_id: TypeId,
+    save: Rc<dyn Fn() -> Result<Vec<u8>, SerializationError>>,
+    load: Rc<dyn Fn(Box<dyn Any>) -> bool>,
+}
+
+impl SerializableSignal {
+    /// The signal's id.
+    pub fn id(&self) -> SignalId {
+        self.id
+    }
+
+    /// The type of the signal's value.
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// Encode the signal's current value, without tracking the read.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SerializationError> {
+        (self.save)()
+    }
+
+    /// Decode `bytes` and set the signal to the value.
+    pub fn load_bytes(&self, bytes: &[u8]) -> Result<(), SerializationError> {
+        let value = from_bytes(self.type_id, bytes)?;
+        if (self.load)(value) {
+            Ok(())
+        } else {
+            Err(SerializationError::Disposed)
+        }
+    }
+}
+
+impl<T: 'static> From<Signal<T>> for SerializableSignal {
+    fn from(signal: Signal<T>) -> Self {
+        Self {
+            id: signal.id(),
+            type_id: TypeId::of::<T>(),
+            save: Rc::new(move || {
+                if !signal.is_alive() {
+                    return Err(SerializationError::Disposed);
+                }
+                // Encoded inside the read, so the value isn't cloned first.
+                signal.with_untracked(|value| to_bytes(value))
+            }),
+            load: Rc::new(move |value| {
+                let Ok(value) = value.downcast::<T>() else {
+                    return false;
+                };
+                if !signal.is_alive() {
+                    return false;
+                }
+                signal.set(*value);
+                true
+            }),
+        }
+    }
+}
+
+impl fmt::Debug for SerializableSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SerializableSignal")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_round_trip_through_the_registry() {
+        register_serde::<(u32, String)>();
+        let cursor = Signal::new((3u32, String::from("lib.rs")));
+        let erased = SerializableSignal::from(cursor);
+        let bytes = erased.to_bytes().unwrap();
+
+        cursor.set((0, String::new()));
+        erased.load_bytes(&bytes).unwrap();
+        assert_eq!(cursor.get(), (3, String::from("lib.rs")));
+        assert!(matches!(
+            erased.load_bytes(b"not json"),
+            Err(SerializationError::Format(_))
+        ));
+
+        let unregistered = SerializableSignal::from(Signal::new(1.5f64));
+        assert_eq!(
+            unregistered.to_bytes(),
+            Err(SerializationError::NotRegistered)
+        );
+        cursor.dispose();
+        assert_eq!(erased.to_bytes(), Err(SerializationError::Disposed));
+    }
+}
@@ -0,0 +1,122 @@
+//! A registry of long-lived services, such as a database client or a language server
+//! connection, held in signals.
+//!
+//! A service is provided once at startup and looked up by type from anywhere on the
+//! thread. Lookups return a signal rather than the service itself, so a view that
+//! reads it while rendering re-renders when the service is replaced, for example after
+//! a reconnect, instead of holding on to the old handle.
+//!
+//! ```rust
+//! use gpui_signals::services;
+//! use std::sync::Arc;
+//!
+//! struct Database {
+//!     url: String,
+//! }
+//!
+//! services::provide(Arc::new(Database { url: "postgres://primary".into() }));
+//! let database = services::require::<Database>();
+//! assert_eq!(database.with(|db| db.url.clone()), "postgres://primary");
+//!
+//! // Reconnecting to a replica updates every reader of the signal.
+//! services::provide(Arc::new(Database { url: "postgres://replica".into() }));
+//! assert_eq!(database.with(|db| db.url.clone()), "postgres://replica");
+//! ```
+//!
+//! Services can also be provided as trait objects, such as `Arc<dyn Clock>`, and are
+//! then looked up with that same type.
+
+use crate::{ReadOnlySignal, Signal};
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+thread_local! {
+    static SERVICES: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Register `service`, or replace the one of the same type and notify its readers.
+pub fn provide<S: ?Sized + 'static>(service: Arc<S>) {
+    match signal::<S>() {
+        // Set outside the registry borrow, since subscribers may look up services.
+        Some(signal) => signal.set(service),
+        None => {
+            let signal = Signal::new(service);
+            SERVICES.with(|services| {
+                services
+                    .borrow_mut()
+                    .insert(TypeId::of::<S>(), Box::new(signal))
+            });
+        }
+    }
+}
+
+/// The signal holding the service of type `S`, if one was provided.
+pub fn get<S: ?Sized + 'static>() -> Option<ReadOnlySignal<Arc<S>>> {
+    signal::<S>().map(Signal::read_only)
+}
+
+/// The signal holding the service of type `S`.
+///
+/// # Panics
+///
+/// Panics if no service of type `S` was provided.
+#[track_caller]
+pub fn require<S: ?Sized + 'static>() -> ReadOnlySignal<Arc<S>> {
+    get::<S>().unwrap_or_else(|| {
+        panic!(
+            "no service of type `{}` was provided; call `services::provide` at startup",
+            std::any::type_name::<S>()
+        )
+    })
+}
+
+fn signal<S: ?Sized + 'static>() -> Option<Signal<Arc<S>>> {
+    SERVICES.with(|services| {
+        services.borrow().get(&TypeId::of::<S>()).map(|signal| {
+            *signal
+                .downcast_ref::<Signal<Arc<S>>>()
+                .expect("services are keyed by their type")
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Effect;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    trait Connection {
+        fn endpoint(&self) -> &str;
+    }
+
+    struct Server(&'static str);
+
+    impl Connection for Server {
+        fn endpoint(&self) -> &str {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_replacing_a_service_notifies_readers() {
+        assert!(get::<dyn Connection>().is_none());
+        provide::<dyn Connection>(Arc::new(Server("primary")));
+        let connection = require::<dyn Connection>();
+        let runs = Rc::new(Cell::new(0));
+        let _effect = Effect::new({
+            let runs = runs.clone();
+            move || {
+                connection.with(|connection| connection.endpoint().len());
+                runs.set(runs.get() + 1);
+            }
+        });
+
+        provide::<dyn Connection>(Arc::new(Server("replica")));
+        assert_eq!(runs.get(), 2);
+        assert_eq!(connection.get_untracked().endpoint(), "replica");
+    }
+}
@@ -1,10 +1,19 @@
 //! Core Signal type and operations.
 
-use crate::storage::{with_signal_storage, SignalId};
-use gpui::{IntoElement, SharedString};
+use crate::restricted::{Permissions, RestrictedSignal};
+use crate::runtime::{SignalRuntime, StaleHandle, StaleHandlePolicy};
+use crate::scheduler::Notifications;
+use crate::storage::{
+    check_thread, runtime_storage, with_runtime_storage, RuntimeId, SignalId, SignalStorage,
+    Subscriber,
+};
+use std::cell::{Cell, OnceCell};
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+use std::thread::ThreadId;
 
 
 /// A reactive signal that holds a value of type `T`.
@@ -35,6 +44,8 @@ use std::marker::PhantomData;
 pub struct Signal<T> {
     id: SignalId,
     generation: u32,
+    runtime: RuntimeId,
+    thread: ThreadId,
     _phantom: PhantomData<T>,
 }
 
@@ -48,7 +59,7 @@ impl<T> Clone for Signal<T> {
 
 impl<T> PartialEq for Signal<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.id == other.id
+        self.id == other.id && self.runtime == other.runtime
     }
 }
 
@@ -57,35 +68,61 @@ impl<T> Eq for Signal<T> {}
 impl<T> Hash for Signal<T> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.id.hash(state);
+        self.runtime.hash(state);
     }
 }
 
 impl Signal<bool> {
     /// Toggle the boolean value of the signal.
+    #[track_caller]
     pub fn toggle(&self) {
         self.update(|v| *v = !*v);
     }
 }
 
-impl<T: fmt::Display + Clone + 'static> IntoElement for Signal<T> {
-    type Element = SharedString;
-
-    fn into_element(self) -> Self::Element {
-        self.get().to_string().into()
+impl<T: Clone + 'static> Signal<Arc<T>> {
+    /// Update the shared value in place, copying it first only if it is still shared.
+    ///
+    /// Readers holding a clone of the `Arc`, such as a task still working from an
+    /// earlier snapshot, keep the value they have; the copy is made only while such a
+    /// clone exists. Otherwise a large document is edited without ever holding two
+    /// copies of it.
+    #[track_caller]
+    pub fn update_cow(&self, f: impl FnOnce(&mut T)) {
+        self.update(|value| f(Arc::make_mut(value)));
     }
 }
 
 impl<T: 'static> Signal<T> {
     /// Create a new signal with the given initial value.
-    pub(crate) fn new(value: T) -> Self {
-        with_signal_storage(|storage| {
-            let id = storage.insert(value);
-            Self {
-                id,
-                generation: 0,
-                _phantom: PhantomData,
-            }
-        })
+    ///
+    /// In a GPUI view, prefer `cx.create_signal`, which also re-renders the view when
+    /// the signal changes.
+    pub fn new(value: T) -> Self {
+        Self::new_in(value, &SignalRuntime::default())
+    }
+
+    /// Create a new signal in `runtime`.
+    pub fn new_in(value: T, runtime: &SignalRuntime) -> Self {
+        runtime.with_storage(|storage| Self::insert(storage, value))
+    }
+
+    /// Create a signal in `runtime` whose value is filled in later with `set`.
+    ///
+    /// Reading it before then panics, as for a disposed signal.
+    pub(crate) fn reserve_in(runtime: &SignalRuntime) -> Self {
+        runtime.with_storage(|storage| Self::insert(storage, ()))
+    }
+
+    fn insert<V: 'static>(storage: &mut SignalStorage, value: V) -> Self {
+        let (id, generation) = storage.insert(value);
+        Self {
+            id,
+            generation,
+            runtime: storage.runtime(),
+            thread: storage.thread(),
+            _phantom: PhantomData,
+        }
     }
 
     /// Get the current value of the signal.
@@ -95,8 +132,7 @@ impl<T: 'static> Signal<T> {
     where
         T: Clone,
     {
-        with_signal_storage(|storage| {
-            storage.track_read(self.id);
+        self.read_storage(true, |storage| {
             storage
                 .get::<T>(self.id, self.generation)
                 .cloned()
@@ -104,6 +140,19 @@ impl<T: 'static> Signal<T> {
         })
     }
 
+    /// Get the current value, or `None` if the signal has been disposed.
+    ///
+    /// This will track the read if called within a reactive context and the signal
+    /// is still alive.
+    pub fn try_get(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.read_storage(true, |storage| {
+            storage.get::<T>(self.id, self.generation).cloned()
+        })
+    }
+
     /// Get a clone of the current value without tracking the read.
     ///
     /// Use this when you want to read a signal without subscribing to it.
@@ -111,7 +160,7 @@ impl<T: 'static> Signal<T> {
     where
         T: Clone,
     {
-        with_signal_storage(|storage| {
+        self.read_storage(false, |storage| {
             storage
                 .get::<T>(self.id, self.generation)
                 .cloned()
@@ -119,27 +168,89 @@ impl<T: 'static> Signal<T> {
         })
     }
 
+    /// Get the current value, or `T::default()` if the signal has been disposed and
+    /// the runtime's [`StaleHandlePolicy`](crate::StaleHandlePolicy) lets the read
+    /// carry on.
+    ///
+    /// This will track the read if called within a reactive context.
+    #[track_caller]
+    pub fn get_or_default(&self) -> T
+    where
+        T: Clone + Default,
+    {
+        match self.try_get() {
+            Some(value) => value,
+            None => {
+                self.report_stale();
+                T::default()
+            }
+        }
+    }
+
     /// Set the signal to a new value.
     ///
     /// This will notify all subscribers of the change.
+    #[track_caller]
     pub fn set(&self, value: T) {
-        if let Some(callbacks) =
-            with_signal_storage(|storage| storage.set(self.id, self.generation, value))
-        {
-            for callback in callbacks {
-                callback();
-            }
+        #[cfg(debug_assertions)]
+        self.check_writable();
+        #[cfg(any(debug_assertions, feature = "provenance"))]
+        self.record_writer();
+        match self.with_storage(|storage| storage.set(self.id, self.generation, value)) {
+            Some(callbacks) => self.deliver(callbacks),
+            None => self.report_stale(),
         }
     }
 
+    /// Set the value without notifying subscribers or marking dependents dirty.
+    pub(crate) fn set_without_notify(&self, value: T) {
+        self.with_storage(|storage| storage.replace(self.id, self.generation, value));
+    }
+
+    /// Set the signal to a new value, tagging the change with where it came from.
+    ///
+    /// Subscribers added with [`subscribe_with_origin`](Self::subscribe_with_origin)
+    /// receive `origin`, so a sync engine or undo manager can skip the changes it
+    /// made itself instead of guarding its writes with a flag.
+    #[track_caller]
+    pub fn set_with_origin(&self, value: T, origin: ChangeOrigin) {
+        #[cfg(debug_assertions)]
+        self.check_writable();
+        #[cfg(any(debug_assertions, feature = "provenance"))]
+        self.record_writer();
+        let callbacks = self.with_storage(|storage| {
+            let callbacks = storage.set(self.id, self.generation, value)?;
+            storage.set_origin(self.id, origin);
+            Some(callbacks)
+        });
+        match callbacks {
+            Some(callbacks) => self.deliver(callbacks),
+            None => self.report_stale(),
+        }
+    }
+
+    /// The origin the latest write was tagged with, or `None` if it wasn't written
+    /// with [`set_with_origin`](Self::set_with_origin).
+    pub fn last_origin(&self) -> Option<ChangeOrigin> {
+        self.with_storage(|storage| storage.origin(self.id, self.generation))
+    }
+
     /// Set the signal only if the value has changed.
     ///
     /// Returns true if the value was updated.
+    #[track_caller]
     pub fn set_if_changed(&self, value: T) -> bool
     where
         T: PartialEq,
     {
-        let should_update = self.with_untracked(|current| current != &value);
+        let Some(should_update) = self.read_storage(false, |storage| {
+            storage
+                .get::<T>(self.id, self.generation)
+                .map(|current| current != &value)
+        }) else {
+            self.report_stale();
+            return false;
+        };
         if should_update {
             self.set(value);
         }
@@ -151,28 +262,34 @@ impl<T: 'static> Signal<T> {
     /// This will notify all subscribers of the change.
     ///
     /// This will notify all subscribers of the change.
+    #[track_caller]
     pub fn update(&self, f: impl FnOnce(&mut T)) {
-        if let Some((_, callbacks)) =
-            with_signal_storage(|storage| storage.update(self.id, self.generation, f))
-        {
-            for callback in callbacks {
-                callback();
-            }
+        #[cfg(debug_assertions)]
+        self.check_writable();
+        #[cfg(any(debug_assertions, feature = "provenance"))]
+        self.record_writer();
+        match self.with_storage(|storage| storage.update(self.id, self.generation, f)) {
+            Some((_, callbacks)) => self.deliver(callbacks),
+            None => self.report_stale(),
         }
     }
 
     /// Update the signal's value with a closure and return a result.
     ///
     /// This will notify all subscribers of the change.
+    #[track_caller]
     pub fn update_with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        #[cfg(debug_assertions)]
+        self.check_writable();
+        #[cfg(any(debug_assertions, feature = "provenance"))]
+        self.record_writer();
         if let Some((result, callbacks)) =
-            with_signal_storage(|storage| storage.update(self.id, self.generation, f))
+            self.with_storage(|storage| storage.update(self.id, self.generation, f))
         {
-            for callback in callbacks {
-                callback();
-            }
+            self.deliver(callbacks);
             return Some(result);
         }
+        self.report_stale();
         None
     }
 
@@ -180,8 +297,7 @@ impl<T: 'static> Signal<T> {
     ///
     /// This will track the read if called within a reactive context.
     pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
-        with_signal_storage(|storage| {
-            storage.track_read(self.id);
+        self.read_storage(true, |storage| {
             let value = storage
                 .get::<T>(self.id, self.generation)
                 .expect("Signal value not found");
@@ -189,9 +305,34 @@ impl<T: 'static> Signal<T> {
         })
     }
 
+    /// Read the signal's value with a closure, or read `T::default()` if the signal
+    /// has been disposed and the runtime's
+    /// [`StaleHandlePolicy`](crate::StaleHandlePolicy) lets the read carry on.
+    ///
+    /// This will track the read if called within a reactive context.
+    #[track_caller]
+    pub fn with_or_default<R>(&self, f: impl FnOnce(&T) -> R) -> R
+    where
+        T: Default,
+    {
+        let read = self.read_storage(true, |storage| {
+            match storage.get::<T>(self.id, self.generation) {
+                Some(value) => Ok(f(value)),
+                None => Err(f),
+            }
+        });
+        match read {
+            Ok(result) => result,
+            Err(f) => {
+                self.report_stale();
+                f(&T::default())
+            }
+        }
+    }
+
     /// Read the signal's value with a closure without tracking.
     pub fn with_untracked<R>(&self, f: impl FnOnce(&T) -> R) -> R {
-        with_signal_storage(|storage| {
+        self.read_storage(false, |storage| {
             let value = storage
                 .get::<T>(self.id, self.generation)
                 .expect("Signal value not found");
@@ -202,9 +343,103 @@ impl<T: 'static> Signal<T> {
     /// Subscribe to changes on this signal.
     ///
     /// The callback will be called whenever the signal's value changes.
+    #[track_caller]
     pub fn subscribe(&self, callback: impl Fn() + 'static) {
-        with_signal_storage(|storage| {
-            storage.subscribe(self.id, callback);
+        self.subscribe_checked(callback);
+    }
+
+    /// Add `callback` to the signal's subscribers, or apply the runtime's stale handle
+    /// policy if the signal has been disposed.
+    #[track_caller]
+    fn subscribe_checked(&self, callback: impl Fn() + 'static) -> Option<Subscriber> {
+        let subscriber = self.with_storage(|storage| {
+            storage
+                .contains(self.id, self.generation)
+                .then(|| storage.subscribe(self.id, callback))
+        });
+        if subscriber.is_none() {
+            self.report_stale();
+        }
+        subscriber
+    }
+
+    /// Subscribe to changes on this signal, receiving the origin of each change.
+    ///
+    /// The origin is the one passed to [`set_with_origin`](Self::set_with_origin), or
+    /// `None` for other writes. When a scheduler delivers several writes at once,
+    /// the callback sees the origin of the latest.
+    #[track_caller]
+    pub fn subscribe_with_origin(&self, callback: impl Fn(Option<ChangeOrigin>) + 'static) {
+        let signal = *self;
+        self.subscribe(move || callback(signal.last_origin()));
+    }
+
+    /// Subscribe to the next change only.
+    ///
+    /// The callback is removed after it runs, which suits one-shot waits such as
+    /// running something when data first becomes available.
+    #[track_caller]
+    pub fn subscribe_once(&self, callback: impl FnOnce() + 'static) {
+        let callback = Cell::new(Some(callback));
+        self.subscribe_while(move || {
+            if let Some(callback) = callback.take() {
+                callback();
+            }
+            false
+        });
+    }
+
+    /// Subscribe `callback`, returning a function that removes it.
+    #[cfg(feature = "gpui")]
+    #[track_caller]
+    pub(crate) fn subscribe_removable(&self, callback: impl Fn() + 'static) -> impl FnOnce() {
+        let signal = *self;
+        let subscriber = self.subscribe_checked(callback);
+        move || {
+            if let Some(subscriber) = subscriber {
+                let removed =
+                    signal.with_storage(|storage| storage.unsubscribe(signal.id, &subscriber));
+                drop(removed);
+            }
+        }
+    }
+
+    /// Subscribe `callback` until it returns `false`.
+    #[track_caller]
+    pub(crate) fn subscribe_while(&self, callback: impl Fn() -> bool + 'static) {
+        let signal = *self;
+        // Weak, since the subscriber holds this cell and storage holds the subscriber.
+        let registered = Rc::new(OnceCell::<Weak<dyn Fn()>>::new());
+        let subscriber = self.subscribe_checked({
+            let registered = registered.clone();
+            move || {
+                if callback() {
+                    return;
+                }
+                if let Some(subscriber) = registered.get().and_then(Weak::upgrade) {
+                    let removed =
+                        signal.with_storage(|storage| storage.unsubscribe(signal.id, &subscriber));
+                    drop(removed);
+                }
+            }
+        });
+        if let Some(subscriber) = subscriber {
+            let _ = registered.set(Rc::downgrade(&subscriber));
+        }
+    }
+
+    /// Subscribe to changes after which `predicate` holds for the new value.
+    #[track_caller]
+    pub fn subscribe_when(
+        &self,
+        predicate: impl Fn(&T) -> bool + 'static,
+        callback: impl Fn() + 'static,
+    ) {
+        let signal = *self;
+        self.subscribe(move || {
+            if signal.with_untracked(&predicate) {
+                callback();
+            }
         });
     }
 
@@ -213,10 +448,231 @@ impl<T: 'static> Signal<T> {
         ReadOnlySignal { inner: self }
     }
 
+    /// Create a handle limited to `permissions`, to hand to code such as a plugin
+    /// that shouldn't have full access to the signal.
+    ///
+    /// Each call grants a new capability that the signal's storage keeps until it is
+    /// [revoked](RestrictedSignal::revoke) or the signal is disposed, so revoke
+    /// handles you are done with, such as when a plugin unloads, rather than calling
+    /// this repeatedly. See [`RestrictedSignal`].
+    pub fn restricted(self, permissions: Permissions) -> RestrictedSignal<T> {
+        RestrictedSignal::new(self, permissions)
+    }
+
     /// Get the underlying signal ID (mainly for debugging).
     pub fn id(&self) -> SignalId {
         self.id
     }
+
+    /// Whether the signal has not been disposed.
+    pub(crate) fn is_alive(&self) -> bool {
+        self.with_storage(|storage| storage.contains(self.id, self.generation))
+    }
+
+    /// Free the signal's value and subscribers.
+    ///
+    /// Signals created with `cx.create_signal` and the other context helpers are
+    /// disposed automatically when their entity is released. Reading the value after
+    /// disposing it panics; writes and subscriptions follow the runtime's
+    /// [`StaleHandlePolicy`](crate::StaleHandlePolicy).
+    pub fn dispose(self) {
+        let disposed = self.with_storage(|storage| storage.dispose(self.id));
+        drop(disposed);
+    }
+
+    /// The number of writes to this signal so far.
+    ///
+    /// The version only grows, so a cache can store it next to data derived from the
+    /// value and later compare, without cloning the value or subscribing. Every write
+    /// counts, including `update` calls that leave the value equal. This doesn't track
+    /// the read.
+    #[track_caller]
+    pub fn version(&self) -> u64 {
+        self.read_storage(false, |storage| storage.version(self.id, self.generation))
+            .unwrap_or_else(|| {
+                self.report_stale();
+                0
+            })
+    }
+
+    /// Whether the signal has been written since it was at `version`.
+    pub fn changed_since(&self, version: u64) -> bool {
+        self.version() != version
+    }
+
+    /// Access the storage for a read, first recomputing the value if it is a memo
+    /// that has been marked dirty, and recording the read under the current observer
+    /// if `track` is set.
+    ///
+    /// Only those two steps take the storage mutably. Most reads need neither, so
+    /// they take a shared borrow and can nest inside reads of other signals.
+    fn read_storage<R>(&self, track: bool, f: impl FnOnce(&SignalStorage) -> R) -> R {
+        check_thread(self.thread);
+        let runtime = runtime_storage(self.runtime);
+        let (dirty, tracking) =
+            runtime.read(|storage| (storage.is_dirty(self.id), track && storage.is_tracking()));
+        if dirty {
+            if let Some(refresh) = runtime.with(|storage| storage.take_dirty_refresh(self.id)) {
+                refresh();
+            }
+        }
+        if tracking {
+            runtime.with(|storage| {
+                if storage.contains(self.id, self.generation) {
+                    storage.track_read(self.id);
+                }
+            });
+        }
+        runtime.read(f)
+    }
+
+    /// Apply the runtime's stale handle policy to an access that found the signal
+    /// disposed.
+    #[track_caller]
+    fn report_stale(&self) {
+        let error = StaleHandle {
+            signal: self.id,
+            location: std::panic::Location::caller(),
+        };
+        let (policy, handler) = self.with_storage(|storage| {
+            (
+                storage.stale_handle_policy(),
+                storage.stale_handle_handler(),
+            )
+        });
+        match policy {
+            StaleHandlePolicy::Panic => panic!("{error}"),
+            StaleHandlePolicy::LogAndDefault => log::warn!("{error}"),
+            StaleHandlePolicy::Error => {
+                if let Some(handler) = handler {
+                    handler(&error);
+                }
+            }
+        }
+    }
+
+    /// Panic if this write happens while rendering or computing a memo.
+    ///
+    /// Such a write notifies the view being rendered, or the memo's own readers,
+    /// which re-renders or recomputes and writes again, looping in a way that is hard
+    /// to trace back to the write.
+    #[cfg(debug_assertions)]
+    #[track_caller]
+    fn check_writable(&self) {
+        if self.with_storage(|storage| storage.is_read_only()) {
+            let previous = self
+                .last_writer()
+                .map(|location| format!(" It was last written at {location}."))
+                .unwrap_or_default();
+            panic!(
+                "Signal {:?} was written at {} while rendering or computing a memo. Writes \
+                 there re-trigger the render or memo and can loop; move the write to an \
+                 event handler or an effect.{previous}",
+                self.id,
+                std::panic::Location::caller(),
+            );
+        }
+    }
+
+    /// Remember the caller as the signal's last writer, before subscribers run.
+    #[cfg(any(debug_assertions, feature = "provenance"))]
+    #[track_caller]
+    fn record_writer(&self) {
+        let location = std::panic::Location::caller();
+        self.with_storage(|storage| storage.set_last_writer(self.id, self.generation, location));
+    }
+
+    /// Where the signal was last written with `set`, `update`, or the operators that
+    /// call them.
+    ///
+    /// Locations are recorded in debug builds, and in release builds with the
+    /// `provenance` feature; otherwise this is always `None`. Also `None` before the
+    /// first write and once the signal is disposed.
+    pub fn last_writer(&self) -> Option<&'static std::panic::Location<'static>> {
+        #[cfg(any(debug_assertions, feature = "provenance"))]
+        return self.with_storage(|storage| storage.last_writer(self.id, self.generation));
+        #[cfg(not(any(debug_assertions, feature = "provenance")))]
+        None
+    }
+
+    /// Run `f` with writes to this signal's runtime allowed or forbidden.
+    pub(crate) fn with_read_only<R>(&self, read_only: bool, f: impl FnOnce() -> R) -> R {
+        let previous = self.with_storage(|storage| storage.set_read_only(read_only));
+        let result = f();
+        self.with_storage(|storage| storage.set_read_only(previous));
+        result
+    }
+
+    /// Hand the callbacks produced by a write to the runtime's scheduler.
+    pub(crate) fn deliver(&self, callbacks: Vec<Subscriber>) {
+        #[cfg(feature = "metrics")]
+        crate::telemetry::notifications_delivered(callbacks.len());
+        let location = self.last_writer();
+        if callbacks.is_empty() {
+            let finished = self.with_storage(|storage| {
+                storage.record_write(self.id, location);
+                storage.finish_wave()
+            });
+            if let Some((handler, wave)) = finished {
+                handler(&wave);
+            }
+            return;
+        }
+        let entered = self.with_storage(|storage| {
+            storage.record_write(self.id, location);
+            storage
+                .enter_delivery(self.id)
+                .map(|()| storage.scheduler())
+        });
+        let scheduler = match entered {
+            Ok(scheduler) => scheduler,
+            Err(error) => {
+                let handler = self.with_storage(|storage| storage.update_depth_handler());
+                match handler {
+                    Some(handler) => handler(&error),
+                    None => log::error!("{error}"),
+                }
+                return;
+            }
+        };
+        // Left on unwind too, so a panicking callback doesn't leave the depth raised.
+        let _delivery = ExitDelivery(self);
+        scheduler.schedule(Notifications::new(callbacks));
+    }
+
+    /// Run `f` with the reads it makes tracked as dependencies of this signal,
+    /// replacing the dependencies tracked by the previous run.
+    pub(crate) fn track_as_observer<R>(&self, f: impl FnOnce() -> R) -> R {
+        let previous = self.with_storage(|storage| {
+            storage.clear_dependencies(self.id);
+            storage.set_observer(Some(self.id))
+        });
+        let result = f();
+        self.with_storage(|storage| storage.set_observer(previous));
+        result
+    }
+
+    /// Access the storage of the runtime this signal lives in.
+    pub(crate) fn with_storage<R>(&self, f: impl FnOnce(&mut SignalStorage) -> R) -> R {
+        check_thread(self.thread);
+        with_runtime_storage(self.runtime, f)
+    }
+
+    /// The value's `Display` output, reusing the last output while the value is unchanged.
+    ///
+    /// This tracks the read.
+    pub fn display_text(&self) -> Arc<str>
+    where
+        T: fmt::Display,
+    {
+        self.read_storage(true, |_| ());
+        // Formatting caches the text in the storage, so this part takes it mutably.
+        self.with_storage(|storage| {
+            storage
+                .display::<T>(self.id, self.generation)
+                .expect("Signal value not found")
+        })
+    }
 }
 
 impl<T: 'static + Default> Default for Signal<T> {
@@ -236,29 +692,82 @@ impl<T: 'static + fmt::Debug + Clone> fmt::Debug for Signal<T> {
 
 // Implement common trait operations that automatically call update()
 impl<T: 'static + std::ops::AddAssign<T> + Clone> std::ops::AddAssign<T> for Signal<T> {
+    #[track_caller]
     fn add_assign(&mut self, rhs: T) {
         self.update(|v| *v += rhs);
     }
 }
 
 impl<T: 'static + std::ops::SubAssign<T> + Clone> std::ops::SubAssign<T> for Signal<T> {
+    #[track_caller]
     fn sub_assign(&mut self, rhs: T) {
         self.update(|v| *v -= rhs);
     }
 }
 
 impl<T: 'static + std::ops::MulAssign<T> + Clone> std::ops::MulAssign<T> for Signal<T> {
+    #[track_caller]
     fn mul_assign(&mut self, rhs: T) {
         self.update(|v| *v *= rhs);
     }
 }
 
 impl<T: 'static + std::ops::DivAssign<T> + Clone> std::ops::DivAssign<T> for Signal<T> {
+    #[track_caller]
     fn div_assign(&mut self, rhs: T) {
         self.update(|v| *v /= rhs);
     }
 }
 
+/// Where a change to a signal came from, passed to
+/// [`Signal::set_with_origin`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gpui_signals::{ChangeOrigin, Signal};
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+///
+/// let title = Signal::new(String::new());
+/// let uploads = Rc::new(Cell::new(0));
+/// title.subscribe_with_origin({
+///     let uploads = uploads.clone();
+///     move |origin| {
+///         // Changes that came from the server don't need to go back to it.
+///         if origin != Some(ChangeOrigin::Sync) {
+///             uploads.set(uploads.get() + 1);
+///         }
+///     }
+/// });
+///
+/// title.set_with_origin("Draft".to_string(), ChangeOrigin::UserInput);
+/// title.set_with_origin("Final".to_string(), ChangeOrigin::Sync);
+/// assert_eq!(uploads.get(), 1);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChangeOrigin {
+    /// The user edited the value.
+    UserInput,
+    /// A sync engine applied a change made elsewhere.
+    Sync,
+    /// An undo or redo restored the value.
+    Undo,
+    /// An app-defined origin.
+    Other(u32),
+}
+
+/// Ends a delivery started by [`Signal::deliver`] when dropped.
+struct ExitDelivery<'a, T: 'static>(&'a Signal<T>);
+
+impl<T: 'static> Drop for ExitDelivery<'_, T> {
+    fn drop(&mut self) {
+        if let Some((handler, wave)) = self.0.with_storage(|storage| storage.exit_delivery()) {
+            handler(&wave);
+        }
+    }
+}
+
 /// A read-only view of a signal.
 ///
 /// This prevents accidental mutations while still allowing reads and subscriptions.
@@ -297,6 +806,14 @@ impl<T: 'static> ReadOnlySignal<T> {
         self.inner.get()
     }
 
+    /// Get the current value, or `None` if the signal has been disposed.
+    pub fn try_get(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.inner.try_get()
+    }
+
     /// Get the current value without tracking the read.
     pub fn get_untracked(&self) -> T
     where
@@ -315,10 +832,106 @@ impl<T: 'static> ReadOnlySignal<T> {
         self.inner.with_untracked(f)
     }
 
+    /// Get the current value, or `T::default()` if the signal has been disposed and
+    /// the runtime's stale handle policy lets the read carry on.
+    #[track_caller]
+    pub fn get_or_default(&self) -> T
+    where
+        T: Clone + Default,
+    {
+        self.inner.get_or_default()
+    }
+
+    /// Read the value with a closure, or read `T::default()` if the signal has been
+    /// disposed and the runtime's stale handle policy lets the read carry on.
+    #[track_caller]
+    pub fn with_or_default<R>(&self, f: impl FnOnce(&T) -> R) -> R
+    where
+        T: Default,
+    {
+        self.inner.with_or_default(f)
+    }
+
     /// Subscribe to changes on this signal.
+    #[track_caller]
     pub fn subscribe(&self, callback: impl Fn() + 'static) {
         self.inner.subscribe(callback);
     }
+
+    /// Subscribe to changes on this signal, receiving the origin of each change.
+    #[track_caller]
+    pub fn subscribe_with_origin(&self, callback: impl Fn(Option<ChangeOrigin>) + 'static) {
+        self.inner.subscribe_with_origin(callback);
+    }
+
+    /// Subscribe to the next change only.
+    #[track_caller]
+    pub fn subscribe_once(&self, callback: impl FnOnce() + 'static) {
+        self.inner.subscribe_once(callback);
+    }
+
+    /// Subscribe to changes after which `predicate` holds for the new value.
+    #[track_caller]
+    pub fn subscribe_when(
+        &self,
+        predicate: impl Fn(&T) -> bool + 'static,
+        callback: impl Fn() + 'static,
+    ) {
+        self.inner.subscribe_when(predicate, callback);
+    }
+
+    /// Get the underlying signal ID (mainly for debugging).
+    pub fn id(&self) -> SignalId {
+        self.inner.id
+    }
+
+    /// Whether the signal has not been disposed.
+    #[cfg(feature = "gpui")]
+    pub(crate) fn is_alive(&self) -> bool {
+        self.inner.is_alive()
+    }
+
+    /// The value's `Display` output, reusing the last output while the value is unchanged.
+    ///
+    /// This tracks the read.
+    pub fn display_text(&self) -> Arc<str>
+    where
+        T: fmt::Display,
+    {
+        self.inner.display_text()
+    }
+
+    /// The number of writes to this signal so far, without tracking the read.
+    pub fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    /// Whether the signal has been written since it was at `version`.
+    pub fn changed_since(&self, version: u64) -> bool {
+        self.inner.changed_since(version)
+    }
+
+    /// Subscribe `callback` until it returns `false`.
+    #[track_caller]
+    pub(crate) fn subscribe_while(&self, callback: impl Fn() -> bool + 'static) {
+        self.inner.subscribe_while(callback);
+    }
+
+    /// Where the signal was last written with `set` or `update`, if recorded.
+    pub fn last_writer(&self) -> Option<&'static std::panic::Location<'static>> {
+        self.inner.last_writer()
+    }
+
+    /// The origin the latest write was tagged with, if any.
+    pub fn last_origin(&self) -> Option<ChangeOrigin> {
+        self.inner.last_origin()
+    }
+}
+
+impl<T: 'static> From<Signal<T>> for ReadOnlySignal<T> {
+    fn from(signal: Signal<T>) -> Self {
+        signal.read_only()
+    }
 }
 
 impl<T: 'static + fmt::Debug + Clone> fmt::Debug for ReadOnlySignal<T> {
@@ -329,11 +942,10 @@ impl<T: 'static + fmt::Debug + Clone> fmt::Debug for ReadOnlySignal<T> {
     }
 }
 
-
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::RefCell;
 
     #[test]
     fn test_signal_creation() {
@@ -393,6 +1005,28 @@ mod tests {
         assert_eq!(*count.lock(), 3);
     }
 
+    #[test]
+    fn test_signal_subscribe_once_and_when() {
+        let signal = Signal::new(0);
+        let once_runs = Rc::new(Cell::new(0));
+        let even_runs = Rc::new(Cell::new(0));
+
+        signal.subscribe_once({
+            let once_runs = once_runs.clone();
+            move || once_runs.set(once_runs.get() + 1)
+        });
+        signal.subscribe_when(|value| value % 2 == 0, {
+            let even_runs = even_runs.clone();
+            move || even_runs.set(even_runs.get() + 1)
+        });
+
+        for value in 1..=4 {
+            signal.set(value);
+        }
+        assert_eq!(once_runs.get(), 1);
+        assert_eq!(even_runs.get(), 2);
+    }
+
     #[test]
     fn test_read_only_signal() {
         let signal = Signal::new(42);
@@ -416,6 +1050,19 @@ mod tests {
         assert!(!signal.get());
     }
 
+    #[test]
+    fn test_signal_update_cow_copies_only_shared_values() {
+        let document = Signal::new(Arc::new(vec![1, 2]));
+        let address = document.with(Arc::as_ptr);
+        document.update_cow(|lines| lines.push(3));
+        assert_eq!(document.with(Arc::as_ptr), address);
+
+        let snapshot = document.get();
+        document.update_cow(|lines| lines.push(4));
+        assert_eq!(*snapshot, [1, 2, 3]);
+        assert_eq!(*document.get(), [1, 2, 3, 4]);
+    }
+
     #[test]
     fn test_signal_set_if_changed() {
         let signal = Signal::new(5);
@@ -425,6 +1072,74 @@ mod tests {
         assert_eq!(signal.get(), 6);
     }
 
+    #[test]
+    fn test_signal_version_counts_writes() {
+        let signal = Signal::new(1);
+        let version = signal.version();
+        assert!(!signal.changed_since(version));
+        signal.set(2);
+        signal.update(|_| {});
+        assert_eq!(signal.version(), version + 2);
+        assert!(signal.read_only().changed_since(version));
+        assert!(!signal.read_only().changed_since(version + 2));
+    }
+
+    #[test]
+    #[cfg(any(debug_assertions, feature = "provenance"))]
+    fn test_last_writer_is_the_latest_call_site() {
+        let signal = Signal::new(0);
+        assert_eq!(signal.last_writer(), None);
+
+        signal.set(1);
+        let set_line = line!() - 1;
+        let writer = signal.last_writer().unwrap();
+        assert_eq!((writer.file(), writer.line()), (file!(), set_line));
+        signal.update(|value| *value += 1);
+        let update_line = line!() - 1;
+        assert_eq!(
+            signal.read_only().last_writer().unwrap().line(),
+            update_line
+        );
+
+        signal.dispose();
+        assert_eq!(signal.last_writer(), None);
+    }
+
+    #[test]
+    fn test_reads_nest_inside_other_reads() {
+        let first = Signal::new(String::from("a"));
+        let second = Signal::new(String::from("b"));
+        let joined = first.with(|first| second.with_untracked(|second| format!("{first}{second}")));
+        assert_eq!(joined, "ab");
+        assert_eq!(first.with(|first| second.get() + first), "ba");
+    }
+
+    #[test]
+    fn test_origin_is_passed_to_subscribers() {
+        let signal = Signal::new(0);
+        let origins = Rc::new(RefCell::new(Vec::new()));
+        signal.read_only().subscribe_with_origin({
+            let origins = origins.clone();
+            move |origin| origins.borrow_mut().push(origin)
+        });
+
+        signal.set_with_origin(1, ChangeOrigin::Undo);
+        assert_eq!(signal.last_origin(), Some(ChangeOrigin::Undo));
+        signal.update(|value| *value += 1);
+        signal.set_with_origin(3, ChangeOrigin::Other(7));
+        signal.set(4);
+        assert_eq!(
+            *origins.borrow(),
+            [
+                Some(ChangeOrigin::Undo),
+                None,
+                Some(ChangeOrigin::Other(7)),
+                None
+            ]
+        );
+        assert_eq!(signal.last_origin(), None);
+    }
+
     #[test]
     fn test_signal_eq() {
         let s1 = Signal::new(10);
@@ -434,4 +1149,29 @@ mod tests {
         assert_eq!(s1, s2);
         assert_ne!(s1, s3);
     }
+
+    #[test]
+    fn test_use_on_other_thread_explains_constraint() {
+        let signal = Signal::new(1);
+        let panic = std::thread::spawn(move || signal.get()).join().unwrap_err();
+        let message = panic.downcast_ref::<&str>().unwrap();
+        assert!(message.contains("different thread"));
+        assert!(message.contains("SyncSignal"));
+    }
+
+    #[test]
+    fn test_disposed_signal_stays_stale_after_slot_reuse() {
+        let runtime = SignalRuntime::new();
+        runtime.set_stale_handle_policy(StaleHandlePolicy::Error);
+        let old = Signal::new_in(1, &runtime);
+        old.dispose();
+        let new = Signal::new_in(2, &runtime);
+
+        assert_eq!(old.try_get(), None);
+        assert_eq!(new.try_get(), Some(2));
+        assert_ne!(old, new);
+
+        old.set(3);
+        assert_eq!(new.get(), 2);
+    }
 }
@@ -0,0 +1,231 @@
+//! Typed channels between views, delivered through signals.
+
+use crate::signal::{ReadOnlySignal, Signal};
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Create a channel whose receiver is a signal holding the latest value sent, or
+/// `None` before the first.
+///
+/// A parent hands the sender to a child view, or the other way around, and the
+/// receiving view reads the signal like any other, so small interactions don't need
+/// a GPUI event type each. Every send notifies the receiver, even of a value equal
+/// to the last. Use [`signal_inbox`] when no value may be missed between reads.
+///
+/// # Examples
+///
+/// ```rust
+/// use gpui_signals::signal_channel;
+///
+/// let (selected, selection) = signal_channel::<usize>();
+/// assert_eq!(selection.get(), None);
+///
+/// // In the child's click handler:
+/// selected.send(3);
+/// // In the parent's render:
+/// assert_eq!(selection.get(), Some(3));
+/// ```
+pub fn signal_channel<T: 'static>() -> (SignalSender<T>, ReadOnlySignal<Option<T>>) {
+    let latest = Signal::new(None);
+    (SignalSender { latest }, latest.read_only())
+}
+
+/// The sending half of a [`signal_channel`].
+pub struct SignalSender<T> {
+    latest: Signal<Option<T>>,
+}
+
+impl<T> Copy for SignalSender<T> {}
+
+impl<T> Clone for SignalSender<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for SignalSender<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.latest == other.latest
+    }
+}
+
+impl<T> Eq for SignalSender<T> {}
+
+impl<T: 'static> SignalSender<T> {
+    /// Send `value`, replacing the last one.
+    #[track_caller]
+    pub fn send(&self, value: T) {
+        self.latest.set(Some(value));
+    }
+
+    /// Free the channel. Reading the receiving signal afterwards panics.
+    pub fn close(self) {
+        self.latest.dispose();
+    }
+}
+
+impl<T: 'static> fmt::Debug for SignalSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignalSender")
+            .field("signal", &self.latest.id())
+            .finish()
+    }
+}
+
+/// Create a channel whose receiver queues every value sent until it is taken.
+///
+/// # Examples
+///
+/// ```rust
+/// use gpui_signals::signal_inbox;
+///
+/// let (notify, toasts) = signal_inbox::<String>();
+/// notify.send("Saved".to_string());
+/// notify.send("Synced".to_string());
+///
+/// assert_eq!(toasts.len(), 2);
+/// assert_eq!(toasts.pop().as_deref(), Some("Saved"));
+/// assert_eq!(toasts.drain(), ["Synced"]);
+/// ```
+pub fn signal_inbox<T: 'static>() -> (InboxSender<T>, Inbox<T>) {
+    let messages = Signal::new(VecDeque::new());
+    (InboxSender { messages }, Inbox { messages })
+}
+
+/// The sending half of a [`signal_inbox`].
+pub struct InboxSender<T> {
+    messages: Signal<VecDeque<T>>,
+}
+
+impl<T> Copy for InboxSender<T> {}
+
+impl<T> Clone for InboxSender<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for InboxSender<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.messages == other.messages
+    }
+}
+
+impl<T> Eq for InboxSender<T> {}
+
+impl<T: 'static> InboxSender<T> {
+    /// Queue `value` at the back of the inbox.
+    #[track_caller]
+    pub fn send(&self, value: T) {
+        self.messages.update(|messages| messages.push_back(value));
+    }
+}
+
+impl<T: 'static> fmt::Debug for InboxSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InboxSender")
+            .field("signal", &self.messages.id())
+            .finish()
+    }
+}
+
+/// The receiving half of a [`signal_inbox`]: the values sent and not yet taken,
+/// oldest first.
+pub struct Inbox<T> {
+    messages: Signal<VecDeque<T>>,
+}
+
+impl<T> Copy for Inbox<T> {}
+
+impl<T> Clone for Inbox<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for Inbox<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.messages == other.messages
+    }
+}
+
+impl<T> Eq for Inbox<T> {}
+
+impl<T: 'static> Inbox<T> {
+    /// The queued values.
+    pub fn messages(&self) -> ReadOnlySignal<VecDeque<T>> {
+        self.messages.read_only()
+    }
+
+    /// The number of queued values, tracking the read.
+    pub fn len(&self) -> usize {
+        self.messages.with(VecDeque::len)
+    }
+
+    /// Whether nothing is queued, tracking the read.
+    pub fn is_empty(&self) -> bool {
+        self.messages.with(VecDeque::is_empty)
+    }
+
+    /// Take the oldest value.
+    #[track_caller]
+    pub fn pop(&self) -> Option<T> {
+        if self.messages.with_untracked(VecDeque::is_empty) {
+            return None;
+        }
+        self.messages.update_with(VecDeque::pop_front).flatten()
+    }
+
+    /// Take every queued value.
+    #[track_caller]
+    pub fn drain(&self) -> Vec<T> {
+        if self.messages.with_untracked(VecDeque::is_empty) {
+            return Vec::new();
+        }
+        self.messages
+            .update_with(|messages| messages.drain(..).collect())
+            .unwrap_or_default()
+    }
+
+    /// Free the channel. Sending afterwards follows the runtime's
+    /// [`StaleHandlePolicy`](crate::StaleHandlePolicy).
+    pub fn close(self) {
+        self.messages.dispose();
+    }
+}
+
+impl<T: 'static> fmt::Debug for Inbox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Inbox")
+            .field("signal", &self.messages.id())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Effect;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_inbox_keeps_every_message_until_taken() {
+        let (sender, inbox) = signal_inbox();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _effect = Effect::new({
+            let seen = seen.clone();
+            move || {
+                if !inbox.is_empty() {
+                    seen.borrow_mut().extend(inbox.drain());
+                }
+            }
+        });
+
+        sender.send(1);
+        sender.send(2);
+        assert_eq!(*seen.borrow(), [1, 2]);
+        assert!(inbox.is_empty());
+        assert_eq!(inbox.pop(), None);
+    }
+}
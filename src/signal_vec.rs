@@ -0,0 +1,224 @@
+//! Collection signals.
+
+use crate::runtime::SignalRuntime;
+use crate::signal::{ReadOnlySignal, Signal};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// A reactive list of values.
+///
+/// `SignalVec<T>` is a Copy-able handle around a `Signal<Vec<T>>` with list-shaped
+/// mutation helpers. Every mutation notifies subscribers once.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::Context;
+/// use gpui_signals::prelude::*;
+///
+/// struct Tags {
+///     tags: SignalVec<String>,
+/// }
+///
+/// impl Tags {
+///     fn new(cx: &mut Context<Self>) -> Self {
+///         let tags = cx.create_signal_vec(vec!["rust".to_string()]);
+///         tags.push("gpui".to_string());
+///         Self { tags }
+///     }
+/// }
+/// ```
+pub struct SignalVec<T> {
+    signal: Signal<Vec<T>>,
+}
+
+impl<T> Copy for SignalVec<T> {}
+
+impl<T> Clone for SignalVec<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> PartialEq for SignalVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.signal == other.signal
+    }
+}
+
+impl<T> Eq for SignalVec<T> {}
+
+impl<T> Hash for SignalVec<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.signal.hash(state);
+    }
+}
+
+impl<T: 'static> From<SignalVec<T>> for ReadOnlySignal<Vec<T>> {
+    fn from(items: SignalVec<T>) -> Self {
+        items.signal.read_only()
+    }
+}
+
+impl<T: 'static> SignalVec<T> {
+    /// Create a new list signal with the given initial values.
+    pub fn new(values: Vec<T>) -> Self {
+        Self::from_signal(Signal::new(values))
+    }
+
+    /// Create a new list signal in `runtime`.
+    pub fn new_in(values: Vec<T>, runtime: &SignalRuntime) -> Self {
+        Self::from_signal(Signal::new_in(values, runtime))
+    }
+
+    pub(crate) fn from_signal(signal: Signal<Vec<T>>) -> Self {
+        Self { signal }
+    }
+
+    /// Get the underlying signal.
+    pub fn signal(&self) -> Signal<Vec<T>> {
+        self.signal
+    }
+
+    /// Get a clone of the list.
+    ///
+    /// This will track the read if called within a reactive context.
+    pub fn get(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.signal.get()
+    }
+
+    /// Get a clone of the list without tracking the read.
+    pub fn get_untracked(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.signal.get_untracked()
+    }
+
+    /// Read the list with a closure.
+    ///
+    /// This will track the read if called within a reactive context.
+    pub fn with<R>(&self, f: impl FnOnce(&[T]) -> R) -> R {
+        self.signal.with(|values| f(values))
+    }
+
+    /// Read the list with a closure without tracking.
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&[T]) -> R) -> R {
+        self.signal.with_untracked(|values| f(values))
+    }
+
+    /// The number of items in the list.
+    pub fn len(&self) -> usize {
+        self.with(|values| values.len())
+    }
+
+    /// Whether the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.with(|values| values.is_empty())
+    }
+
+    /// Replace the whole list.
+    #[track_caller]
+    pub fn set(&self, values: Vec<T>) {
+        self.signal.set(values);
+    }
+
+    /// Mutate the list with a closure.
+    #[track_caller]
+    pub fn update(&self, f: impl FnOnce(&mut Vec<T>)) {
+        self.signal.update(f);
+    }
+
+    /// Append a value to the end of the list.
+    #[track_caller]
+    pub fn push(&self, value: T) {
+        self.signal.update(|values| values.push(value));
+    }
+
+    /// Insert a value at `index`, clamped to the end of the list.
+    #[track_caller]
+    pub fn insert(&self, index: usize, value: T) {
+        self.signal.update(|values| {
+            let index = index.min(values.len());
+            values.insert(index, value);
+        });
+    }
+
+    /// Remove and return the value at `index`, if it exists.
+    ///
+    /// Subscribers are only notified when a value was removed.
+    #[track_caller]
+    pub fn remove(&self, index: usize) -> Option<T> {
+        if index >= self.with_untracked(|values| values.len()) {
+            return None;
+        }
+        self.signal.update_with(|values| values.remove(index))
+    }
+
+    /// Keep only the values for which `f` returns true.
+    #[track_caller]
+    pub fn retain(&self, f: impl FnMut(&T) -> bool) {
+        self.signal.update(|values| values.retain(f));
+    }
+
+    /// Remove every value from the list.
+    #[track_caller]
+    pub fn clear(&self) {
+        self.signal.update(|values| values.clear());
+    }
+
+    /// Subscribe to changes on this list.
+    pub fn subscribe(&self, callback: impl Fn() + 'static) {
+        self.signal.subscribe(callback);
+    }
+}
+
+impl<T: 'static + fmt::Debug + Clone> fmt::Debug for SignalVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignalVec")
+            .field("values", &self.get_untracked())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signal_vec_mutations() {
+        let list = SignalVec::new(vec![1, 2, 3]);
+        list.push(4);
+        list.insert(0, 0);
+        assert_eq!(list.get(), vec![0, 1, 2, 3, 4]);
+
+        assert_eq!(list.remove(1), Some(1));
+        assert_eq!(list.remove(10), None);
+        list.retain(|n| n % 2 == 0);
+        assert_eq!(list.get(), vec![0, 2, 4]);
+
+        list.clear();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_signal_vec_notifies_once_per_mutation() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let list = SignalVec::new(Vec::<i32>::new());
+        let count = Rc::new(Cell::new(0));
+        list.subscribe({
+            let count = count.clone();
+            move || count.set(count.get() + 1)
+        });
+
+        list.push(1);
+        list.remove(5);
+        list.remove(0);
+        assert_eq!(count.get(), 2);
+    }
+}
@@ -4,61 +4,347 @@
 //! to signal values. This prevents use-after-free bugs when signals are dropped
 //! and their slots are reused.
 
+use crate::computed::EffectPriority;
+use crate::restricted::{AccessError, Capability, Permissions};
+use crate::runtime::{
+    NotificationWave, StaleHandle, StaleHandlePolicy, UpdateDepthExceeded, WaveEntry, WaveEvent,
+};
+use crate::scheduler::{ImmediateScheduler, Scheduler};
+use crate::signal::ChangeOrigin;
 use slotmap::{new_key_type, SlotMap};
 use std::any::Any;
 use std::cell::RefCell;
-use std::collections::{BTreeMap, HashSet};
-use std::rc::Rc;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use std::panic::Location;
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+use std::thread::{self, ThreadId};
 
 new_key_type! {
     /// Unique identifier for a signal in the storage.
     pub struct SignalId;
+
+    /// Identifier of the runtime whose storage a signal lives in.
+    pub(crate) struct RuntimeId;
+
+    /// Identifier of a permission grant made by `Signal::restricted`.
+    pub(crate) struct CapabilityId;
 }
 
 /// A type-erased signal value with generational checking.
 pub(crate) struct SignalValue {
-    /// The actual value, boxed and type-erased.
-    pub value: Box<dyn Any>,
-    /// Generation counter to detect stale handles.
+    /// The actual value, type-erased.
+    pub value: StoredValue,
+    /// Generation the value was inserted at, to detect stale handles.
     pub generation: u32,
+    /// Incremented on every write, so derived data can tell whether it is stale.
+    pub version: u64,
+    /// The value's `Display` output and the version it was formatted at.
+    pub display: Option<(u64, Arc<str>)>,
+    /// The name of the value's type, for introspection.
+    #[cfg(feature = "gpui")]
+    pub type_name: &'static str,
+    /// Where the value was last written with `set` or `update`.
+    #[cfg(any(debug_assertions, feature = "provenance"))]
+    pub last_writer: Option<&'static Location<'static>>,
+}
+
+/// A type-erased value, held inline when it is a primitive.
+///
+/// Counters and flags are the most common signals and the most often written, so
+/// they skip the allocation and pointer chase of a box.
+pub(crate) enum StoredValue {
+    Inline(InlineValue),
+    Boxed(Box<dyn Any>),
+}
+
+impl StoredValue {
+    fn new<T: 'static>(value: T) -> Self {
+        match InlineValue::new(value) {
+            Ok(inline) => Self::Inline(inline),
+            Err(value) => Self::Boxed(Box::new(value)),
+        }
+    }
+
+    fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        match self {
+            Self::Inline(inline) => inline.as_any().downcast_ref(),
+            Self::Boxed(boxed) => boxed.downcast_ref(),
+        }
+    }
+
+    fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        match self {
+            Self::Inline(inline) => inline.as_any_mut().downcast_mut(),
+            Self::Boxed(boxed) => boxed.downcast_mut(),
+        }
+    }
+}
+
+macro_rules! inline_values {
+    ($($variant:ident($ty:ty)),+ $(,)?) => {
+        /// A primitive value stored without boxing.
+        #[derive(Clone, Copy)]
+        pub(crate) enum InlineValue {
+            $($variant($ty),)+
+        }
+
+        impl InlineValue {
+            /// Wrap `value` if it has one of the inline types, or hand it back.
+            fn new<T: 'static>(value: T) -> Result<Self, T> {
+                // Moving the value through an `Option` lets it be taken out as the
+                // concrete type without a box or unsafe code.
+                let mut slot = Some(value);
+                let any: &mut dyn Any = &mut slot;
+                $(
+                    if let Some(value) = any.downcast_mut::<Option<$ty>>() {
+                        return Ok(Self::$variant(value.take().expect("slot is filled")));
+                    }
+                )+
+                Err(slot.expect("slot is filled"))
+            }
+
+            fn as_any(&self) -> &dyn Any {
+                match self {
+                    $(Self::$variant(value) => value,)+
+                }
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn Any {
+                match self {
+                    $(Self::$variant(value) => value,)+
+                }
+            }
+        }
+    };
+}
+
+inline_values! {
+    Bool(bool),
+    Char(char),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    Isize(isize),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    Usize(usize),
+    F32(f32),
+    F64(f64),
 }
 
 /// Subscriber callback for signal changes.
 pub(crate) type Subscriber = Rc<dyn Fn()>;
 
-/// Thread-local storage for all signals.
+/// Callback for uses of stale handles under `StaleHandlePolicy::Error`.
+pub(crate) type StaleHandleHandler = Rc<dyn Fn(&StaleHandle)>;
+
+/// Callback for writes nested past the maximum update depth.
+pub(crate) type UpdateDepthHandler = Rc<dyn Fn(&UpdateDepthExceeded)>;
+
+/// Callback for each finished notification wave.
+pub(crate) type WaveHandler = Rc<dyn Fn(&NotificationWave)>;
+
+/// A shared memo's value type and key.
+#[cfg(feature = "gpui")]
+pub(crate) type SharedMemoKey = (std::any::TypeId, String);
+
+/// A signal as seen by the graph export.
+#[cfg(feature = "gpui")]
+pub(crate) struct GraphEntry {
+    pub id: SignalId,
+    pub type_name: &'static str,
+    pub name: Option<Rc<str>>,
+    pub is_memo: bool,
+    pub is_effect: bool,
+    pub dependencies: Vec<SignalId>,
+}
+
+/// A live signal's id, value type name, subscriber count, and last writer.
+#[cfg(feature = "gpui")]
+pub(crate) type LiveSignalEntry = (
+    SignalId,
+    &'static str,
+    usize,
+    Option<&'static std::panic::Location<'static>>,
+);
+
+/// Storage for all signals of one runtime.
 ///
 /// This is the backing store for all signal values and their subscribers.
 /// It uses interior mutability to allow Copy handles to access and modify values.
 pub(crate) struct SignalStorage {
+    /// The runtime this storage belongs to.
+    runtime: RuntimeId,
+    /// The thread whose registry holds this storage.
+    thread: ThreadId,
     /// Arena of signal values indexed by SignalId.
     values: SlotMap<SignalId, SignalValue>,
+    /// Generation given to the next inserted value.
+    next_generation: u32,
     /// Subscribers for each signal.
     subscribers: BTreeMap<SignalId, Vec<Subscriber>>,
     /// Dependencies tracked for each observer (observer -> set of signals read).
     dependencies: BTreeMap<SignalId, HashSet<SignalId>>,
+    /// The reverse of `dependencies` (signal -> observers that read it). Keyed by
+    /// observer so that tracking the same read again doesn't add another entry.
+    observers: BTreeMap<SignalId, BTreeSet<SignalId>>,
     /// The current observer (if any) for dependency tracking.
     current_observer: Option<SignalId>,
+    /// Callbacks that recompute lazy values, run on read while the value is dirty.
+    refreshers: BTreeMap<SignalId, Subscriber>,
+    /// Lazy values whose dependencies changed since they were last computed.
+    dirty: HashSet<SignalId>,
+    /// Priorities of effects, keyed by the observer their reads are tracked under.
+    priorities: BTreeMap<SignalId, EffectPriority>,
+    /// Delivers the notifications produced by writes.
+    scheduler: Rc<dyn Scheduler>,
+    /// Whether a render or memo computation is running, during which writes are a bug.
+    read_only: bool,
+    /// What to do when a handle to a disposed signal is used.
+    stale_handle_policy: StaleHandlePolicy,
+    /// Receives stale handle accesses under `StaleHandlePolicy::Error`.
+    stale_handle_handler: Option<StaleHandleHandler>,
+    /// Signals whose writes are being delivered, outermost first.
+    delivering: Vec<SignalId>,
+    /// How many deliveries may nest before the innermost is dropped.
+    max_update_depth: usize,
+    /// Receives writes nested past `max_update_depth`.
+    update_depth_handler: Option<UpdateDepthHandler>,
+    /// Receives each notification wave once it finishes; waves are only recorded
+    /// while one is set.
+    wave_handler: Option<WaveHandler>,
+    /// The events of the wave in progress.
+    wave: Vec<WaveEntry>,
+    /// Memos marked dirty by the write being recorded.
+    wave_invalidated: Vec<SignalId>,
+    /// Memo computations and effect runs in progress within the current wave.
+    wave_nesting: usize,
+    /// Origins of the latest writes, for signals last written with
+    /// `Signal::set_with_origin`.
+    origins: BTreeMap<SignalId, ChangeOrigin>,
+    /// Permissions granted to restricted handles.
+    capabilities: SlotMap<CapabilityId, Capability>,
+    /// Names given with `Memo::named` and `Effect::named`, for the graph export.
+    #[cfg(feature = "gpui")]
+    names: BTreeMap<SignalId, Rc<str>>,
+    /// Memos shared by key, with the number of entities using each.
+    #[cfg(feature = "gpui")]
+    shared_memos: std::collections::HashMap<SharedMemoKey, (Box<dyn Any>, usize)>,
 }
 
 impl SignalStorage {
     /// Create a new empty signal storage.
-    pub fn new() -> Self {
+    pub fn new(runtime: RuntimeId) -> Self {
         Self {
+            runtime,
+            thread: current_thread(),
             values: SlotMap::with_key(),
+            next_generation: 0,
             subscribers: BTreeMap::new(),
             dependencies: BTreeMap::new(),
+            observers: BTreeMap::new(),
             current_observer: None,
+            refreshers: BTreeMap::new(),
+            dirty: HashSet::new(),
+            priorities: BTreeMap::new(),
+            scheduler: Rc::new(ImmediateScheduler),
+            read_only: false,
+            stale_handle_policy: StaleHandlePolicy::default(),
+            stale_handle_handler: None,
+            delivering: Vec::new(),
+            max_update_depth: 100,
+            update_depth_handler: None,
+            wave_handler: None,
+            wave: Vec::new(),
+            wave_invalidated: Vec::new(),
+            wave_nesting: 0,
+            origins: BTreeMap::new(),
+            capabilities: SlotMap::with_key(),
+            #[cfg(feature = "gpui")]
+            names: BTreeMap::new(),
+            #[cfg(feature = "gpui")]
+            shared_memos: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Create an empty signal storage with room for `capacity` signals before the
+    /// arena grows.
+    pub fn with_capacity(runtime: RuntimeId, capacity: usize) -> Self {
+        Self {
+            values: SlotMap::with_capacity_and_key(capacity),
+            ..Self::new(runtime)
+        }
+    }
+
+    /// The number of signals the arena holds before it grows.
+    pub fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    /// The number of signals not yet disposed, including those memos and effects
+    /// track their reads under.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Release memory held for signals that no longer need it.
+    ///
+    /// Drops the bookkeeping left behind by removed subscribers and dependencies,
+    /// trims the remaining tables to their contents, and forgets cached display
+    /// text, which is formatted again on its next use. The arena's slots are kept:
+    /// disposed slots are reused by later signals, and their generations are what
+    /// make stale handles fail rather than reach a new signal.
+    pub fn shrink_to_fit(&mut self) {
+        self.subscribers.retain(|_, subscribers| {
+            subscribers.shrink_to_fit();
+            !subscribers.is_empty()
+        });
+        self.dependencies.retain(|_, dependencies| {
+            dependencies.shrink_to_fit();
+            !dependencies.is_empty()
+        });
+        self.observers.retain(|_, observers| !observers.is_empty());
+        self.dirty.shrink_to_fit();
+        for signal_value in self.values.values_mut() {
+            signal_value.display = None;
         }
     }
 
-    /// Insert a new signal value and return its ID.
-    pub fn insert<T: 'static>(&mut self, value: T) -> SignalId {
+    /// The runtime this storage belongs to.
+    pub fn runtime(&self) -> RuntimeId {
+        self.runtime
+    }
+
+    /// The thread this storage belongs to.
+    pub fn thread(&self) -> ThreadId {
+        self.thread
+    }
+
+    /// Insert a new signal value and return its ID and generation.
+    ///
+    /// Every insert takes a new generation, so a value placed in the slot of a
+    /// disposed signal never matches a handle to the old one.
+    pub fn insert<T: 'static>(&mut self, value: T) -> (SignalId, u32) {
+        let generation = self.next_generation;
+        self.next_generation = self.next_generation.wrapping_add(1);
         let signal_value = SignalValue {
-            value: Box::new(value),
-            generation: 0,
+            value: StoredValue::new(value),
+            generation,
+            version: 0,
+            display: None,
+            #[cfg(feature = "gpui")]
+            type_name: std::any::type_name::<T>(),
+            #[cfg(any(debug_assertions, feature = "provenance"))]
+            last_writer: None,
         };
-        self.values.insert(signal_value)
+        #[cfg(feature = "metrics")]
+        crate::telemetry::signal_created();
+        (self.values.insert(signal_value), generation)
     }
 
     /// Get a reference to a signal value.
@@ -73,9 +359,15 @@ impl SignalStorage {
     }
 
     /// Get a mutable reference to a signal value.
+    ///
+    /// This counts as a write and bumps the signal's version.
     pub fn get_mut<T: 'static>(&mut self, id: SignalId, generation: u32) -> Option<&mut T> {
         self.values.get_mut(id).and_then(|signal_value| {
             if signal_value.generation == generation {
+                signal_value.version += 1;
+                if !self.origins.is_empty() {
+                    self.origins.remove(&id);
+                }
                 signal_value.value.downcast_mut()
             } else {
                 None
@@ -83,25 +375,154 @@ impl SignalStorage {
         })
     }
 
-    /// Update a signal value and notify subscribers.
+    /// Update a signal value and return the callbacks to notify.
     pub fn set<T: 'static>(
         &mut self,
         id: SignalId,
         generation: u32,
         value: T,
     ) -> Option<Vec<Subscriber>> {
+        self.replace(id, generation, value)?;
+        Some(self.notifications(id))
+    }
+
+    /// Update a signal value without propagating the change.
+    pub fn replace<T: 'static>(&mut self, id: SignalId, generation: u32, value: T) -> Option<()> {
+        let signal_value = self.values.get_mut(id)?;
+        if signal_value.generation != generation {
+            return None;
+        }
+        // Assigning in place reuses the existing allocation of a boxed value.
+        match signal_value.value.downcast_mut() {
+            Some(slot) => *slot = value,
+            None => signal_value.value = StoredValue::new(value),
+        }
+        signal_value.version += 1;
+        if !self.origins.is_empty() {
+            self.origins.remove(&id);
+        }
+        Some(())
+    }
+
+    /// Record `origin` as the origin of the write just made to `id`.
+    pub fn set_origin(&mut self, id: SignalId, origin: ChangeOrigin) {
+        self.origins.insert(id, origin);
+    }
+
+    /// The origin of the latest write to `id`, if it was given one.
+    pub fn origin(&self, id: SignalId, generation: u32) -> Option<ChangeOrigin> {
+        if !self.contains(id, generation) {
+            return None;
+        }
+        self.origins.get(&id).copied()
+    }
+
+    /// Collect the callbacks to run after a write to `id`.
+    ///
+    /// Every memo that depends on `id`, directly or through other memos, is marked
+    /// dirty before any callback runs, so a callback that reads one sees the new value.
+    /// A memo that is already dirty has already notified its dependents, so the walk
+    /// stops there.
+    ///
+    /// Callbacks are ordered by the priority of the effect they belong to, with other
+    /// subscribers counted as the default priority. The sort is stable, so callbacks
+    /// of equal priority keep the order they were reached in.
+    fn notifications(&mut self, id: SignalId) -> Vec<Subscriber> {
+        let mut callbacks = Vec::new();
+        let mut visited = HashSet::new();
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            if let Some(subscribers) = self.subscribers.get(&id) {
+                let priority = self.priorities.get(&id).copied().unwrap_or_default();
+                callbacks.extend(
+                    subscribers
+                        .iter()
+                        .map(|callback| (priority, callback.clone())),
+                );
+            }
+            if let Some(observers) = self.observers.get(&id) {
+                for &observer in observers {
+                    if self.refreshers.contains_key(&observer) {
+                        if !self.dirty.insert(observer) {
+                            continue;
+                        }
+                        if self.wave_handler.is_some() {
+                            self.wave_invalidated.push(observer);
+                        }
+                    }
+                    pending.push(observer);
+                }
+            }
+        }
+        callbacks.sort_by_key(|(priority, _)| *priority);
+        callbacks
+            .into_iter()
+            .map(|(_, callback)| callback)
+            .collect()
+    }
+
+    /// Whether `id` still holds the value inserted at `generation`.
+    pub fn contains(&self, id: SignalId, generation: u32) -> bool {
+        self.values
+            .get(id)
+            .is_some_and(|signal_value| signal_value.generation == generation)
+    }
+
+    /// Record where a signal is being written.
+    #[cfg(any(debug_assertions, feature = "provenance"))]
+    pub fn set_last_writer(
+        &mut self,
+        id: SignalId,
+        generation: u32,
+        location: &'static Location<'static>,
+    ) {
         if let Some(signal_value) = self.values.get_mut(id) {
             if signal_value.generation == generation {
-                signal_value.value = Box::new(value);
-                let callbacks: Vec<Subscriber> = self
-                    .subscribers
-                    .get(&id)
-                    .map(|subs| subs.iter().cloned().collect())
-                    .unwrap_or_default();
-                return Some(callbacks);
+                signal_value.last_writer = Some(location);
             }
         }
-        None
+    }
+
+    /// Where a signal was last written with `set` or `update`.
+    #[cfg(any(debug_assertions, feature = "provenance"))]
+    pub fn last_writer(&self, id: SignalId, generation: u32) -> Option<&'static Location<'static>> {
+        self.values
+            .get(id)
+            .filter(|signal_value| signal_value.generation == generation)
+            .and_then(|signal_value| signal_value.last_writer)
+    }
+
+    /// The number of writes to a signal so far.
+    pub fn version(&self, id: SignalId, generation: u32) -> Option<u64> {
+        self.values
+            .get(id)
+            .filter(|signal_value| signal_value.generation == generation)
+            .map(|signal_value| signal_value.version)
+    }
+
+    /// Get the value's `Display` output, formatting it only if it changed since the
+    /// last call.
+    pub fn display<T: fmt::Display + 'static>(
+        &mut self,
+        id: SignalId,
+        generation: u32,
+    ) -> Option<Arc<str>> {
+        let signal_value = self.values.get_mut(id)?;
+        if signal_value.generation != generation {
+            return None;
+        }
+        let version = signal_value.version;
+        if let Some((cached_version, text)) = &signal_value.display {
+            if *cached_version == version {
+                return Some(text.clone());
+            }
+        }
+        let text: Arc<str> = signal_value.value.downcast_ref::<T>()?.to_string().into();
+        signal_value.display = Some((version, text.clone()));
+        Some(text)
     }
 
     /// Update a signal value with a closure and notify subscribers.
@@ -111,27 +532,115 @@ impl SignalStorage {
         generation: u32,
         f: impl FnOnce(&mut T) -> R,
     ) -> Option<(R, Vec<Subscriber>)> {
-        if let Some(value) = self.get_mut::<T>(id, generation) {
-            let result = f(value);
-            let callbacks = self
-                .subscribers
-                .get(&id)
-                .map(|subs| subs.iter().cloned().collect())
-                .unwrap_or_default();
-            Some((result, callbacks))
-        } else {
-            None
+        let result = f(self.get_mut::<T>(id, generation)?);
+        Some((result, self.notifications(id)))
+    }
+
+
+
+    /// Remove a signal's value, subscribers, and tracked dependencies.
+    ///
+    /// The removed data is returned so the caller can drop it after releasing the
+    /// storage, since dropping values or callbacks may touch signals again.
+    pub fn dispose(&mut self, id: SignalId) -> Option<(SignalValue, Vec<Subscriber>)> {
+        let value = self.values.remove(id)?;
+        #[cfg(feature = "metrics")]
+        crate::telemetry::signal_disposed();
+        let mut subscribers = self.subscribers.remove(&id).unwrap_or_default();
+        subscribers.extend(self.refreshers.remove(&id));
+        self.clear_dependencies(id);
+        self.observers.remove(&id);
+        self.dirty.remove(&id);
+        self.priorities.remove(&id);
+        self.origins.remove(&id);
+        #[cfg(feature = "gpui")]
+        self.names.remove(&id);
+        if !self.capabilities.is_empty() {
+            self.capabilities
+                .retain(|_, capability| capability.signal() != id);
         }
+        Some((value, subscribers))
     }
 
+    /// Grant `permissions` on `signal` to a new restricted handle.
+    pub fn grant(&mut self, signal: SignalId, permissions: Permissions) -> CapabilityId {
+        self.capabilities
+            .insert(Capability::new(signal, permissions))
+    }
+
+    /// Withdraw a grant, so every handle holding it is refused.
+    pub fn revoke(&mut self, capability: CapabilityId) {
+        self.capabilities.remove(capability);
+    }
+
+    /// Check whether `capability` allows a read or write, counting writes against its
+    /// rate limit.
+    pub fn check_access(
+        &mut self,
+        capability: CapabilityId,
+        write: bool,
+    ) -> Result<(), AccessError> {
+        self.capabilities
+            .get_mut(capability)
+            .ok_or(AccessError::Revoked)?
+            .check(write)
+    }
+
+    /// Make `id` a computed value, produced by `refresh` on its next read and again
+    /// on the first read after each change to its dependencies.
+    pub fn set_refresher(&mut self, id: SignalId, refresh: Subscriber) {
+        self.refreshers.insert(id, refresh);
+        self.dirty.insert(id);
+    }
+
+    /// Mark a computed value dirty without notifying anything, so its next read
+    /// recomputes it.
+    pub fn mark_dirty(&mut self, id: SignalId) {
+        if self.refreshers.contains_key(&id) {
+            self.dirty.insert(id);
+        }
+    }
+
+    /// Mark a computed value dirty and return the callbacks to notify, as if one of
+    /// its dependencies had been written.
+    pub fn invalidate(&mut self, id: SignalId) -> Vec<Subscriber> {
+        if !self.refreshers.contains_key(&id) || !self.dirty.insert(id) {
+            return Vec::new();
+        }
+        self.notifications(id)
+    }
+
+    /// Whether `id` is a computed value that must be recomputed before it is read.
+    pub fn is_dirty(&self, id: SignalId) -> bool {
+        !self.dirty.is_empty() && self.dirty.contains(&id)
+    }
 
+    /// If `id` is a dirty computed value, clear the flag and return its refresher.
+    pub fn take_dirty_refresh(&mut self, id: SignalId) -> Option<Subscriber> {
+        if self.dirty.is_empty() || !self.dirty.remove(&id) {
+            return None;
+        }
+        self.refreshers.get(&id).cloned()
+    }
 
     /// Subscribe to changes on a signal.
-    pub fn subscribe(&mut self, id: SignalId, callback: impl Fn() + 'static) {
+    pub fn subscribe(&mut self, id: SignalId, callback: impl Fn() + 'static) -> Subscriber {
+        let subscriber: Subscriber = Rc::new(callback);
         self.subscribers
             .entry(id)
             .or_default()
-            .push(Rc::new(callback));
+            .push(subscriber.clone());
+        subscriber
+    }
+
+    /// Remove a subscriber added with `subscribe`, returning it so it can be dropped
+    /// outside the borrow.
+    pub fn unsubscribe(&mut self, id: SignalId, subscriber: &Subscriber) -> Option<Subscriber> {
+        let subscribers = self.subscribers.get_mut(&id)?;
+        let index = subscribers
+            .iter()
+            .position(|existing| Rc::ptr_eq(existing, subscriber))?;
+        Some(subscribers.remove(index))
     }
 
     /// Track a read for the current observer.
@@ -139,45 +648,386 @@ impl SignalStorage {
         if let Some(observer_id) = self.current_observer {
             let deps = self.dependencies.entry(observer_id).or_default();
             if deps.insert(id) {
-                // Only subscribe once per observer/dependency pair.
-                let observer_ptr = observer_id;
-                self.subscribe(id, move || notify_subscribers(observer_ptr));
+                self.observers.entry(id).or_default().insert(observer_id);
             }
         }
     }
 
+    /// Every signal not yet disposed, with its value's type name, subscriber count, and
+    /// last writer if recorded.
+    #[cfg(feature = "gpui")]
+    pub fn live_signals(&self) -> Vec<LiveSignalEntry> {
+        self.values
+            .iter()
+            .map(|(id, value)| {
+                let subscribers = self.subscribers.get(&id).map_or(0, Vec::len);
+                #[cfg(any(debug_assertions, feature = "provenance"))]
+                let last_writer = value.last_writer;
+                #[cfg(not(any(debug_assertions, feature = "provenance")))]
+                let last_writer = None;
+                (id, value.type_name, subscribers, last_writer)
+            })
+            .collect()
+    }
+
+    /// Give a memo or effect a name to show in the graph export.
+    #[cfg(feature = "gpui")]
+    pub fn set_name(&mut self, id: SignalId, name: Rc<str>) {
+        self.names.insert(id, name);
+    }
+
+    /// Every signal with what it is and the signals it read on its last run.
+    #[cfg(feature = "gpui")]
+    pub fn graph(&self) -> Vec<GraphEntry> {
+        self.values
+            .iter()
+            .map(|(id, value)| {
+                let mut dependencies: Vec<SignalId> = self
+                    .dependencies
+                    .get(&id)
+                    .map(|dependencies| dependencies.iter().copied().collect())
+                    .unwrap_or_default();
+                dependencies.sort();
+                GraphEntry {
+                    id,
+                    type_name: value.type_name,
+                    name: self.names.get(&id).cloned(),
+                    is_memo: self.refreshers.contains_key(&id),
+                    is_effect: self.priorities.contains_key(&id),
+                    dependencies,
+                }
+            })
+            .collect()
+    }
+
+    /// The memo shared under `key`, counting one more user of it.
+    #[cfg(feature = "gpui")]
+    pub fn acquire_shared_memo(&mut self, key: &SharedMemoKey) -> Option<&dyn Any> {
+        let (memo, users) = self.shared_memos.get_mut(key)?;
+        *users += 1;
+        Some(&**memo)
+    }
+
+    /// Share `memo` under `key`, with one user.
+    #[cfg(feature = "gpui")]
+    pub fn insert_shared_memo(&mut self, key: SharedMemoKey, memo: Box<dyn Any>) {
+        self.shared_memos.insert(key, (memo, 1));
+    }
+
+    /// Count one less user of the memo shared under `key`, returning the memo once it
+    /// has none so the caller can dispose it.
+    #[cfg(feature = "gpui")]
+    pub fn release_shared_memo(&mut self, key: &SharedMemoKey) -> Option<Box<dyn Any>> {
+        let (_, users) = self.shared_memos.get_mut(key)?;
+        *users -= 1;
+        if *users > 0 {
+            return None;
+        }
+        self.shared_memos.remove(key).map(|(memo, _)| memo)
+    }
+
+    /// The observer that reads are currently tracked under.
+    #[cfg(feature = "gpui")]
+    pub fn observer(&self) -> Option<SignalId> {
+        self.current_observer
+    }
+
+    /// Set the priority of the effect whose reads are tracked under `observer`.
+    pub fn set_priority(&mut self, observer: SignalId, priority: EffectPriority) {
+        self.priorities.insert(observer, priority);
+    }
+
+    /// The scheduler that delivers notifications.
+    pub fn scheduler(&self) -> Rc<dyn Scheduler> {
+        self.scheduler.clone()
+    }
+
+    /// Replace the scheduler, returning the previous one so it can be dropped
+    /// outside the borrow.
+    pub fn replace_scheduler(&mut self, scheduler: Rc<dyn Scheduler>) -> Rc<dyn Scheduler> {
+        std::mem::replace(&mut self.scheduler, scheduler)
+    }
+
+    /// What to do when a handle to a disposed signal is used.
+    pub fn stale_handle_policy(&self) -> StaleHandlePolicy {
+        self.stale_handle_policy
+    }
+
+    /// Choose what happens when a handle to a disposed signal is used.
+    pub fn set_stale_handle_policy(&mut self, policy: StaleHandlePolicy) {
+        self.stale_handle_policy = policy;
+    }
+
+    /// The handler for stale handle accesses under `StaleHandlePolicy::Error`.
+    pub fn stale_handle_handler(&self) -> Option<StaleHandleHandler> {
+        self.stale_handle_handler.clone()
+    }
+
+    /// Replace the stale handle handler, returning the previous one so it can be
+    /// dropped outside the borrow.
+    pub fn replace_stale_handle_handler(
+        &mut self,
+        handler: StaleHandleHandler,
+    ) -> Option<StaleHandleHandler> {
+        self.stale_handle_handler.replace(handler)
+    }
+
+    /// Limit how deeply deliveries of writes may nest.
+    pub fn set_max_update_depth(&mut self, depth: usize) {
+        self.max_update_depth = depth;
+    }
+
+    /// The handler for writes nested past the maximum update depth.
+    pub fn update_depth_handler(&self) -> Option<UpdateDepthHandler> {
+        self.update_depth_handler.clone()
+    }
+
+    /// Replace the update depth handler, returning the previous one so it can be
+    /// dropped outside the borrow.
+    pub fn replace_update_depth_handler(
+        &mut self,
+        handler: UpdateDepthHandler,
+    ) -> Option<UpdateDepthHandler> {
+        self.update_depth_handler.replace(handler)
+    }
+
+    /// Record that the notifications of a write to `id` are being delivered, or
+    /// return the chain of writes if that would nest past the maximum depth.
+    pub fn enter_delivery(&mut self, id: SignalId) -> Result<(), UpdateDepthExceeded> {
+        if self.delivering.len() >= self.max_update_depth {
+            let mut chain = self.delivering.clone();
+            chain.push(id);
+            return Err(UpdateDepthExceeded { chain });
+        }
+        self.delivering.push(id);
+        Ok(())
+    }
+
+    /// Record that the innermost delivery finished, returning the wave and its
+    /// handler once the outermost one has, so the handler runs outside the borrow.
+    pub fn exit_delivery(&mut self) -> Option<(WaveHandler, NotificationWave)> {
+        self.delivering.pop();
+        self.finish_wave()
+    }
+
+    /// Replace the notification wave handler, returning the previous one so it can
+    /// be dropped outside the borrow.
+    pub fn replace_wave_handler(&mut self, handler: Option<WaveHandler>) -> Option<WaveHandler> {
+        self.wave.clear();
+        self.wave_invalidated.clear();
+        std::mem::replace(&mut self.wave_handler, handler)
+    }
+
+    /// Record a write to `id` and the memos it marked dirty, starting a wave if
+    /// none is in progress.
+    pub fn record_write(&mut self, id: SignalId, location: Option<&'static Location<'static>>) {
+        if self.wave_handler.is_none() {
+            return;
+        }
+        let depth = self.delivering.len() + self.wave_nesting;
+        self.wave.push(WaveEntry {
+            depth,
+            event: WaveEvent::Write {
+                signal: id,
+                location,
+            },
+        });
+        let invalidated = self.wave_invalidated.drain(..).map(|memo| WaveEntry {
+            depth: depth + 1,
+            event: WaveEvent::MemoInvalidated(memo),
+        });
+        self.wave.extend(invalidated);
+    }
+
+    /// Record work done while delivering a write, returning whether it was
+    /// recorded. Work outside a wave, such as a render reading a dirty memo, is not.
+    ///
+    /// With `nests`, what happens until [`end_wave_event`](Self::end_wave_event)
+    /// is recorded as caused by this event.
+    pub fn record_wave_event(&mut self, event: WaveEvent, nests: bool) -> bool {
+        if self.wave_handler.is_none() || self.delivering.is_empty() {
+            return false;
+        }
+        self.wave.push(WaveEntry {
+            depth: self.delivering.len() + self.wave_nesting,
+            event,
+        });
+        if nests {
+            self.wave_nesting += 1;
+        }
+        true
+    }
+
+    /// End a nesting event started with [`record_wave_event`](Self::record_wave_event).
+    pub fn end_wave_event(&mut self) {
+        self.wave_nesting = self.wave_nesting.saturating_sub(1);
+    }
+
+    /// Take the recorded wave and its handler if no delivery is in progress.
+    pub fn finish_wave(&mut self) -> Option<(WaveHandler, NotificationWave)> {
+        if !self.delivering.is_empty() || self.wave.is_empty() {
+            return None;
+        }
+        let handler = self.wave_handler.clone()?;
+        let entries = std::mem::take(&mut self.wave);
+        Some((handler, NotificationWave { entries }))
+    }
+
+    /// Set whether writes are currently forbidden, returning the previous setting.
+    pub fn set_read_only(&mut self, read_only: bool) -> bool {
+        std::mem::replace(&mut self.read_only, read_only)
+    }
+
+    /// Whether a render or memo computation is running.
+    #[cfg(any(debug_assertions, feature = "gpui"))]
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Whether reads are being tracked as dependencies of an observer.
+    pub fn is_tracking(&self) -> bool {
+        self.current_observer.is_some()
+    }
+
     /// Set the current observer for dependency tracking.
     pub fn set_observer(&mut self, observer: Option<SignalId>) -> Option<SignalId> {
-        // Don't clear dependencies when recomputing. This prevents duplicate subscriptions:
-        // - If a dependency was already tracked, deps.insert() returns false, so no new subscription
-        // - If it's a new dependency, deps.insert() returns true, so we subscribe
-        // This way, each dependency only gets one subscription per observer.
         std::mem::replace(&mut self.current_observer, observer)
     }
+
+    /// Forget the signals `observer` read, so a run that re-tracks them drops any it
+    /// no longer reads.
+    pub fn clear_dependencies(&mut self, observer: SignalId) {
+        for dependency in self.dependencies.remove(&observer).into_iter().flatten() {
+            if let Some(observers) = self.observers.get_mut(&dependency) {
+                observers.remove(&observer);
+            }
+        }
+    }
+}
+
+/// A runtime's signal storage, registered so signal handles can find it by id.
+pub(crate) struct RuntimeStorage {
+    id: RuntimeId,
+    storage: RefCell<SignalStorage>,
+}
+
+impl RuntimeStorage {
+    /// Create and register an empty storage with room for `capacity` signals.
+    pub fn new(capacity: usize) -> Rc<Self> {
+        RUNTIMES.with(|runtimes| {
+            let mut created = None;
+            runtimes.borrow_mut().insert_with_key(|id| {
+                let runtime = Rc::new(Self {
+                    id,
+                    storage: RefCell::new(SignalStorage::with_capacity(id, capacity)),
+                });
+                let registered = Rc::downgrade(&runtime);
+                created = Some(runtime);
+                registered
+            });
+            created.expect("insert_with_key calls its closure")
+        })
+    }
+
+    /// The id signals use to find this storage.
+    pub fn id(&self) -> RuntimeId {
+        self.id
+    }
+
+    /// Access the storage.
+    pub fn with<R>(&self, f: impl FnOnce(&mut SignalStorage) -> R) -> R {
+        f(&mut self.storage.borrow_mut())
+    }
+
+    /// Access the storage for a read that changes nothing.
+    ///
+    /// Such reads share the borrow, so one can run inside another, such as reading a
+    /// signal within another one's `with_untracked`.
+    pub fn read<R>(&self, f: impl FnOnce(&SignalStorage) -> R) -> R {
+        f(&self.storage.borrow())
+    }
+}
+
+impl Drop for RuntimeStorage {
+    fn drop(&mut self) {
+        // The registry is already gone if the thread is exiting.
+        let _ = RUNTIMES.try_with(|runtimes| {
+            if let Ok(mut runtimes) = runtimes.try_borrow_mut() {
+                runtimes.remove(self.id);
+            }
+        });
+    }
 }
 
 thread_local! {
-    static STORAGE: RefCell<SignalStorage> = RefCell::new(SignalStorage::new());
+    static RUNTIMES: RefCell<SlotMap<RuntimeId, Weak<RuntimeStorage>>> =
+        RefCell::new(SlotMap::with_key());
+    static DEFAULT_RUNTIME: Rc<RuntimeStorage> = RuntimeStorage::new(0);
+    static CURRENT_THREAD: ThreadId = thread::current().id();
+}
+
+fn current_thread() -> ThreadId {
+    CURRENT_THREAD.with(|thread| *thread)
+}
+
+/// Panic with an explanation if a handle owned by `owner` is used on another thread.
+///
+/// Without this check the lookup would fail against the other thread's empty
+/// registry with a message that doesn't point at the cause.
+pub(crate) fn check_thread(owner: ThreadId) {
+    if current_thread() != owner {
+        panic!(
+            "Signal used on a different thread than the one that created it. Signals live \
+             in thread-local storage, so they can only be read or written on their owning \
+             thread (for a GPUI app, the foreground thread). To write from a background \
+             thread use `SyncSignal`; to observe changes elsewhere use `to_stream` or \
+             `to_watch`."
+        );
+    }
 }
 
-/// Access the thread-local signal storage.
+/// The storage of this thread's default runtime.
+pub(crate) fn default_runtime() -> Rc<RuntimeStorage> {
+    DEFAULT_RUNTIME.with(Rc::clone)
+}
+
+/// Access the storage of this thread's default runtime.
+#[cfg(any(feature = "gpui", test))]
 pub(crate) fn with_signal_storage<R>(f: impl FnOnce(&mut SignalStorage) -> R) -> R {
-    STORAGE.with(|storage| f(&mut storage.borrow_mut()))
+    DEFAULT_RUNTIME.with(|runtime| runtime.with(f))
 }
 
-/// Notify all subscribers of a signal by temporarily borrowing storage.
-pub(crate) fn notify_subscribers(id: SignalId) {
-    let callbacks: Vec<Subscriber> = with_signal_storage(|storage| {
-        storage
-            .subscribers
-            .get(&id)
-            .map(|subs| subs.iter().cloned().collect())
-            .unwrap_or_default()
-    });
+/// Access the storage of the runtime `id`.
+///
+/// Panics if the runtime has been dropped.
+pub(crate) fn with_runtime_storage<R>(id: RuntimeId, f: impl FnOnce(&mut SignalStorage) -> R) -> R {
+    runtime_storage(id).with(f)
+}
 
-    for callback in callbacks {
-        callback();
-    }
+/// The storage of the runtime `id`.
+///
+/// Panics if the runtime has been dropped.
+pub(crate) fn runtime_storage(id: RuntimeId) -> Rc<RuntimeStorage> {
+    RUNTIMES
+        .with(|runtimes| runtimes.borrow().get(id).and_then(Weak::upgrade))
+        .expect("Signal runtime was dropped")
+}
+
+/// Run `f` without tracking its reads in the runtime `id`.
+pub(crate) fn untracked<R>(id: RuntimeId, f: impl FnOnce() -> R) -> R {
+    let previous = with_runtime_storage(id, |storage| storage.set_observer(None));
+    let result = f();
+    with_runtime_storage(id, |storage| storage.set_observer(previous));
+    result
+}
+
+/// Run `f` with reads tracked under `observer`, restoring the previous observer after.
+#[cfg(feature = "gpui")]
+pub(crate) fn with_observer<R>(observer: Option<SignalId>, f: impl FnOnce() -> R) -> R {
+    let previous = with_signal_storage(|storage| storage.set_observer(observer));
+    let result = f();
+    with_signal_storage(|storage| storage.set_observer(previous));
+    result
 }
 
 #[cfg(test)]
@@ -187,23 +1037,63 @@ mod tests {
     #[test]
     fn test_insert_and_get() {
         with_signal_storage(|storage| {
-            let id = storage.insert(42i32);
-            assert_eq!(storage.get::<i32>(id, 0), Some(&42));
+            let (id, generation) = storage.insert(42i32);
+            assert_eq!(storage.get::<i32>(id, generation), Some(&42));
+        });
+    }
+
+    #[test]
+    fn test_primitives_are_stored_inline() {
+        with_signal_storage(|storage| {
+            let (count, count_generation) = storage.insert(1u64);
+            let (name, name_generation) = storage.insert(String::from("a"));
+            assert!(matches!(
+                storage.values[count].value,
+                StoredValue::Inline(InlineValue::U64(1))
+            ));
+            assert!(matches!(storage.values[name].value, StoredValue::Boxed(_)));
+
+            storage.replace(count, count_generation, 2u64).unwrap();
+            storage
+                .replace(name, name_generation, String::from("b"))
+                .unwrap();
+            assert_eq!(storage.get::<u64>(count, count_generation), Some(&2));
+            assert_eq!(storage.get::<i64>(count, count_generation), None);
+            assert_eq!(
+                storage
+                    .get::<String>(name, name_generation)
+                    .map(String::as_str),
+                Some("b")
+            );
         });
     }
 
     #[test]
     fn test_update() {
         with_signal_storage(|storage| {
-            let id = storage.insert(10i32);
+            let (id, generation) = storage.insert(10i32);
             let callbacks = storage
-                .update::<i32, _>(id, 0, |value| *value += 5)
+                .update::<i32, _>(id, generation, |value| *value += 5)
                 .map(|(_, callbacks)| callbacks)
                 .unwrap();
             for callback in callbacks {
                 callback();
             }
-            assert_eq!(storage.get::<i32>(id, 0), Some(&15));
+            assert_eq!(storage.get::<i32>(id, generation), Some(&15));
+        });
+    }
+
+    #[test]
+    fn test_display_reuses_text_until_write() {
+        with_signal_storage(|storage| {
+            let (id, generation) = storage.insert(7i32);
+            let first = storage.display::<i32>(id, generation).unwrap();
+            let second = storage.display::<i32>(id, generation).unwrap();
+            assert_eq!(&*first, "7");
+            assert!(Arc::ptr_eq(&first, &second));
+
+            storage.set(id, generation, 8i32);
+            assert_eq!(&*storage.display::<i32>(id, generation).unwrap(), "8");
         });
     }
 
@@ -213,7 +1103,7 @@ mod tests {
         use std::sync::Arc;
 
         with_signal_storage(|storage| {
-            let id = storage.insert(0i32);
+            let (id, generation) = storage.insert(0i32);
             let called = Arc::new(Mutex::new(false));
             let called_clone = called.clone();
 
@@ -221,11 +1111,46 @@ mod tests {
                 *called_clone.lock() = true;
             });
 
-            let callbacks = storage.set(id, 0, 10).unwrap();
+            let callbacks = storage.set(id, generation, 10).unwrap();
             for callback in callbacks {
                 callback();
             }
             assert!(*called.lock());
         });
     }
+
+    #[test]
+    fn test_observer_edges_are_not_duplicated_or_leaked() {
+        with_signal_storage(|storage| {
+            let (source, _) = storage.insert(0i32);
+            let (observer, _) = storage.insert(());
+
+            let previous = storage.set_observer(Some(observer));
+            for _ in 0..3 {
+                storage.clear_dependencies(observer);
+                storage.track_read(source);
+                storage.track_read(source);
+            }
+            storage.set_observer(previous);
+            assert_eq!(storage.observers[&source].len(), 1);
+
+            storage.dispose(observer);
+            assert!(storage.observers[&source].is_empty());
+            assert!(!storage.dependencies.contains_key(&observer));
+        });
+    }
+
+    #[test]
+    fn test_reused_slot_rejects_stale_generation() {
+        with_signal_storage(|storage| {
+            let (old_id, old_generation) = storage.insert(1i32);
+            storage.dispose(old_id);
+            let (new_id, new_generation) = storage.insert(2i32);
+
+            assert_ne!(old_generation, new_generation);
+            assert_eq!(storage.get::<i32>(old_id, old_generation), None);
+            assert_eq!(storage.get::<i32>(new_id, old_generation), None);
+            assert_eq!(storage.get::<i32>(new_id, new_generation), Some(&2));
+        });
+    }
 }
@@ -0,0 +1,163 @@
+//! Signal changes as a `futures::Stream`, and futures that wait on signal values.
+
+use crate::{Memo, ReadOnlySignal, Signal};
+use futures::channel::{mpsc, oneshot};
+use futures::Stream;
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A stream of a signal's values, yielding one item per change.
+///
+/// Created by `to_stream` on [`Signal`], [`ReadOnlySignal`], and [`Memo`]. Combinators
+/// from the futures ecosystem apply directly, for example to debounce or batch changes.
+#[must_use = "streams do nothing unless polled"]
+pub struct SignalStream<T> {
+    receiver: mpsc::UnboundedReceiver<T>,
+}
+
+impl<T> Stream for SignalStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+impl<T: Clone + 'static> Signal<T> {
+    /// A stream of this signal's values after each change.
+    ///
+    /// The current value is not yielded; every later write is, even if the stream is
+    /// polled less often than the signal changes. Dropping the stream unsubscribes it
+    /// at the signal's next change.
+    pub fn to_stream(&self) -> SignalStream<T> {
+        self.read_only().to_stream()
+    }
+
+    /// Wait until `predicate` holds for the signal's value, and resolve to that value.
+    ///
+    /// Resolves immediately if the predicate already holds, and to `None` if the
+    /// signal is disposed first. The future works on any executor, including GPUI's,
+    /// so an async task can pause on UI state without polling.
+    pub fn wait_until(
+        &self,
+        predicate: impl Fn(&T) -> bool + 'static,
+    ) -> impl Future<Output = Option<T>> {
+        self.read_only().wait_until(predicate)
+    }
+}
+
+impl<T: Clone + 'static> ReadOnlySignal<T> {
+    /// A stream of this signal's values after each change.
+    pub fn to_stream(&self) -> SignalStream<T> {
+        let (sender, receiver) = mpsc::unbounded();
+        let signal = *self;
+        let sent_version = Cell::new(signal.version());
+        // Unsubscribes at the first change after the stream is dropped.
+        self.subscribe_while(move || {
+            if sender.is_closed() {
+                return false;
+            }
+            // A notification only means the value may have changed, so send once per
+            // write rather than once per notification.
+            let version = signal.version();
+            if sent_version.replace(version) != version {
+                let _ = sender.unbounded_send(signal.get_untracked());
+            }
+            true
+        });
+        SignalStream { receiver }
+    }
+
+    /// Wait until `predicate` holds for the signal's value, and resolve to that value.
+    pub fn wait_until(
+        &self,
+        predicate: impl Fn(&T) -> bool + 'static,
+    ) -> impl Future<Output = Option<T>> {
+        let (sender, receiver) = oneshot::channel();
+        if self.with_untracked(&predicate) {
+            let _ = sender.send(self.get_untracked());
+        } else {
+            let signal = *self;
+            let sender = Cell::new(Some(sender));
+            self.subscribe_while(move || {
+                let Some(pending) = sender.take() else {
+                    return false;
+                };
+                if pending.is_canceled() {
+                    return false;
+                }
+                if signal.with_untracked(&predicate) {
+                    let _ = pending.send(signal.get_untracked());
+                    return false;
+                }
+                sender.set(Some(pending));
+                true
+            });
+        }
+        async move { receiver.await.ok() }
+    }
+}
+
+impl<T: Clone + 'static> Memo<T> {
+    /// A stream of the computed values after each change.
+    pub fn to_stream(&self) -> SignalStream<T> {
+        self.signal().to_stream()
+    }
+
+    /// Wait until `predicate` holds for the computed value, and resolve to that value.
+    pub fn wait_until(
+        &self,
+        predicate: impl Fn(&T) -> bool + 'static,
+    ) -> impl Future<Output = Option<T>> {
+        self.signal().wait_until(predicate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::StreamExt;
+
+    #[test]
+    fn test_stream_yields_each_change() {
+        let signal = Signal::new(0);
+        let mut stream = signal.to_stream();
+
+        signal.set(1);
+        signal.set(2);
+        assert_eq!(block_on(stream.next()), Some(1));
+        assert_eq!(block_on(stream.next()), Some(2));
+    }
+
+    #[test]
+    fn test_stream_combinators_apply() {
+        let count = Signal::new(0);
+        let doubled = Memo::new(move || count.get() * 2);
+        let stream = doubled
+            .to_stream()
+            .filter(|value| futures::future::ready(value % 4 == 0));
+
+        for value in 1..=4 {
+            count.set(value);
+        }
+        assert_eq!(block_on(stream.take(2).collect::<Vec<_>>()), vec![4, 8]);
+    }
+
+    #[test]
+    fn test_wait_until_resolves_when_predicate_holds() {
+        let dialog_open = Signal::new(true);
+        assert_eq!(block_on(dialog_open.wait_until(|open| *open)), Some(true));
+
+        let closed = dialog_open.wait_until(|open| !*open);
+        dialog_open.set(true);
+        dialog_open.set(false);
+        assert_eq!(block_on(closed), Some(false));
+
+        let never = dialog_open.wait_until(|open| *open);
+        dialog_open.dispose();
+        assert_eq!(block_on(never), None);
+    }
+}
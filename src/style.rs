@@ -0,0 +1,119 @@
+//! Element-builder extensions that style elements from signals.
+
+use crate::ReadOnlySignal;
+use gpui::{Fill, Hsla, Styled};
+
+/// Style methods that take their values from signals or memos.
+///
+/// Each method reads its signal while the element is being built, so the read is
+/// tracked by the narrowest enclosing scope: a [`ReactiveRegion`](crate::ReactiveRegion)
+/// if there is one, otherwise `cx.track_render`. A change re-renders only that scope.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+///
+/// fn button(loading: Signal<bool>) -> impl IntoElement {
+///     div()
+///         .bg(rgb(0x4a9eff))
+///         .when_signal(loading, |this| this.bg(rgb(0x555555)))
+///         .child("Fetch")
+/// }
+/// ```
+pub trait SignalStyled: Styled + Sized {
+    /// Set the background from a signal.
+    fn bg_signal<F>(self, fill: impl Into<ReadOnlySignal<F>>) -> Self
+    where
+        F: Into<Fill> + Clone + 'static,
+    {
+        self.bg(fill.into().get())
+    }
+
+    /// Set the text color from a signal.
+    fn text_color_signal<C>(self, color: impl Into<ReadOnlySignal<C>>) -> Self
+    where
+        C: Into<Hsla> + Clone + 'static,
+    {
+        self.text_color(color.into().get())
+    }
+
+    /// Set the border color from a signal.
+    fn border_color_signal<C>(self, color: impl Into<ReadOnlySignal<C>>) -> Self
+    where
+        C: Into<Hsla> + Clone + 'static,
+    {
+        self.border_color(color.into().get())
+    }
+
+    /// Apply `then` to the element only while `flag` is true.
+    fn when_signal(
+        self,
+        flag: impl Into<ReadOnlySignal<bool>>,
+        then: impl FnOnce(Self) -> Self,
+    ) -> Self {
+        if flag.into().get() {
+            then(self)
+        } else {
+            self
+        }
+    }
+
+    /// Apply `then` while `flag` is true and `otherwise` while it is false.
+    fn when_signal_else(
+        self,
+        flag: impl Into<ReadOnlySignal<bool>>,
+        then: impl FnOnce(Self) -> Self,
+        otherwise: impl FnOnce(Self) -> Self,
+    ) -> Self {
+        if flag.into().get() {
+            then(self)
+        } else {
+            otherwise(self)
+        }
+    }
+}
+
+impl<E: Styled> SignalStyled for E {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Memo, Signal};
+    use gpui::{div, rgb, Div};
+
+    fn background(element: &mut Div) -> Option<Fill> {
+        element.style().background.clone()
+    }
+
+    #[test]
+    fn test_when_signal_follows_flag() {
+        let flag = Signal::new(false);
+        let mut element = div().when_signal(flag, |this| this.bg(rgb(0xff0000)));
+        assert!(background(&mut element).is_none());
+
+        flag.set(true);
+        let mut element = div().when_signal(flag, |this| this.bg(rgb(0xff0000)));
+        assert_eq!(background(&mut element), Some(rgb(0xff0000).into()));
+    }
+
+    #[test]
+    fn test_bg_signal_accepts_memo() {
+        let dark = Signal::new(true);
+        let color = Memo::new(move || {
+            if dark.get() {
+                rgb(0x000000)
+            } else {
+                rgb(0xffffff)
+            }
+        });
+
+        let mut element = div().bg_signal(color);
+        assert_eq!(background(&mut element), Some(rgb(0x000000).into()));
+
+        dark.set(false);
+        let mut element = div().bg_signal(color);
+        assert_eq!(background(&mut element), Some(rgb(0xffffff).into()));
+    }
+}
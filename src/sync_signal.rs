@@ -0,0 +1,119 @@
+//! Signals that can be written from background threads.
+
+use crate::context::track_subscription;
+use crate::{Signal, SignalContext};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use gpui::{Context, Subscription};
+
+/// A handle for writing a signal from any thread.
+///
+/// Signals live in thread-local storage, so a background computation cannot set one
+/// directly. `SyncSignal::set` sends the value over a channel instead, and the write
+/// is applied on the foreground thread, where the underlying [`Signal`] is read as
+/// usual.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+///
+/// struct Export {
+///     progress: SyncSignal<u32>,
+/// }
+///
+/// impl Export {
+///     fn new(cx: &mut Context<Self>) -> Self {
+///         let progress = SyncSignal::new(0, cx);
+///         let writer = progress.clone();
+///         cx.background_spawn(async move {
+///             for percent in 0..=100 {
+///                 writer.set(percent);
+///             }
+///         })
+///         .detach();
+///         Self { progress }
+///     }
+/// }
+///
+/// impl Render for Export {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         div().child(self.progress.signal())
+///     }
+/// }
+/// ```
+pub struct SyncSignal<T> {
+    signal: Signal<T>,
+    sender: mpsc::UnboundedSender<T>,
+}
+
+impl<T> Clone for SyncSignal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            signal: self.signal,
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<T: Send + 'static> SyncSignal<T> {
+    /// Create a signal owned by the entity, along with a handle for writing it from
+    /// any thread.
+    ///
+    /// The entity is notified whenever a write is applied.
+    pub fn new<V: 'static>(initial: T, cx: &mut Context<V>) -> Self {
+        let signal = cx.create_signal(initial);
+        let (sender, mut receiver) = mpsc::unbounded::<T>();
+        let task = cx.spawn(async move |_, _| {
+            while let Some(value) = receiver.next().await {
+                signal.set(value);
+            }
+        });
+        track_subscription(cx, Subscription::new(move || drop(task)));
+        Self { signal, sender }
+    }
+
+    /// Set the signal's value from any thread.
+    ///
+    /// The write is applied on the foreground thread once its executor next runs.
+    /// Writes made after the owning entity is released are dropped.
+    pub fn set(&self, value: T) {
+        let _ = self.sender.unbounded_send(value);
+    }
+
+    /// The underlying signal, for reading on the foreground thread.
+    pub fn signal(&self) -> Signal<T> {
+        self.signal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext, TestAppContext};
+
+    struct Export {
+        progress: SyncSignal<u32>,
+    }
+
+    #[gpui::test]
+    async fn test_set_from_another_thread(cx: &mut TestAppContext) {
+        let export = cx.new(|cx| Export {
+            progress: SyncSignal::new(0, cx),
+        });
+        let progress = export.read_with(cx, |export, _| export.progress.clone());
+
+        let writer = progress.clone();
+        std::thread::spawn(move || {
+            writer.set(50);
+            writer.set(100);
+        })
+        .join()
+        .unwrap();
+        assert_eq!(progress.signal().get(), 0);
+
+        cx.run_until_parked();
+        assert_eq!(progress.signal().get(), 100);
+    }
+}
@@ -0,0 +1,181 @@
+//! Counting in-flight tasks as signals.
+
+use crate::runtime::SignalRuntime;
+use crate::{Memo, ReadOnlySignal, Signal};
+use gpui::{App, AsyncApp, Task};
+use std::fmt;
+use std::future::Future;
+
+/// Counts the tasks registered through it that are still in flight.
+///
+/// A task counts from the moment it is registered until it completes or is dropped,
+/// so cancelled tasks do not leave the count stuck. Reading
+/// [`is_busy`](Self::is_busy) in a render or effect makes busy indicators and
+/// "quit while saving?" prompts follow the count.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+/// use std::time::Duration;
+///
+/// struct Editor {
+///     saves: TaskTracker,
+/// }
+///
+/// impl Editor {
+///     fn new(cx: &mut Context<Self>) -> Self {
+///         Self {
+///             saves: cx.create_task_tracker(),
+///         }
+///     }
+///
+///     fn save(&self, cx: &mut Context<Self>) {
+///         self.saves
+///             .spawn(cx, async |cx| {
+///                 cx.background_executor().timer(Duration::from_secs(1)).await;
+///             })
+///             .detach();
+///     }
+/// }
+///
+/// impl Render for Editor {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         div().when(self.saves.is_busy().get(), |this| this.child("Saving..."))
+///     }
+/// }
+/// ```
+pub struct TaskTracker {
+    pending: Signal<usize>,
+    is_busy: Memo<bool>,
+}
+
+impl Copy for TaskTracker {}
+
+impl Clone for TaskTracker {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl TaskTracker {
+    /// Create a tracker with no tasks in flight.
+    pub fn new() -> Self {
+        Self::new_in(&SignalRuntime::default())
+    }
+
+    /// Create a tracker in `runtime`.
+    pub fn new_in(runtime: &SignalRuntime) -> Self {
+        let pending = Signal::new_in(0, runtime);
+        Self::from_parts(pending, Memo::new_in(move || pending.get() > 0, runtime))
+    }
+
+    pub(crate) fn from_parts(pending: Signal<usize>, is_busy: Memo<bool>) -> Self {
+        Self { pending, is_busy }
+    }
+
+    /// The number of tasks in flight.
+    pub fn pending(&self) -> ReadOnlySignal<usize> {
+        self.pending.read_only()
+    }
+
+    /// Whether any task is in flight.
+    pub fn is_busy(&self) -> Memo<bool> {
+        self.is_busy
+    }
+
+    /// Count `future` as in flight until it completes or is dropped.
+    ///
+    /// The count goes up immediately, not when the future is first polled.
+    pub fn track<F: Future>(&self, future: F) -> impl Future<Output = F::Output> {
+        let guard = PendingGuard::new(self.pending);
+        async move {
+            let _guard = guard;
+            future.await
+        }
+    }
+
+    /// Spawn `f` on the foreground executor and count it until it completes or its
+    /// task is dropped.
+    pub fn spawn<R: 'static>(
+        &self,
+        cx: &App,
+        f: impl AsyncFnOnce(&mut AsyncApp) -> R + 'static,
+    ) -> Task<R> {
+        let guard = PendingGuard::new(self.pending);
+        cx.spawn(async move |cx| {
+            let _guard = guard;
+            f(cx).await
+        })
+    }
+
+    /// Free the count and its memo.
+    pub fn dispose(self) {
+        self.is_busy.signal().dispose();
+        self.pending.dispose();
+    }
+}
+
+impl Default for TaskTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for TaskTracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskTracker")
+            .field("pending", &self.pending.get_untracked())
+            .finish()
+    }
+}
+
+struct PendingGuard {
+    pending: Signal<usize>,
+}
+
+impl PendingGuard {
+    fn new(pending: Signal<usize>) -> Self {
+        pending.update(|count| *count += 1);
+        Self { pending }
+    }
+}
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        self.pending
+            .update(|count| *count = count.saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::channel::oneshot;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_counts_tasks_until_completed_or_dropped(cx: &mut TestAppContext) {
+        let tracker = TaskTracker::new();
+        let (finish, finished) = oneshot::channel::<()>();
+        let completing = cx.update(|cx| {
+            tracker.spawn(cx, async move |_| {
+                let _ = finished.await;
+            })
+        });
+        let cancelled =
+            cx.update(|cx| tracker.spawn(cx, async move |_| std::future::pending::<()>().await));
+        assert_eq!(tracker.pending().get(), 2);
+        assert!(tracker.is_busy().get());
+
+        finish.send(()).unwrap();
+        completing.await;
+        assert_eq!(tracker.pending().get(), 1);
+
+        drop(cancelled);
+        cx.run_until_parked();
+        assert_eq!(tracker.pending().get(), 0);
+        assert!(!tracker.is_busy().get());
+    }
+}
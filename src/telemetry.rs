@@ -0,0 +1,100 @@
+//! Metrics about the reactive graph, published through the [`metrics`] crate facade.
+//!
+//! With the `metrics` feature enabled, the crate records the metrics named below to
+//! whichever recorder the app installed, such as a Prometheus exporter, so reactive
+//! overhead can be watched alongside the app's other telemetry. Without a recorder
+//! the calls do nothing.
+
+/// Counter of signals created, including those memos and effects track their reads
+/// under.
+pub const SIGNALS_CREATED: &str = "gpui_signals.signals_created";
+
+/// Gauge of signals created and not yet disposed.
+pub const LIVE_SIGNALS: &str = "gpui_signals.live_signals";
+
+/// Counter of subscriber, effect, and memo notifications delivered by writes.
+pub const NOTIFICATIONS_DELIVERED: &str = "gpui_signals.notifications_delivered";
+
+/// Counter of memo computations.
+pub const MEMO_RECOMPUTES: &str = "gpui_signals.memo_recomputes";
+
+/// Histogram of the notifications delivered per write; its mean is the average
+/// fan-out.
+pub const FAN_OUT: &str = "gpui_signals.fan_out";
+
+pub(crate) fn signal_created() {
+    metrics::counter!(SIGNALS_CREATED).increment(1);
+    metrics::gauge!(LIVE_SIGNALS).increment(1.0);
+}
+
+pub(crate) fn signal_disposed() {
+    metrics::gauge!(LIVE_SIGNALS).decrement(1.0);
+}
+
+pub(crate) fn notifications_delivered(count: usize) {
+    metrics::counter!(NOTIFICATIONS_DELIVERED).increment(count as u64);
+    metrics::histogram!(FAN_OUT).record(count as f64);
+}
+
+pub(crate) fn memo_recomputed() {
+    metrics::counter!(MEMO_RECOMPUTES).increment(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Memo, Signal};
+    use metrics::{
+        Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Counters(Mutex<HashMap<String, Arc<AtomicU64>>>);
+
+    impl Counters {
+        fn get(&self, name: &str) -> u64 {
+            let counters = self.0.lock().unwrap();
+            counters
+                .get(name)
+                .map_or(0, |counter| counter.load(Ordering::Relaxed))
+        }
+    }
+
+    impl Recorder for Counters {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            let mut counters = self.0.lock().unwrap();
+            Counter::from_arc(counters.entry(key.name().to_string()).or_default().clone())
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn test_graph_activity_is_counted() {
+        let counters = Counters::default();
+        metrics::with_local_recorder(&counters, || {
+            let count = Signal::new(1);
+            let doubled = Memo::new(move || count.get() * 2);
+            doubled.subscribe(|| {});
+            count.set(2);
+            assert_eq!(doubled.get(), 4);
+        });
+
+        assert_eq!(counters.get(SIGNALS_CREATED), 2);
+        assert_eq!(counters.get(MEMO_RECOMPUTES), 2);
+        assert_eq!(counters.get(NOTIFICATIONS_DELIVERED), 1);
+    }
+}
@@ -0,0 +1,553 @@
+//! Helpers for testing views built on signals.
+//!
+//! [`assert_signal!`](crate::assert_signal) and
+//! [`assert_signal_eventually!`](crate::assert_signal_eventually) check a signal's
+//! value against a [`Matcher`], and [`collect_values`] gathers the values a signal
+//! takes during a test. These work without the `gpui` feature.
+
+pub mod matchers;
+
+pub use matchers::Matcher;
+
+use crate::ReadOnlySignal;
+#[cfg(feature = "test-support")]
+use crate::Signal;
+use futures::channel::oneshot;
+use futures::future::{self, Either};
+use futures::StreamExt;
+#[cfg(feature = "test-support")]
+use gpui::TestAppContext;
+#[cfg(feature = "gpui")]
+use std::cell::Cell;
+#[cfg(feature = "test-support")]
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::future::Future;
+use std::panic::Location;
+#[cfg(feature = "test-support")]
+use std::path::Path;
+#[cfg(feature = "test-support")]
+use std::str::FromStr;
+use std::time::Duration;
+#[cfg(feature = "test-support")]
+use std::{fs, io};
+use std::{pin, thread};
+
+/// Assert that a signal's current value satisfies a matcher.
+///
+/// The matchers in [`testing::matchers`](crate::testing::matchers) are in scope for
+/// the second argument, and any [`Matcher`](crate::testing::Matcher), including a
+/// closure over the value, can be passed. Works with [`Signal`](crate::Signal),
+/// [`ReadOnlySignal`](crate::ReadOnlySignal), and [`Memo`](crate::Memo).
+///
+/// # Examples
+///
+/// ```rust
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::assert_signal;
+///
+/// let count = Signal::new(5);
+/// let doubled = Memo::new(move || count.get() * 2);
+/// assert_signal!(count, eq(5));
+/// assert_signal!(doubled, gt(8));
+/// assert_signal!(doubled, |value: &i32| value % 2 == 0);
+/// ```
+#[macro_export]
+macro_rules! assert_signal {
+    ($signal:expr, $matcher:expr $(,)?) => {{
+        let signal = $signal;
+        let matcher = {
+            #[allow(unused_imports)]
+            use $crate::testing::matchers::*;
+            $matcher
+        };
+        $crate::testing::check_signal(signal, matcher, stringify!($signal))
+    }};
+}
+
+/// Wait until a signal's value satisfies a matcher, failing after a timeout.
+///
+/// Evaluates to the matching value. This awaits, so it belongs in an async test,
+/// typically one waiting on a [`Resource`](crate::Resource) or a spawned task to
+/// write its result. Matchers are resolved as in [`assert_signal!`](crate::assert_signal).
+///
+/// The timeout is measured in real time on a helper thread, so it works on any
+/// executor. GPUI's test executor fails as soon as it has nothing left to run, so
+/// call `cx.executor().allow_parking()` before waiting on writes from outside it.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::assert_signal_eventually;
+/// use std::time::Duration;
+///
+/// # async fn example(status: Signal<String>) {
+/// let status = assert_signal_eventually!(
+///     status,
+///     |status: &String| status.starts_with("Loaded"),
+///     Duration::from_secs(1)
+/// );
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_signal_eventually {
+    ($signal:expr, $matcher:expr, $timeout:expr $(,)?) => {{
+        let signal = $signal;
+        let matcher = {
+            #[allow(unused_imports)]
+            use $crate::testing::matchers::*;
+            $matcher
+        };
+        $crate::testing::wait_for_signal(
+            signal,
+            matcher,
+            $timeout,
+            stringify!($signal),
+            ::std::panic::Location::caller(),
+        )
+        .await
+    }};
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn check_signal<T: Clone + fmt::Debug + 'static>(
+    signal: impl Into<ReadOnlySignal<T>>,
+    matcher: impl Matcher<T>,
+    expression: &str,
+) {
+    let signal = signal.into();
+    let value = signal.get_untracked();
+    if !matcher.matches(&value) {
+        panic!(
+            "assertion failed: `{expression}` is {value:?}, expected {}{}",
+            matcher.describe(),
+            written_at(signal)
+        );
+    }
+}
+
+#[doc(hidden)]
+pub async fn wait_for_signal<T: Clone + fmt::Debug + 'static>(
+    signal: impl Into<ReadOnlySignal<T>>,
+    matcher: impl Matcher<T> + 'static,
+    timeout: Duration,
+    expression: &str,
+    location: &Location<'static>,
+) -> T {
+    let signal = signal.into();
+    let description = matcher.describe();
+    let matched = pin::pin!(signal.wait_until(move |value| matcher.matches(value)));
+    match future::select(matched, timer(timeout)).await {
+        Either::Left((Some(value), _)) => value,
+        Either::Left((None, _)) => panic!(
+            "assertion failed at {location}: `{expression}` was disposed before it was {description}"
+        ),
+        Either::Right(_) => panic!(
+            "assertion failed at {location}: `{expression}` was still {:?} after {timeout:?}, expected {description}{}",
+            signal.try_get(),
+            written_at(signal)
+        ),
+    }
+}
+
+/// Where the signal was last written, for failure messages.
+fn written_at<T: 'static>(signal: ReadOnlySignal<T>) -> String {
+    signal
+        .last_writer()
+        .map(|location| format!(" (last written at {location})"))
+        .unwrap_or_default()
+}
+
+fn timer(duration: Duration) -> oneshot::Receiver<()> {
+    let (sender, receiver) = oneshot::channel();
+    thread::spawn(move || {
+        thread::sleep(duration);
+        let _ = sender.send(());
+    });
+    receiver
+}
+
+/// Collect the next `count` values written to a signal.
+///
+/// The signal is subscribed to when this is called, so make the writes after it and
+/// await the future once they are done, or concurrently with it. The future resolves
+/// early, with the values so far, if the signal is disposed.
+///
+/// # Examples
+///
+/// ```rust
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::testing::collect_values;
+///
+/// let step = Signal::new(0);
+/// let values = collect_values(step, 3);
+/// for value in 1..=3 {
+///     step.set(value * 10);
+/// }
+/// assert_eq!(futures::executor::block_on(values), [10, 20, 30]);
+/// ```
+pub fn collect_values<T: Clone + 'static>(
+    signal: impl Into<ReadOnlySignal<T>>,
+    count: usize,
+) -> impl Future<Output = Vec<T>> {
+    signal.into().to_stream().take(count).collect()
+}
+
+#[cfg(feature = "gpui")]
+thread_local! {
+    static SYNCHRONOUS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Notify entities inline when their signals change, on this thread, until the
+/// returned guard is dropped.
+///
+/// Normally a write reaches an entity's `cx.notify()` through a task on the
+/// foreground executor, so a test has to call `cx.run_until_parked()` before it
+/// sees the re-render. In synchronous mode the notification is applied before the
+/// write returns.
+///
+/// This affects entities whose signals are created while the guard is held, so
+/// enable it at the start of the test, and write signals from the test body rather
+/// than inside `cx.update` or `entity.update`, where the app can't be updated again.
+/// Writes made by observers while a notification is being applied are delivered
+/// through the executor as usual.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::testing;
+///
+/// struct Counter {
+///     count: Signal<i32>,
+/// }
+///
+/// fn test_counter_notifies(cx: &mut TestAppContext) {
+///     let _synchronous = testing::synchronous_mode();
+///     let counter = cx.new(|cx| Counter {
+///         count: cx.create_signal(0),
+///     });
+///     let count = counter.read_with(cx, |counter, _| counter.count);
+///
+///     let notified = std::rc::Rc::new(std::cell::Cell::new(false));
+///     let _observation = cx.update(|cx| {
+///         let notified = notified.clone();
+///         cx.observe(&counter, move |_, _| notified.set(true))
+///     });
+///     count.set(1);
+///     assert!(notified.get());
+/// }
+/// ```
+#[cfg(feature = "gpui")]
+pub fn synchronous_mode() -> SynchronousMode {
+    SynchronousMode {
+        previous: SYNCHRONOUS.with(|synchronous| synchronous.replace(true)),
+    }
+}
+
+/// Keeps [`synchronous_mode`] on for this thread until dropped.
+#[cfg(feature = "gpui")]
+#[must_use = "synchronous mode ends when the guard is dropped"]
+pub struct SynchronousMode {
+    previous: bool,
+}
+
+#[cfg(feature = "gpui")]
+impl Drop for SynchronousMode {
+    fn drop(&mut self) {
+        SYNCHRONOUS.with(|synchronous| synchronous.set(self.previous));
+    }
+}
+
+#[cfg(feature = "gpui")]
+pub(crate) fn is_synchronous() -> bool {
+    SYNCHRONOUS.with(Cell::get)
+}
+
+/// One write read back from a [`FlightRecorder`](crate::FlightRecorder) log.
+#[cfg(feature = "test-support")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedWrite {
+    /// When the write was made, in milliseconds since the Unix epoch.
+    pub millis: u128,
+    /// The name the signal was recorded under.
+    pub name: String,
+    /// The value's `Debug` output.
+    pub value: String,
+}
+
+#[cfg(feature = "test-support")]
+type ReplayTarget = Box<dyn Fn(&str)>;
+#[cfg(feature = "test-support")]
+type Checkpoint = Box<dyn FnOnce(&mut TestAppContext)>;
+
+/// Replays a recorded session of signal writes against a test app.
+///
+/// Each recorded name is bound to the signal or closure that reproduces its writes.
+/// After every write the executor runs until parked, so effects, resources, and
+/// re-renders settle before the next one, and checkpoints registered after a given
+/// number of writes can assert on memo values or rendered views. Writes to names
+/// with no target are skipped.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::testing::Replay;
+///
+/// struct Cart {
+///     quantity: Signal<u32>,
+///     total: Memo<u32>,
+/// }
+///
+/// fn test_cart_session(cx: &mut TestAppContext) {
+///     let cart = cx.new(|cx| {
+///         let quantity = cx.create_signal(0);
+///         let total = cx.create_memo(move || quantity.get() * 15);
+///         Cart { quantity, total }
+///     });
+///     let (quantity, total) = cart.read_with(cx, |cart, _| (cart.quantity, cart.total));
+///
+///     Replay::from_file("tests/sessions/checkout.log")
+///         .unwrap()
+///         .signal("quantity", quantity)
+///         .checkpoint(2, move |_| assert_eq!(total.get(), 45))
+///         .run(cx);
+/// }
+/// ```
+#[cfg(feature = "test-support")]
+pub struct Replay {
+    writes: Vec<RecordedWrite>,
+    targets: HashMap<String, ReplayTarget>,
+    checkpoints: BTreeMap<usize, Vec<Checkpoint>>,
+}
+
+#[cfg(feature = "test-support")]
+impl Replay {
+    /// Read a session from a recorder log file.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Read a session from the contents of a recorder log.
+    pub fn parse(log: &str) -> io::Result<Self> {
+        let writes = log
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                parse_write(line).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("not a recorded write: {line}"),
+                    )
+                })
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self {
+            writes,
+            targets: HashMap::new(),
+            checkpoints: BTreeMap::new(),
+        })
+    }
+
+    /// The recorded writes, in order.
+    pub fn writes(&self) -> &[RecordedWrite] {
+        &self.writes
+    }
+
+    /// Replay writes recorded under `name` by parsing them into `signal`.
+    ///
+    /// Panics during [`run`](Self::run) if a recorded value doesn't parse, since the
+    /// session no longer matches the code under test.
+    pub fn signal<T>(self, name: impl Into<String>, signal: Signal<T>) -> Self
+    where
+        T: FromStr + 'static,
+        T::Err: fmt::Debug,
+    {
+        let name = name.into();
+        let target_name = name.clone();
+        self.target(name, move |value| match value.parse() {
+            Ok(value) => signal.set(value),
+            Err(error) => panic!("recorded `{target_name}` value {value} is invalid: {error:?}"),
+        })
+    }
+
+    /// Replay writes recorded under `name` with `apply`, which receives each value's
+    /// `Debug` output.
+    pub fn target(mut self, name: impl Into<String>, apply: impl Fn(&str) + 'static) -> Self {
+        self.targets.insert(name.into(), Box::new(apply));
+        self
+    }
+
+    /// Run `check` once the first `writes` writes have been replayed and settled.
+    pub fn checkpoint(
+        mut self,
+        writes: usize,
+        check: impl FnOnce(&mut TestAppContext) + 'static,
+    ) -> Self {
+        self.checkpoints
+            .entry(writes)
+            .or_default()
+            .push(Box::new(check));
+        self
+    }
+
+    /// Replay every write, running checkpoints as they are reached.
+    pub fn run(mut self, cx: &mut TestAppContext) {
+        self.run_checkpoints(0, cx);
+        let writes = std::mem::take(&mut self.writes);
+        for (index, write) in writes.iter().enumerate() {
+            if let Some(apply) = self.targets.get(&write.name) {
+                apply(&write.value);
+                cx.run_until_parked();
+            }
+            self.run_checkpoints(index + 1, cx);
+        }
+        // Checkpoints past the end of the session still run, against the final state.
+        for check in std::mem::take(&mut self.checkpoints)
+            .into_values()
+            .flatten()
+        {
+            check(cx);
+        }
+    }
+
+    fn run_checkpoints(&mut self, writes: usize, cx: &mut TestAppContext) {
+        for check in self.checkpoints.remove(&writes).unwrap_or_default() {
+            check(cx);
+        }
+    }
+}
+
+#[cfg(feature = "test-support")]
+fn parse_write(line: &str) -> Option<RecordedWrite> {
+    let (millis, entry) = line.split_once(' ')?;
+    let (name, value) = entry.split_once(" = ")?;
+    Some(RecordedWrite {
+        millis: millis.parse().ok()?,
+        name: name.to_string(),
+        value: value.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "test-support")]
+    use crate::SignalContext;
+    use crate::{Memo, Signal};
+    use futures::executor::block_on;
+    #[cfg(feature = "test-support")]
+    use gpui::AppContext;
+    #[cfg(feature = "test-support")]
+    use std::cell::RefCell;
+    #[cfg(feature = "test-support")]
+    use std::rc::Rc;
+
+    #[test]
+    fn test_assert_signal_matches_current_value() {
+        let count = Signal::new(5);
+        let doubled = Memo::new(move || count.get() * 2);
+        assert_signal!(count, eq(5));
+        assert_signal!(count.read_only(), ne(4));
+        assert_signal!(doubled, ge(10));
+        assert_signal!(doubled, |value: &i32| value % 2 == 0);
+
+        let failure = std::panic::catch_unwind(|| assert_signal!(doubled, lt(10))).unwrap_err();
+        assert_eq!(
+            failure.downcast_ref::<String>().unwrap(),
+            "assertion failed: `doubled` is 10, expected less than 10"
+        );
+    }
+
+    #[test]
+    fn test_assert_signal_eventually_waits_for_a_write() {
+        let status = Signal::new("loading");
+        let (sender, receiver) = oneshot::channel();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            let _ = sender.send(());
+        });
+        let write = async move {
+            let _ = receiver.await;
+            status.set("loaded");
+        };
+        let wait =
+            async move { assert_signal_eventually!(status, eq("loaded"), Duration::from_secs(5)) };
+        let ((), value) = block_on(future::join(write, wait));
+        assert_eq!(value, "loaded");
+
+        let timed_out = std::panic::catch_unwind(|| {
+            block_on(async {
+                assert_signal_eventually!(status, eq("failed"), Duration::from_millis(10))
+            })
+        });
+        assert!(timed_out.is_err());
+    }
+
+    #[test]
+    fn test_collect_values_gathers_the_next_writes() {
+        let step = Signal::new(0);
+        let values = collect_values(step, 3);
+        for value in 1..=4 {
+            step.set(value);
+        }
+        assert_eq!(block_on(values), [1, 2, 3]);
+    }
+
+    #[cfg(feature = "test-support")]
+    struct Cart {
+        quantity: Signal<u32>,
+        coupon: Signal<Option<String>>,
+        total: Memo<u32>,
+    }
+
+    #[cfg(feature = "test-support")]
+    #[gpui::test]
+    fn test_replay_applies_writes_and_runs_checkpoints(cx: &mut TestAppContext) {
+        let cart = cx.new(|cx| {
+            let quantity = cx.create_signal(0u32);
+            let coupon = cx.create_signal(None::<String>);
+            let total = cx.create_memo(move || {
+                let discount = if coupon.get().is_some() { 10 } else { 0 };
+                (quantity.get() * 15).saturating_sub(discount)
+            });
+            Cart {
+                quantity,
+                coupon,
+                total,
+            }
+        });
+        let (quantity, coupon, total) =
+            cart.read_with(cx, |cart, _| (cart.quantity, cart.coupon, cart.total));
+
+        let totals = Rc::new(RefCell::new(Vec::new()));
+        let checkpoint = |totals: &Rc<RefCell<Vec<u32>>>| {
+            let totals = totals.clone();
+            move |_: &mut TestAppContext| totals.borrow_mut().push(total.get())
+        };
+        let log = "\
+            1700000000000 quantity = 2\n\
+            1700000000100 scroll = 340.0\n\
+            1700000000200 coupon = Some(\"SPRING\")\n\
+            1700000000300 quantity = 3\n";
+        Replay::parse(log)
+            .unwrap()
+            .signal("quantity", quantity)
+            .target("coupon", move |value| {
+                coupon.set((value != "None").then(|| value.to_string()))
+            })
+            .checkpoint(0, checkpoint(&totals))
+            .checkpoint(1, checkpoint(&totals))
+            .checkpoint(3, checkpoint(&totals))
+            .checkpoint(4, checkpoint(&totals))
+            .run(cx);
+
+        assert_eq!(*totals.borrow(), [0, 30, 20, 35]);
+        assert!(Replay::parse("not a log line").is_err());
+    }
+}
@@ -0,0 +1,92 @@
+//! Matchers for [`assert_signal!`](crate::assert_signal) and
+//! [`assert_signal_eventually!`](crate::assert_signal_eventually).
+//!
+//! The assertion macros bring these into scope for their matcher argument, so
+//! `assert_signal!(count, eq(5))` needs no import.
+
+use std::fmt;
+
+/// A condition on a signal's value, described for failure messages.
+///
+/// Implemented for closures taking the value by reference, which describe
+/// themselves as "a value satisfying the predicate".
+pub trait Matcher<T> {
+    /// Whether `value` satisfies the condition.
+    fn matches(&self, value: &T) -> bool;
+
+    /// The condition as it completes "expected ...", as in "equal to 5".
+    fn describe(&self) -> String;
+}
+
+impl<T, F: Fn(&T) -> bool> Matcher<T> for F {
+    fn matches(&self, value: &T) -> bool {
+        self(value)
+    }
+
+    fn describe(&self) -> String {
+        "a value satisfying the predicate".to_string()
+    }
+}
+
+/// A predicate with a description, returned by the matcher functions.
+pub struct Described<F> {
+    description: String,
+    predicate: F,
+}
+
+impl<T, F: Fn(&T) -> bool> Matcher<T> for Described<F> {
+    fn matches(&self, value: &T) -> bool {
+        (self.predicate)(value)
+    }
+
+    fn describe(&self) -> String {
+        self.description.clone()
+    }
+}
+
+/// Matches `predicate`, described as `description` in failure messages.
+pub fn satisfies<T>(
+    description: impl Into<String>,
+    predicate: impl Fn(&T) -> bool,
+) -> Described<impl Fn(&T) -> bool> {
+    Described {
+        description: description.into(),
+        predicate,
+    }
+}
+
+/// Matches values equal to `expected`.
+pub fn eq<T: PartialEq + fmt::Debug>(expected: T) -> Described<impl Fn(&T) -> bool> {
+    satisfies(format!("equal to {expected:?}"), move |value| {
+        *value == expected
+    })
+}
+
+/// Matches values not equal to `unexpected`.
+pub fn ne<T: PartialEq + fmt::Debug>(unexpected: T) -> Described<impl Fn(&T) -> bool> {
+    satisfies(format!("not equal to {unexpected:?}"), move |value| {
+        *value != unexpected
+    })
+}
+
+/// Matches values greater than `bound`.
+pub fn gt<T: PartialOrd + fmt::Debug>(bound: T) -> Described<impl Fn(&T) -> bool> {
+    satisfies(format!("greater than {bound:?}"), move |value| {
+        *value > bound
+    })
+}
+
+/// Matches values greater than or equal to `bound`.
+pub fn ge<T: PartialOrd + fmt::Debug>(bound: T) -> Described<impl Fn(&T) -> bool> {
+    satisfies(format!("at least {bound:?}"), move |value| *value >= bound)
+}
+
+/// Matches values less than `bound`.
+pub fn lt<T: PartialOrd + fmt::Debug>(bound: T) -> Described<impl Fn(&T) -> bool> {
+    satisfies(format!("less than {bound:?}"), move |value| *value < bound)
+}
+
+/// Matches values less than or equal to `bound`.
+pub fn le<T: PartialOrd + fmt::Debug>(bound: T) -> Described<impl Fn(&T) -> bool> {
+    satisfies(format!("at most {bound:?}"), move |value| *value <= bound)
+}
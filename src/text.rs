@@ -0,0 +1,78 @@
+//! Signals holding GPUI's native text type.
+
+use crate::Signal;
+use gpui::SharedString;
+use std::fmt;
+
+/// A signal holding [`SharedString`], GPUI's native text type.
+///
+/// Rendering a text signal shares its string with the element instead of formatting
+/// it into new text.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+///
+/// struct Status {
+///     message: TextSignal,
+/// }
+///
+/// impl Status {
+///     fn new(cx: &mut Context<Self>) -> Self {
+///         let message: TextSignal = cx.create_signal("Ready".into());
+///         Self { message }
+///     }
+///
+///     fn saved(&self, count: usize) {
+///         self.message.set_fmt(format_args!("Saved {count} files"));
+///     }
+/// }
+///
+/// impl Render for Status {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         div().child(self.message)
+///     }
+/// }
+/// ```
+pub type TextSignal = Signal<SharedString>;
+
+impl Signal<SharedString> {
+    /// Set the text from a string slice.
+    #[track_caller]
+    pub fn set_str(&self, text: &str) {
+        self.set(SharedString::from(text.to_owned()));
+    }
+
+    /// Set the text from formatting arguments, as built by `format_args!`.
+    ///
+    /// Arguments with nothing to format are stored without allocating.
+    #[track_caller]
+    pub fn set_fmt(&self, arguments: fmt::Arguments<'_>) {
+        let text = match arguments.as_str() {
+            Some(text) => SharedString::new_static(text),
+            None => SharedString::from(arguments.to_string()),
+        };
+        self.set(text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::IntoElement;
+
+    #[test]
+    fn test_text_signal_helpers_and_rendering() {
+        let message = TextSignal::new("Ready".into());
+        message.set_fmt(format_args!("Saved {} files", 3));
+        assert_eq!(message.get(), "Saved 3 files");
+
+        message.set_str("Idle");
+        let stored = message.get_untracked();
+        let rendered = message.into_element();
+        assert_eq!(rendered, "Idle");
+        assert_eq!(rendered.as_ptr(), stored.as_ptr());
+    }
+}
@@ -0,0 +1,357 @@
+//! An app-wide theme exposed as reactive design tokens.
+//!
+//! The current [`Theme`] lives in a global signal and each token is a memo over it,
+//! so a view reading `theme::bg_primary().get()` while rendering re-renders when the
+//! theme changes, without holding the theme or subscribing to it. Swap the whole
+//! theme with [`set_theme`].
+//!
+//! ```rust,no_run
+//! use gpui::*;
+//! use gpui_signals::prelude::*;
+//! use gpui_signals::theme::{self, Theme};
+//!
+//! struct Card;
+//!
+//! impl Render for Card {
+//!     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+//!         cx.track_render(|_| {
+//!             div()
+//!                 .bg(theme::bg_secondary().get())
+//!                 .text_color(theme::text_primary().get())
+//!                 .rounded(theme::radius_md().get())
+//!                 .p(theme::spacing_md().get())
+//!                 .on_mouse_down(MouseButton::Left, |_, _, _| theme::set_theme(Theme::dark()))
+//!                 .child("Go dark")
+//!         })
+//!     }
+//! }
+//! ```
+
+use crate::{Memo, ReadOnlySignal};
+use futures::channel::mpsc;
+use futures::future::{self, Either};
+use futures::StreamExt;
+use gpui::{px, rgb, App, Hsla, Pixels, Rgba};
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+
+/// How often an animated theme change updates the colors.
+const FRAME: Duration = Duration::from_millis(16);
+
+/// The theme set by the app and the one tokens currently show, which differ only
+/// while colors are animating towards the target.
+#[derive(Clone, Copy)]
+struct Themes {
+    target: Theme,
+    shown: Theme,
+}
+
+crate::global_signal! {
+    static THEME: Themes = Themes {
+        target: Theme::light(),
+        shown: Theme::light(),
+    };
+}
+
+thread_local! {
+    static TOKENS: Tokens = Tokens::new();
+    static TRANSITION: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+    static ANIMATOR: RefCell<Option<mpsc::UnboundedSender<()>>> = const { RefCell::new(None) };
+}
+
+/// Declares the token memos and one accessor per token.
+macro_rules! tokens {
+    ($($(#[$doc:meta])* $name:ident: $ty:ty,)+) => {
+        struct Tokens {
+            current: Memo<Theme>,
+            $($name: Memo<$ty>,)+
+        }
+
+        impl Tokens {
+            fn new() -> Self {
+                Self {
+                    current: Memo::new(|| THEME.with(|themes| themes.target)),
+                    $($name: Memo::new(|| THEME.with(|themes| themes.shown.$name)),)+
+                }
+            }
+        }
+
+        $(
+            $(#[$doc])*
+            pub fn $name() -> Memo<$ty> {
+                TOKENS.with(|tokens| tokens.$name)
+            }
+        )+
+    };
+}
+
+/// Colors, radii, and spacing shared by every view.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    /// Background of the window and main surfaces.
+    pub bg_primary: Hsla,
+    /// Background of raised surfaces such as cards, inputs, and popovers.
+    pub bg_secondary: Hsla,
+    /// Body text.
+    pub text_primary: Hsla,
+    /// Secondary text such as hints and captions.
+    pub text_muted: Hsla,
+    /// Highlights, focus rings, and primary buttons.
+    pub accent: Hsla,
+    /// Borders and dividers.
+    pub border: Hsla,
+    /// Corner radius of small controls.
+    pub radius_sm: Pixels,
+    /// Corner radius of cards and panels.
+    pub radius_md: Pixels,
+    /// Gap between related items.
+    pub spacing_sm: Pixels,
+    /// Padding inside cards and panels.
+    pub spacing_md: Pixels,
+    /// Gap between sections.
+    pub spacing_lg: Pixels,
+}
+
+impl Theme {
+    /// A light theme, the default.
+    pub fn light() -> Self {
+        Self {
+            bg_primary: rgb(0xffffff).into(),
+            bg_secondary: rgb(0xf3f3f3).into(),
+            text_primary: rgb(0x1e1e1e).into(),
+            text_muted: rgb(0x6e6e6e).into(),
+            accent: rgb(0x2f6fde).into(),
+            border: rgb(0xd4d4d4).into(),
+            radius_sm: px(4.),
+            radius_md: px(8.),
+            spacing_sm: px(4.),
+            spacing_md: px(12.),
+            spacing_lg: px(24.),
+        }
+    }
+
+    /// A dark theme with the light theme's radii and spacing.
+    pub fn dark() -> Self {
+        Self {
+            bg_primary: rgb(0x1e1e1e).into(),
+            bg_secondary: rgb(0x2d2d2d).into(),
+            text_primary: rgb(0xf0f0f0).into(),
+            text_muted: rgb(0x9a9a9a).into(),
+            accent: rgb(0x5b93f0).into(),
+            border: rgb(0x3d3d3d).into(),
+            ..Self::light()
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::light()
+    }
+}
+
+/// The current theme, tracking the read when read with `get` or `with`.
+///
+/// While a change is animating this is already the new theme; the color tokens
+/// show the colors in between.
+pub fn current() -> ReadOnlySignal<Theme> {
+    TOKENS.with(|tokens| tokens.current).into()
+}
+
+/// Replace the current theme.
+///
+/// This is a single write, so every token changes in the same notification wave and
+/// no view renders with a mix of the old and new themes. After
+/// [`animate_transitions`], radii and spacing still change at once while the color
+/// tokens fade to the new colors.
+pub fn set_theme(theme: Theme) {
+    let animator = ANIMATOR.with(|animator| animator.borrow().clone());
+    let animate = animator.is_some() && !TRANSITION.get().is_zero();
+    THEME.update(|themes| {
+        themes.target = theme;
+        themes.shown = if animate {
+            with_colors_of(&themes.shown, theme)
+        } else {
+            theme
+        };
+    });
+    if let Some(animator) = animator.filter(|_| animate) {
+        let _ = animator.unbounded_send(());
+    }
+}
+
+/// Change part of the current theme, such as the accent color, in one write.
+pub fn update_theme(f: impl FnOnce(&mut Theme)) {
+    let mut theme = THEME.with(|themes| themes.target);
+    f(&mut theme);
+    set_theme(theme);
+}
+
+/// Fade the color tokens over `duration` whenever the theme changes, instead of
+/// switching at once; a zero duration switches at once again.
+///
+/// Colors are mixed in RGB space with an ease-in-out curve, updating about every
+/// 16ms. A change while a fade is still running continues from the colors shown at
+/// that moment, so toggling quickly never jumps.
+pub fn animate_transitions(duration: Duration, cx: &mut App) {
+    TRANSITION.set(duration);
+    let (sender, mut receiver) = mpsc::unbounded::<()>();
+    // Replacing the sender ends the task started by an earlier call.
+    ANIMATOR.with(|animator| animator.borrow_mut().replace(sender));
+    cx.spawn(async move |cx| {
+        let mut changed = receiver.next().await;
+        'fade: while changed.is_some() {
+            let from = THEME.with(|themes| themes.shown);
+            let frames = TRANSITION.get().div_duration_f32(FRAME).ceil().max(1.) as u32;
+            for frame in 1..=frames {
+                match future::select(receiver.next(), cx.background_executor().timer(FRAME)).await {
+                    Either::Left((next, _)) => {
+                        changed = next;
+                        continue 'fade;
+                    }
+                    Either::Right(_) => {
+                        let progress = ease_in_out(frame as f32 / frames as f32);
+                        THEME.update(|themes| {
+                            themes.shown = mix_colors(&from, &themes.target, progress)
+                        });
+                    }
+                }
+            }
+            changed = receiver.next().await;
+        }
+    })
+    .detach();
+}
+
+/// `theme` with the colors of `colors`.
+fn with_colors_of(colors: &Theme, theme: Theme) -> Theme {
+    Theme {
+        bg_primary: colors.bg_primary,
+        bg_secondary: colors.bg_secondary,
+        text_primary: colors.text_primary,
+        text_muted: colors.text_muted,
+        accent: colors.accent,
+        border: colors.border,
+        ..theme
+    }
+}
+
+/// `to`, with each color `progress` of the way from its color in `from`.
+fn mix_colors(from: &Theme, to: &Theme, progress: f32) -> Theme {
+    let mix = |from: Hsla, to: Hsla| -> Hsla {
+        let (from, to) = (Rgba::from(from), Rgba::from(to));
+        let channel = |from: f32, to: f32| from + (to - from) * progress;
+        Rgba {
+            r: channel(from.r, to.r),
+            g: channel(from.g, to.g),
+            b: channel(from.b, to.b),
+            a: channel(from.a, to.a),
+        }
+        .into()
+    };
+    if progress >= 1. {
+        return *to;
+    }
+    Theme {
+        bg_primary: mix(from.bg_primary, to.bg_primary),
+        bg_secondary: mix(from.bg_secondary, to.bg_secondary),
+        text_primary: mix(from.text_primary, to.text_primary),
+        text_muted: mix(from.text_muted, to.text_muted),
+        accent: mix(from.accent, to.accent),
+        border: mix(from.border, to.border),
+        ..*to
+    }
+}
+
+fn ease_in_out(progress: f32) -> f32 {
+    progress * progress * (3. - 2. * progress)
+}
+
+tokens! {
+    /// Background of the window and main surfaces.
+    bg_primary: Hsla,
+    /// Background of raised surfaces such as cards, inputs, and popovers.
+    bg_secondary: Hsla,
+    /// Body text.
+    text_primary: Hsla,
+    /// Secondary text such as hints and captions.
+    text_muted: Hsla,
+    /// Highlights, focus rings, and primary buttons.
+    accent: Hsla,
+    /// Borders and dividers.
+    border: Hsla,
+    /// Corner radius of small controls.
+    radius_sm: Pixels,
+    /// Corner radius of cards and panels.
+    radius_md: Pixels,
+    /// Gap between related items.
+    spacing_sm: Pixels,
+    /// Padding inside cards and panels.
+    spacing_md: Pixels,
+    /// Gap between sections.
+    spacing_lg: Pixels,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Effect;
+    use gpui::TestAppContext;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_set_theme_updates_every_token_in_one_wave() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let _effect = Effect::new({
+            let seen = seen.clone();
+            move || {
+                seen.borrow_mut()
+                    .push((bg_primary().get(), text_primary().get()))
+            }
+        });
+
+        set_theme(Theme::dark());
+
+        let light = Theme::light();
+        let dark = Theme::dark();
+        assert_eq!(
+            *seen.borrow(),
+            [
+                (light.bg_primary, light.text_primary),
+                (dark.bg_primary, dark.text_primary),
+            ]
+        );
+        update_theme(|theme| theme.radius_md = px(2.));
+        assert_eq!(radius_md().get(), px(2.));
+        assert_eq!(current().get().accent, dark.accent);
+    }
+
+    #[gpui::test]
+    fn test_animated_transition_fades_colors_only(cx: &mut TestAppContext) {
+        cx.update(|cx| animate_transitions(Duration::from_millis(160), cx));
+        let (light, dark) = (Theme::light(), Theme::dark());
+
+        update_theme(|theme| {
+            *theme = Theme {
+                radius_md: px(2.),
+                ..dark
+            }
+        });
+        cx.run_until_parked();
+        assert_eq!(current().get().bg_primary, dark.bg_primary);
+        assert_eq!(bg_primary().get(), light.bg_primary);
+        assert_eq!(radius_md().get(), px(2.));
+
+        cx.executor().advance_clock(Duration::from_millis(80));
+        let halfway = Rgba::from(bg_primary().get());
+        assert!(halfway.r < 0.9 && halfway.r > 0.2, "{halfway:?}");
+
+        // Switching back midway fades from the color shown, not from dark.
+        set_theme(light);
+        cx.run_until_parked();
+        assert_eq!(Rgba::from(bg_primary().get()), halfway);
+        cx.executor().advance_clock(Duration::from_millis(160));
+        assert_eq!(bg_primary().get(), light.bg_primary);
+        assert_eq!(text_primary().get(), light.text_primary);
+    }
+}
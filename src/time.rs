@@ -0,0 +1,509 @@
+//! Memos derived from the passage of time, such as "3 minutes ago" labels, and
+//! stopwatches and countdowns.
+//!
+//! Each helper owns a clock signal that a task advances on a coarse interval, so the
+//! memos re-evaluate a few times a minute rather than every frame. The clock and its
+//! task are cleaned up when the entity is released. Times come from GPUI's executor,
+//! which tests can move forward with `advance_clock`.
+
+use crate::context::{dispose_on_release, track_subscription};
+use crate::{Memo, ReadOnlySignal, Signal, SignalContext};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use gpui::{BackgroundExecutor, Context, Subscription};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// How often [`relative_time`] re-evaluates; finer than its coarsest label changes.
+const RELATIVE_TIME_PERIOD: Duration = Duration::from_secs(10);
+
+/// A signal holding the current time, updated every `period` until the entity is
+/// released.
+pub fn clock<V: 'static>(period: Duration, cx: &mut Context<V>) -> ReadOnlySignal<Instant> {
+    let now = Signal::new(cx.background_executor().now());
+    let task = cx.spawn(async move |_, cx| loop {
+        cx.background_executor().timer(period).await;
+        now.set(cx.background_executor().now());
+    });
+    track_subscription(cx, Subscription::new(move || drop(task)));
+    dispose_on_release(cx, move || now.dispose());
+    now.read_only()
+}
+
+/// A memo of the time elapsed since `start`, re-evaluated every `period` and
+/// whenever `start` changes.
+pub fn elapsed_since<V: 'static>(
+    start: impl Into<ReadOnlySignal<Instant>>,
+    period: Duration,
+    cx: &mut Context<V>,
+) -> Memo<Duration> {
+    let start = start.into();
+    let now = clock(period, cx);
+    cx.create_memo(move || now.get().saturating_duration_since(start.get()))
+}
+
+/// A memo describing `timestamp` relative to now, such as "just now" or
+/// "3 minutes ago", re-evaluated every 10 seconds.
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::time::relative_time;
+/// use std::time::Instant;
+///
+/// struct Draft {
+///     saved_label: Memo<String>,
+/// }
+///
+/// impl Draft {
+///     fn new(cx: &mut Context<Self>) -> Self {
+///         let saved_at = cx.create_signal(Instant::now());
+///         Self {
+///             saved_label: relative_time(saved_at, cx),
+///         }
+///     }
+/// }
+///
+/// impl Render for Draft {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         div().child(format!("Saved {}", self.saved_label.get()))
+///     }
+/// }
+/// ```
+pub fn relative_time<V: 'static>(
+    timestamp: impl Into<ReadOnlySignal<Instant>>,
+    cx: &mut Context<V>,
+) -> Memo<String> {
+    let elapsed = elapsed_since(timestamp, RELATIVE_TIME_PERIOD, cx);
+    cx.create_memo(move || describe_elapsed(elapsed.get()))
+}
+
+fn describe_elapsed(elapsed: Duration) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+
+    let seconds = elapsed.as_secs();
+    let (count, unit) = match seconds {
+        0..MINUTE => return "just now".to_string(),
+        MINUTE..HOUR => (seconds / MINUTE, "minute"),
+        HOUR..DAY => (seconds / HOUR, "hour"),
+        _ => (seconds / DAY, "day"),
+    };
+    if count == 1 {
+        format!("1 {unit} ago")
+    } else {
+        format!("{count} {unit}s ago")
+    }
+}
+
+/// A stopwatch owned by an entity, whose elapsed time advances every `tick` while
+/// it runs.
+///
+/// [`elapsed`](Self::elapsed) is updated by a task that sleeps between ticks, and
+/// only while the stopwatch runs, so a paused stopwatch costs nothing. Pausing
+/// records the exact time elapsed rather than the last tick. The task and signals
+/// are cleaned up when the entity is released.
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::time::Stopwatch;
+/// use std::time::Duration;
+///
+/// struct Lap {
+///     stopwatch: Stopwatch,
+/// }
+///
+/// impl Render for Lap {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         let stopwatch = self.stopwatch;
+///         div()
+///             .child(stopwatch.display().get())
+///             .on_mouse_down(MouseButton::Left, move |_, _, _| {
+///                 if stopwatch.is_running().get() {
+///                     stopwatch.pause();
+///                 } else {
+///                     stopwatch.start();
+///                 }
+///             })
+///     }
+/// }
+///
+/// fn new_lap(cx: &mut Context<Lap>) -> Lap {
+///     Lap {
+///         stopwatch: Stopwatch::new(Duration::from_secs(1), cx),
+///     }
+/// }
+/// ```
+pub struct Stopwatch {
+    clock: Signal<BackgroundExecutor>,
+    running_since: Signal<Option<Instant>>,
+    banked: Signal<Duration>,
+    limit: Signal<Option<Duration>>,
+    elapsed: Signal<Duration>,
+    is_running: Memo<bool>,
+    display: Memo<String>,
+}
+
+impl Copy for Stopwatch {}
+
+impl Clone for Stopwatch {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl PartialEq for Stopwatch {
+    fn eq(&self, other: &Self) -> bool {
+        self.elapsed == other.elapsed
+    }
+}
+
+impl Eq for Stopwatch {}
+
+impl Stopwatch {
+    /// Create a stopped stopwatch at zero.
+    pub fn new<V: 'static>(tick: Duration, cx: &mut Context<V>) -> Self {
+        Self::build(None, tick, cx)
+    }
+
+    fn build<V: 'static>(limit: Option<Duration>, tick: Duration, cx: &mut Context<V>) -> Self {
+        let clock = Signal::new(cx.background_executor().clone());
+        let banked = Signal::new(Duration::ZERO);
+        let limit = Signal::new(limit);
+        dispose_on_release(cx, move || {
+            clock.dispose();
+            banked.dispose();
+            limit.dispose();
+        });
+        let running_since = cx.create_signal(None);
+        let elapsed = cx.create_signal(Duration::ZERO);
+        let stopwatch = Self {
+            clock,
+            running_since,
+            banked,
+            limit,
+            elapsed,
+            is_running: cx.create_memo(move || running_since.with(Option::is_some)),
+            display: cx.create_memo(move || format_duration(elapsed.get())),
+        };
+
+        let (wake, mut woken) = mpsc::unbounded();
+        running_since.subscribe_while(move || wake.unbounded_send(()).is_ok());
+        let task = cx.spawn(async move |_, cx| loop {
+            while woken.try_recv().is_ok() {}
+            if !running_since.is_alive() {
+                break;
+            }
+            if running_since.get_untracked().is_none() {
+                if woken.next().await.is_none() {
+                    break;
+                }
+                continue;
+            }
+            // Wake at the limit rather than the next tick past it, so a countdown
+            // finishes on time.
+            let wait = match limit.get_untracked() {
+                Some(limit) => limit.saturating_sub(stopwatch.current()).min(tick),
+                None => tick,
+            };
+            cx.background_executor().timer(wait).await;
+            stopwatch.tick();
+        });
+        track_subscription(cx, Subscription::new(move || drop(task)));
+        stopwatch
+    }
+
+    /// The time elapsed while running, as of the last tick or pause.
+    pub fn elapsed(&self) -> ReadOnlySignal<Duration> {
+        self.elapsed.read_only()
+    }
+
+    /// A memo tracking whether the stopwatch is running.
+    pub fn is_running(&self) -> Memo<bool> {
+        self.is_running
+    }
+
+    /// A memo of the elapsed time as `m:ss`, or `h:mm:ss` from an hour on.
+    pub fn display(&self) -> Memo<String> {
+        self.display
+    }
+
+    /// Start or resume counting. Does nothing while running.
+    #[track_caller]
+    pub fn start(&self) {
+        if self.running_since.get_untracked().is_some() || self.reached_limit() {
+            return;
+        }
+        self.running_since.set(Some(self.now()));
+    }
+
+    /// Stop counting, keeping the time elapsed so far.
+    #[track_caller]
+    pub fn pause(&self) {
+        if self.running_since.get_untracked().is_none() {
+            return;
+        }
+        let elapsed = self.current();
+        self.banked.set(elapsed);
+        self.running_since.set(None);
+        self.elapsed.set_if_changed(elapsed);
+    }
+
+    /// Go back to zero, carrying on counting if the stopwatch was running.
+    #[track_caller]
+    pub fn reset(&self) {
+        self.banked.set(Duration::ZERO);
+        if self.running_since.get_untracked().is_some() {
+            self.running_since.set(Some(self.now()));
+        }
+        self.elapsed.set_if_changed(Duration::ZERO);
+    }
+
+    fn tick(&self) {
+        if self.running_since.get_untracked().is_none() {
+            return;
+        }
+        if self.reached_limit() {
+            self.pause();
+        } else {
+            self.elapsed.set_if_changed(self.current());
+        }
+    }
+
+    fn now(&self) -> Instant {
+        self.clock.with_untracked(BackgroundExecutor::now)
+    }
+
+    /// The exact time elapsed, which [`elapsed`](Self::elapsed) trails by up to a
+    /// tick.
+    fn current(&self) -> Duration {
+        let running = self
+            .running_since
+            .get_untracked()
+            .map_or(Duration::ZERO, |since| {
+                self.now().saturating_duration_since(since)
+            });
+        let elapsed = self.banked.get_untracked() + running;
+        match self.limit.get_untracked() {
+            Some(limit) => elapsed.min(limit),
+            None => elapsed,
+        }
+    }
+
+    fn reached_limit(&self) -> bool {
+        self.limit
+            .get_untracked()
+            .is_some_and(|limit| self.current() >= limit)
+    }
+}
+
+impl fmt::Debug for Stopwatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stopwatch")
+            .field("elapsed", &self.elapsed.get_untracked())
+            .field("is_running", &self.running_since.get_untracked().is_some())
+            .finish()
+    }
+}
+
+/// A countdown owned by an entity, which stops on its own when the time is up.
+///
+/// It runs on a [`Stopwatch`], with [`remaining`](Self::remaining) and
+/// [`is_finished`](Self::is_finished) derived from the time elapsed. Subscribe to
+/// `is_finished()` to act when the countdown ends.
+pub struct CountdownTimer {
+    stopwatch: Stopwatch,
+    remaining: Memo<Duration>,
+    is_finished: Memo<bool>,
+    display: Memo<String>,
+}
+
+impl Copy for CountdownTimer {}
+
+impl Clone for CountdownTimer {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl PartialEq for CountdownTimer {
+    fn eq(&self, other: &Self) -> bool {
+        self.stopwatch == other.stopwatch
+    }
+}
+
+impl Eq for CountdownTimer {}
+
+impl CountdownTimer {
+    /// Create a stopped countdown from `duration`, updated every `tick` while it runs.
+    pub fn new<V: 'static>(duration: Duration, tick: Duration, cx: &mut Context<V>) -> Self {
+        let stopwatch = Stopwatch::build(Some(duration), tick, cx);
+        let (limit, elapsed) = (stopwatch.limit, stopwatch.elapsed);
+        let remaining = cx.create_memo(move || {
+            limit
+                .get()
+                .unwrap_or_default()
+                .saturating_sub(elapsed.get())
+        });
+        Self {
+            stopwatch,
+            remaining,
+            is_finished: cx.create_memo(move || remaining.get().is_zero()),
+            // Rounded up, so the display reaches 0:00 only as the countdown ends.
+            display: cx.create_memo(move || {
+                let remaining = remaining.get();
+                let rounded = if remaining.subsec_nanos() > 0 {
+                    Duration::from_secs(remaining.as_secs() + 1)
+                } else {
+                    remaining
+                };
+                format_duration(rounded)
+            }),
+        }
+    }
+
+    /// A memo of the time left, as of the last tick or pause.
+    pub fn remaining(&self) -> Memo<Duration> {
+        self.remaining
+    }
+
+    /// A memo tracking whether the time is up.
+    pub fn is_finished(&self) -> Memo<bool> {
+        self.is_finished
+    }
+
+    /// A memo tracking whether the countdown is running.
+    pub fn is_running(&self) -> Memo<bool> {
+        self.stopwatch.is_running
+    }
+
+    /// A memo of the time left as `m:ss`, or `h:mm:ss` from an hour on.
+    pub fn display(&self) -> Memo<String> {
+        self.display
+    }
+
+    /// Start or resume the countdown. Does nothing once it has finished.
+    #[track_caller]
+    pub fn start(&self) {
+        self.stopwatch.start();
+    }
+
+    /// Stop the countdown, keeping the time left.
+    #[track_caller]
+    pub fn pause(&self) {
+        self.stopwatch.pause();
+    }
+
+    /// Go back to the full duration, carrying on if the countdown was running.
+    #[track_caller]
+    pub fn reset(&self) {
+        self.stopwatch.reset();
+    }
+
+    /// Count down from `duration` instead, keeping the time already elapsed.
+    #[track_caller]
+    pub fn set_duration(&self, duration: Duration) {
+        self.stopwatch.limit.set(Some(duration));
+        self.stopwatch.tick();
+    }
+}
+
+impl fmt::Debug for CountdownTimer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CountdownTimer")
+            .field("remaining", &self.remaining.get_untracked())
+            .field("is_running", &self.stopwatch.is_running.get_untracked())
+            .finish()
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{div, AppContext, IntoElement, Render, TestAppContext, Window};
+
+    struct Label {
+        saved_at: Signal<Instant>,
+        label: Memo<String>,
+    }
+
+    impl Render for Label {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div()
+        }
+    }
+
+    #[gpui::test]
+    async fn test_relative_time_follows_the_clock(cx: &mut TestAppContext) {
+        let view = cx.new(|cx| {
+            let saved_at = cx.create_signal(cx.background_executor().now());
+            Label {
+                saved_at,
+                label: relative_time(saved_at, cx),
+            }
+        });
+        let (saved_at, label) = view.read_with(cx, |view, _| (view.saved_at, view.label));
+        assert_eq!(label.get(), "just now");
+
+        cx.executor().advance_clock(Duration::from_secs(130));
+        assert_eq!(label.get(), "2 minutes ago");
+        cx.executor().advance_clock(Duration::from_secs(3600));
+        assert_eq!(label.get(), "1 hour ago");
+
+        saved_at.set(cx.executor().now());
+        assert_eq!(label.get(), "just now");
+    }
+
+    struct Timers {
+        stopwatch: Stopwatch,
+        countdown: CountdownTimer,
+    }
+
+    impl Render for Timers {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div()
+        }
+    }
+
+    #[gpui::test]
+    async fn test_stopwatch_and_countdown_follow_the_clock(cx: &mut TestAppContext) {
+        let second = Duration::from_secs(1);
+        let view = cx.new(|cx| Timers {
+            stopwatch: Stopwatch::new(second, cx),
+            countdown: CountdownTimer::new(Duration::from_secs(3), second, cx),
+        });
+        let (stopwatch, countdown) = view.read_with(cx, |view, _| (view.stopwatch, view.countdown));
+
+        stopwatch.start();
+        countdown.start();
+        cx.executor().advance_clock(Duration::from_millis(2500));
+        assert_eq!(stopwatch.elapsed().get(), 2 * second);
+        assert_eq!(countdown.display().get(), "0:01");
+        stopwatch.pause();
+        assert_eq!(stopwatch.elapsed().get(), Duration::from_millis(2500));
+        assert!(!stopwatch.is_running().get());
+
+        cx.executor().advance_clock(Duration::from_secs(70));
+        assert_eq!(stopwatch.display().get(), "0:02");
+        assert!(countdown.is_finished().get());
+        assert!(!countdown.is_running().get());
+        assert_eq!(countdown.display().get(), "0:00");
+
+        stopwatch.reset();
+        countdown.reset();
+        assert_eq!(stopwatch.elapsed().get(), Duration::ZERO);
+        assert_eq!(countdown.remaining().get(), Duration::from_secs(3));
+    }
+}
@@ -0,0 +1,201 @@
+//! Enter and exit phases for conditionally rendered content.
+
+use crate::context::{dispose_on_release, track_subscription};
+use crate::{ReadOnlySignal, Signal, SignalContext};
+use futures::channel::mpsc;
+use futures::future::{self, Either};
+use futures::StreamExt;
+use gpui::{Context, Subscription};
+use std::time::Duration;
+
+/// The phase of a [`Transition`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TransitionPhase {
+    /// Becoming visible; the enter duration has not elapsed yet.
+    Entering,
+    /// Fully visible.
+    Entered,
+    /// Becoming hidden; the exit duration has not elapsed yet.
+    Exiting,
+    /// Fully hidden.
+    Exited,
+}
+
+impl TransitionPhase {
+    /// Whether content should still be rendered in this phase.
+    pub fn is_mounted(self) -> bool {
+        self != Self::Exited
+    }
+}
+
+/// Derives enter and exit phases from a visibility signal.
+///
+/// When the visibility becomes true the phase moves to `Entering`, then to `Entered`
+/// once the enter duration has elapsed; when it becomes false the phase moves through
+/// `Exiting` to `Exited`. Rendering content while [`is_mounted`](Self::is_mounted)
+/// lets it animate out before it is removed, instead of disappearing instantly.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+/// use std::time::Duration;
+///
+/// struct Panel {
+///     open: Signal<bool>,
+///     transition: Transition,
+/// }
+///
+/// impl Panel {
+///     fn new(cx: &mut Context<Self>) -> Self {
+///         let open = cx.create_signal(false);
+///         let transition = Transition::new(open, cx)
+///             .durations(Duration::from_millis(200), Duration::from_millis(150));
+///         Self { open, transition }
+///     }
+/// }
+///
+/// impl Render for Panel {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         let phase = self.transition.phase().get();
+///         div().when(phase.is_mounted(), |this| {
+///             this.child(div().opacity(if phase == TransitionPhase::Entered { 1. } else { 0.5 }))
+///         })
+///     }
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transition {
+    phase: Signal<TransitionPhase>,
+    enter: Signal<Duration>,
+    exit: Signal<Duration>,
+}
+
+impl Transition {
+    /// Follow `visible`, notifying the entity whenever the phase changes.
+    ///
+    /// Both durations default to 150ms. The initial phase is `Entered` or `Exited`
+    /// depending on the current visibility, so content present at creation does not
+    /// animate in.
+    pub fn new<V: 'static>(visible: impl Into<ReadOnlySignal<bool>>, cx: &mut Context<V>) -> Self {
+        let visible = visible.into();
+        let initial = if visible.get_untracked() {
+            TransitionPhase::Entered
+        } else {
+            TransitionPhase::Exited
+        };
+        let transition = Self {
+            phase: cx.create_signal(initial),
+            enter: Signal::new(Duration::from_millis(150)),
+            exit: Signal::new(Duration::from_millis(150)),
+        };
+
+        let (tx, mut rx) = mpsc::unbounded::<()>();
+        visible.subscribe(move || {
+            let _ = tx.unbounded_send(());
+        });
+        let task = cx.spawn(async move |_, cx| {
+            let mut changed = rx.next().await;
+            while changed.is_some() {
+                use TransitionPhase::*;
+                let (during, after, duration) = if visible.get_untracked() {
+                    (Entering, Entered, transition.enter.get_untracked())
+                } else {
+                    (Exiting, Exited, transition.exit.get_untracked())
+                };
+                if transition.phase.get_untracked() == after {
+                    changed = rx.next().await;
+                    continue;
+                }
+                transition.phase.set_if_changed(during);
+                let timer = cx.background_executor().timer(duration);
+                // A visibility change before the timer fires reverses the transition
+                // from wherever it is.
+                match future::select(rx.next(), timer).await {
+                    Either::Left((next, _)) => changed = next,
+                    Either::Right(_) => {
+                        transition.phase.set_if_changed(after);
+                        changed = rx.next().await;
+                    }
+                }
+            }
+        });
+        track_subscription(cx, Subscription::new(move || drop(task)));
+        dispose_on_release(cx, move || {
+            transition.enter.dispose();
+            transition.exit.dispose();
+        });
+
+        transition
+    }
+
+    /// Set how long the `Entering` and `Exiting` phases last.
+    pub fn durations(self, enter: Duration, exit: Duration) -> Self {
+        self.enter.set(enter);
+        self.exit.set(exit);
+        self
+    }
+
+    /// The signal holding the current phase.
+    pub fn phase(&self) -> ReadOnlySignal<TransitionPhase> {
+        self.phase.read_only()
+    }
+
+    /// Whether content should still be rendered.
+    pub fn is_mounted(&self) -> bool {
+        self.phase.get().is_mounted()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext, TestAppContext};
+
+    struct Panel {
+        transition: Transition,
+    }
+
+    #[gpui::test]
+    async fn test_transition_phases(cx: &mut TestAppContext) {
+        let visible = Signal::new(false);
+        let panel = cx.new(|cx| Panel {
+            transition: Transition::new(visible, cx)
+                .durations(Duration::from_millis(200), Duration::from_millis(100)),
+        });
+        let phase = panel.read_with(cx, |panel, _| panel.transition.phase());
+        assert_eq!(phase.get(), TransitionPhase::Exited);
+
+        visible.set(true);
+        cx.run_until_parked();
+        assert_eq!(phase.get(), TransitionPhase::Entering);
+        cx.executor().advance_clock(Duration::from_millis(200));
+        assert_eq!(phase.get(), TransitionPhase::Entered);
+
+        visible.set(false);
+        cx.run_until_parked();
+        assert_eq!(phase.get(), TransitionPhase::Exiting);
+        cx.executor().advance_clock(Duration::from_millis(100));
+        assert_eq!(phase.get(), TransitionPhase::Exited);
+    }
+
+    #[gpui::test]
+    async fn test_transition_reverses_midway(cx: &mut TestAppContext) {
+        let visible = Signal::new(false);
+        let panel = cx.new(|cx| Panel {
+            transition: Transition::new(visible, cx),
+        });
+        let phase = panel.read_with(cx, |panel, _| panel.transition.phase());
+
+        visible.set(true);
+        cx.run_until_parked();
+        cx.executor().advance_clock(Duration::from_millis(50));
+        visible.set(false);
+        cx.run_until_parked();
+        assert_eq!(phase.get(), TransitionPhase::Exiting);
+
+        cx.executor().advance_clock(Duration::from_millis(150));
+        assert_eq!(phase.get(), TransitionPhase::Exited);
+    }
+}
@@ -0,0 +1,384 @@
+//! Signals that reject writes failing a validation check, and checks of constraints
+//! between fields.
+
+use crate::computed::Memo;
+use crate::runtime::SignalRuntime;
+use crate::signal::{ReadOnlySignal, Signal};
+use std::fmt;
+use std::rc::Rc;
+
+pub(crate) type Validator<T, E> = Rc<dyn Fn(&T) -> Result<(), E>>;
+
+/// A signal whose writes are checked by a validator before they are committed.
+///
+/// A write the validator rejects leaves the value unchanged and records the error in
+/// a companion signal, which a form can render next to its input. The next accepted
+/// write clears the error.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui::*;
+/// use gpui_signals::prelude::*;
+///
+/// struct Settings {
+///     port: ValidatedSignal<u16, String>,
+/// }
+///
+/// impl Settings {
+///     fn new(cx: &mut Context<Self>) -> Self {
+///         let port = cx.create_validated_signal(8080, |port: &u16| {
+///             if *port >= 1024 {
+///                 Ok(())
+///             } else {
+///                 Err("ports below 1024 need elevated privileges".to_string())
+///             }
+///         });
+///         Self { port }
+///     }
+/// }
+///
+/// impl Render for Settings {
+///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+///         div()
+///             .child(self.port.value())
+///             .children(self.port.error().get())
+///     }
+/// }
+/// ```
+pub struct ValidatedSignal<T, E> {
+    value: Signal<T>,
+    error: Signal<Option<E>>,
+    validator: Signal<Validator<T, E>>,
+}
+
+impl<T, E> Copy for ValidatedSignal<T, E> {}
+
+impl<T, E> Clone for ValidatedSignal<T, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, E> PartialEq for ValidatedSignal<T, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T, E> Eq for ValidatedSignal<T, E> {}
+
+impl<T: 'static, E: 'static> ValidatedSignal<T, E> {
+    /// Create a validated signal.
+    ///
+    /// The initial value is kept even if the validator rejects it, with the error
+    /// recorded as for a rejected write.
+    pub fn new(initial: T, validate: impl Fn(&T) -> Result<(), E> + 'static) -> Self {
+        Self::new_in(initial, validate, &SignalRuntime::default())
+    }
+
+    /// Create a validated signal in `runtime`.
+    pub fn new_in(
+        initial: T,
+        validate: impl Fn(&T) -> Result<(), E> + 'static,
+        runtime: &SignalRuntime,
+    ) -> Self {
+        let error = validate(&initial).err();
+        Self::from_signals(
+            Signal::new_in(initial, runtime),
+            Signal::new_in(error, runtime),
+            Signal::new_in(Rc::new(validate), runtime),
+        )
+    }
+
+    pub(crate) fn from_signals(
+        value: Signal<T>,
+        error: Signal<Option<E>>,
+        validator: Signal<Validator<T, E>>,
+    ) -> Self {
+        Self {
+            value,
+            error,
+            validator,
+        }
+    }
+
+    /// The current value, which always passed validation unless it is the initial one.
+    pub fn value(&self) -> ReadOnlySignal<T> {
+        self.value.read_only()
+    }
+
+    /// The error from the most recent rejected write, cleared by the next accepted one.
+    pub fn error(&self) -> ReadOnlySignal<Option<E>> {
+        self.error.read_only()
+    }
+
+    /// Get the current value, tracking the read.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.value.get()
+    }
+
+    /// Get the current value without tracking the read.
+    pub fn get_untracked(&self) -> T
+    where
+        T: Clone,
+    {
+        self.value.get_untracked()
+    }
+
+    /// Read the value with a closure, tracking the read.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.value.with(f)
+    }
+
+    /// Commit `value` if the validator accepts it.
+    ///
+    /// Returns whether the write was accepted. A rejected write records its error and
+    /// leaves the value unchanged.
+    #[track_caller]
+    pub fn set(&self, value: T) -> bool {
+        // Cloned out so the validator runs outside the storage borrow and may read
+        // other signals.
+        let validate = self.validator.get_untracked();
+        match validate(&value) {
+            Ok(()) => {
+                self.value.set(value);
+                if self.error.with_untracked(Option::is_some) {
+                    self.error.set(None);
+                }
+                true
+            }
+            Err(error) => {
+                self.error.set(Some(error));
+                false
+            }
+        }
+    }
+
+    /// Apply `f` to a copy of the value and commit the result if the validator
+    /// accepts it.
+    #[track_caller]
+    pub fn update(&self, f: impl FnOnce(&mut T)) -> bool
+    where
+        T: Clone,
+    {
+        let mut value = self.value.get_untracked();
+        f(&mut value);
+        self.set(value)
+    }
+
+    /// Free the value, error, and validator.
+    pub fn dispose(self) {
+        self.value.dispose();
+        self.error.dispose();
+        self.validator.dispose();
+    }
+}
+
+impl<T: 'static + Clone + fmt::Debug, E: 'static + Clone + fmt::Debug> fmt::Debug
+    for ValidatedSignal<T, E>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidatedSignal")
+            .field("value", &self.value.get_untracked())
+            .field("error", &self.error.get_untracked())
+            .finish()
+    }
+}
+
+/// A failed check of a constraint between fields, naming the fields it concerns.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrossFieldError {
+    /// The fields the check reads, so the error can be shown next to each.
+    pub fields: Vec<&'static str>,
+    /// Why the check failed.
+    pub message: String,
+}
+
+impl CrossFieldError {
+    /// Whether the error concerns `field`.
+    pub fn involves(&self, field: &str) -> bool {
+        self.fields.contains(&field)
+    }
+}
+
+impl fmt::Display for CrossFieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+type Check = Box<dyn Fn() -> Result<(), String>>;
+
+/// Checks of constraints between fields, such as a password and its confirmation
+/// or the two ends of a date range.
+///
+/// Each check reads the field signals it needs and names the fields it concerns.
+/// [`build`](Self::build) turns the checks into a memo of the failures, which
+/// updates whenever a field read by a check changes.
+///
+/// # Examples
+///
+/// ```rust
+/// use gpui_signals::prelude::*;
+/// use gpui_signals::CrossFieldValidator;
+///
+/// let password = Signal::new(String::from("hunter22"));
+/// let confirm = Signal::new(String::new());
+/// let errors = CrossFieldValidator::new()
+///     .check(["password", "confirm"], move || {
+///         if password.get() == confirm.get() {
+///             Ok(())
+///         } else {
+///             Err("the passwords don't match")
+///         }
+///     })
+///     .build();
+///
+/// assert!(errors.get().iter().any(|error| error.involves("confirm")));
+/// confirm.set(String::from("hunter22"));
+/// assert!(errors.get().is_empty());
+/// ```
+#[derive(Default)]
+pub struct CrossFieldValidator {
+    checks: Vec<(Vec<&'static str>, Check)>,
+}
+
+impl CrossFieldValidator {
+    /// Create a validator with no checks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a check of the constraint between `fields`, failing with the error it
+    /// returns.
+    pub fn check<E: fmt::Display>(
+        mut self,
+        fields: impl IntoIterator<Item = &'static str>,
+        check: impl Fn() -> Result<(), E> + 'static,
+    ) -> Self {
+        self.checks.push((
+            fields.into_iter().collect(),
+            Box::new(move || check().map_err(|error| error.to_string())),
+        ));
+        self
+    }
+
+    /// A function running every check, to pass to `cx.create_memo` so the entity
+    /// owns the memo.
+    pub fn into_compute(self) -> impl Fn() -> Vec<CrossFieldError> + 'static {
+        move || {
+            self.checks
+                .iter()
+                .filter_map(|(fields, check)| {
+                    check().err().map(|message| CrossFieldError {
+                        fields: fields.clone(),
+                        message,
+                    })
+                })
+                .collect()
+        }
+    }
+
+    /// A memo of the failed checks, in the order they were added.
+    pub fn build(self) -> Memo<Vec<CrossFieldError>> {
+        self.build_in(&SignalRuntime::default())
+    }
+
+    /// A memo of the failed checks in `runtime`.
+    pub fn build_in(self, runtime: &SignalRuntime) -> Memo<Vec<CrossFieldError>> {
+        Memo::new_in(self.into_compute(), runtime)
+    }
+}
+
+impl fmt::Debug for CrossFieldValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CrossFieldValidator")
+            .field(
+                "checks",
+                &self
+                    .checks
+                    .iter()
+                    .map(|(fields, _)| fields)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn percent(value: &i32) -> Result<(), String> {
+        if (0..=100).contains(value) {
+            Ok(())
+        } else {
+            Err(format!("{value} is not a percentage"))
+        }
+    }
+
+    #[test]
+    fn test_rejected_write_keeps_value_and_records_error() {
+        let volume = ValidatedSignal::new(50, percent);
+        assert_eq!(volume.error().get(), None);
+
+        assert!(!volume.set(120));
+        assert_eq!(volume.get(), 50);
+        assert_eq!(
+            volume.error().get().as_deref(),
+            Some("120 is not a percentage")
+        );
+
+        assert!(volume.update(|volume| *volume += 10));
+        assert_eq!(volume.get(), 60);
+        assert_eq!(volume.error().get(), None);
+    }
+
+    #[test]
+    fn test_cross_field_errors_follow_every_field() {
+        let start = Signal::new(3);
+        let end = Signal::new(1);
+        let nights = Signal::new(2);
+        let errors = CrossFieldValidator::new()
+            .check(["start", "end"], move || {
+                if start.get() <= end.get() {
+                    Ok(())
+                } else {
+                    Err("the stay ends before it starts")
+                }
+            })
+            .check(["start", "end", "nights"], move || {
+                if end.get() - start.get() == nights.get() {
+                    Ok(())
+                } else {
+                    Err(format!("{} nights don't fit the dates", nights.get()))
+                }
+            })
+            .build();
+
+        let messages = || {
+            errors
+                .get()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            messages(),
+            [
+                "the stay ends before it starts",
+                "2 nights don't fit the dates"
+            ]
+        );
+        assert!(!errors.get()[0].involves("nights"));
+
+        end.set(4);
+        assert_eq!(messages(), ["2 nights don't fit the dates"]);
+        nights.set(1);
+        assert!(errors.get().is_empty());
+    }
+}
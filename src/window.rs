@@ -0,0 +1,219 @@
+//! Window properties bound to signals.
+
+use crate::context::track_subscription;
+use crate::{Memo, ReadOnlySignal, SignalContext};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use gpui::{px, App, Context, Pixels, Size, Window};
+
+/// Windows narrower than this are compact.
+const COMPACT_BELOW: f32 = 640.;
+/// Windows at least this wide are wide.
+const WIDE_FROM: f32 = 1024.;
+
+/// Extension trait for binding window properties to signals.
+pub trait WindowSignalExt {
+    /// Keep the OS window title in sync with `title`.
+    ///
+    /// The title is set immediately and again whenever the signal or memo changes,
+    /// until the window is closed.
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    ///
+    /// struct Editor {
+    ///     file_name: Signal<String>,
+    ///     dirty: Signal<bool>,
+    /// }
+    ///
+    /// impl Editor {
+    ///     fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+    ///         let file_name = cx.create_signal("notes.md".to_string());
+    ///         let dirty = cx.create_signal(false);
+    ///         let title = cx.create_memo(move || {
+    ///             let marker = if dirty.get() { " — modified" } else { "" };
+    ///             format!("{}{}", file_name.get(), marker)
+    ///         });
+    ///         window.bind_title(title, cx);
+    ///         Self { file_name, dirty }
+    ///     }
+    /// }
+    /// ```
+    fn bind_title(&mut self, title: impl Into<ReadOnlySignal<String>>, cx: &mut App);
+
+    /// A signal holding the window's viewport size, updated whenever the window is
+    /// resized until the entity is released.
+    fn size_signal<V: 'static>(&mut self, cx: &mut Context<V>) -> ReadOnlySignal<Size<Pixels>>;
+
+    /// Memoized width breakpoints for responsive layouts, derived from
+    /// [`size_signal`](Self::size_signal).
+    ///
+    /// ```rust,no_run
+    /// use gpui::*;
+    /// use gpui_signals::prelude::*;
+    /// use gpui_signals::Breakpoints;
+    ///
+    /// struct Workspace {
+    ///     breakpoints: Breakpoints,
+    /// }
+    ///
+    /// impl Render for Workspace {
+    ///     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+    ///         if self.breakpoints.is_compact().get() {
+    ///             div().flex_col().child("Sidebar below")
+    ///         } else {
+    ///             div().flex_row().child("Sidebar beside")
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// fn open(window: &mut Window, cx: &mut Context<Workspace>) -> Workspace {
+    ///     Workspace { breakpoints: window.breakpoints(cx) }
+    /// }
+    /// ```
+    fn breakpoints<V: 'static>(&mut self, cx: &mut Context<V>) -> Breakpoints;
+}
+
+/// Whether the window is narrower or wider than fixed widths, as memos.
+///
+/// Layouts read the memos instead of comparing the window size themselves. Compact
+/// means narrower than 640px and wide means at least 1024px; add other widths with
+/// [`min_width`](Self::min_width).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Breakpoints {
+    size: ReadOnlySignal<Size<Pixels>>,
+    compact: Memo<bool>,
+    wide: Memo<bool>,
+}
+
+impl Breakpoints {
+    /// The viewport size the breakpoints are derived from.
+    pub fn size(&self) -> ReadOnlySignal<Size<Pixels>> {
+        self.size
+    }
+
+    /// Whether the window is narrower than 640px.
+    pub fn is_compact(&self) -> Memo<bool> {
+        self.compact
+    }
+
+    /// Whether the window is at least 1024px wide.
+    pub fn is_wide(&self) -> Memo<bool> {
+        self.wide
+    }
+
+    /// A custom breakpoint: whether the window is at least `width` wide, owned by the
+    /// entity of `cx`.
+    pub fn min_width<V: 'static>(&self, width: Pixels, cx: &mut Context<V>) -> Memo<bool> {
+        let size = self.size;
+        cx.create_memo(move || size.with(|size| size.width >= width))
+    }
+}
+
+impl WindowSignalExt for Window {
+    fn bind_title(&mut self, title: impl Into<ReadOnlySignal<String>>, cx: &mut App) {
+        let title = title.into();
+        self.set_window_title(&title.get_untracked());
+
+        let (tx, mut rx) = mpsc::unbounded::<()>();
+        title.subscribe(move || {
+            let _ = tx.unbounded_send(());
+        });
+        self.spawn(cx, async move |cx| {
+            while let Some(()) = rx.next().await {
+                let updated = cx.update(|window, _| {
+                    window.set_window_title(&title.get_untracked());
+                });
+                if updated.is_err() {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn size_signal<V: 'static>(&mut self, cx: &mut Context<V>) -> ReadOnlySignal<Size<Pixels>> {
+        let size = cx.create_signal(self.viewport_size());
+        let subscription = cx.observe_window_bounds(self, move |_, window, _| {
+            size.set_if_changed(window.viewport_size());
+        });
+        track_subscription(cx, subscription);
+        size.read_only()
+    }
+
+    fn breakpoints<V: 'static>(&mut self, cx: &mut Context<V>) -> Breakpoints {
+        let size = self.size_signal(cx);
+        Breakpoints {
+            size,
+            compact: cx.create_memo(move || size.with(|size| size.width < px(COMPACT_BELOW))),
+            wide: cx.create_memo(move || size.with(|size| size.width >= px(WIDE_FROM))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Signal;
+    use gpui::{div, IntoElement, Render, TestAppContext};
+
+    struct Editor;
+
+    struct BreakpointView {
+        breakpoints: Breakpoints,
+        medium: Memo<bool>,
+    }
+
+    impl Render for BreakpointView {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div()
+        }
+    }
+
+    impl Render for Editor {
+        fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+            div()
+        }
+    }
+
+    #[gpui::test]
+    async fn test_bind_title_follows_signal(cx: &mut TestAppContext) {
+        let title = Signal::new("untitled".to_string());
+        let (_editor, cx) = cx.add_window_view(|window, cx| {
+            window.bind_title(title, cx);
+            Editor
+        });
+        assert_eq!(cx.window_title().as_deref(), Some("untitled"));
+
+        title.set("notes.md — modified".to_string());
+        cx.run_until_parked();
+        assert_eq!(cx.window_title().as_deref(), Some("notes.md — modified"));
+    }
+
+    #[gpui::test]
+    async fn test_breakpoints_follow_window_width(cx: &mut TestAppContext) {
+        let (breakpoints, cx) = cx.add_window_view(|window, cx| {
+            let breakpoints = window.breakpoints(cx);
+            let medium = breakpoints.min_width(px(800.), cx);
+            BreakpointView {
+                breakpoints,
+                medium,
+            }
+        });
+        let (breakpoints, medium) =
+            breakpoints.read_with(cx, |view, _| (view.breakpoints, view.medium));
+
+        cx.simulate_resize(gpui::size(px(500.), px(400.)));
+        cx.run_until_parked();
+        assert_eq!(breakpoints.size().get().width, px(500.));
+        assert!(breakpoints.is_compact().get());
+        assert!(!medium.get());
+
+        cx.simulate_resize(gpui::size(px(1200.), px(800.)));
+        cx.run_until_parked();
+        assert!(!breakpoints.is_compact().get());
+        assert!(medium.get());
+        assert!(breakpoints.is_wide().get());
+    }
+}
@@ -0,0 +1,91 @@
+//! Write-only handles that transform input before committing it to a signal.
+
+use crate::signal::Signal;
+use std::fmt;
+use std::rc::Rc;
+
+/// A write-only handle that accepts values of type `U` and commits them to a signal
+/// through a transformation.
+///
+/// Created with [`Signal::derive_setter`]. An input widget can be given a
+/// `WriteSignal<String>` that parses, clamps, or normalizes what the user typed
+/// into the typed state it edits.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use gpui_signals::prelude::*;
+///
+/// let volume = Signal::new(50u8);
+/// let volume_text = volume.derive_setter(|input: String, volume: &mut u8| {
+///     if let Ok(value) = input.trim().parse::<u8>() {
+///         *volume = value.min(100);
+///     }
+/// });
+///
+/// volume_text.set("250".to_string());
+/// assert_eq!(volume.get(), 100);
+/// ```
+pub struct WriteSignal<U> {
+    setter: Rc<dyn Fn(U)>,
+}
+
+impl<U> Clone for WriteSignal<U> {
+    fn clone(&self) -> Self {
+        Self {
+            setter: self.setter.clone(),
+        }
+    }
+}
+
+impl<U> WriteSignal<U> {
+    /// Transform `input` and commit it to the underlying signal.
+    pub fn set(&self, input: U) {
+        (self.setter)(input);
+    }
+}
+
+impl<U> fmt::Debug for WriteSignal<U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WriteSignal").finish_non_exhaustive()
+    }
+}
+
+impl<T: 'static> Signal<T> {
+    /// A write-only handle whose input is applied to this signal by `transform`.
+    ///
+    /// `transform` receives the input and the current value to modify in place, and
+    /// subscribers are notified after each write. Like [`update`](Self::update), it
+    /// runs while the value is borrowed, so it must not read or write signals.
+    pub fn derive_setter<U: 'static>(
+        &self,
+        transform: impl Fn(U, &mut T) + 'static,
+    ) -> WriteSignal<U> {
+        let signal = *self;
+        WriteSignal {
+            setter: Rc::new(move |input| signal.update(|current| transform(input, current))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derived_setter_transforms_input() {
+        let width = Signal::new(10.0_f32);
+        let width_text = width.derive_setter(|input: &str, width: &mut f32| {
+            if let Ok(value) = input.trim().parse::<f32>() {
+                *width = value.clamp(0., 100.);
+            }
+        });
+
+        width_text.set(" 42.5 ");
+        assert_eq!(width.get(), 42.5);
+        width_text.set("250");
+        assert_eq!(width.get(), 100.);
+        width_text.set("wide");
+        assert_eq!(width.get(), 100.);
+    }
+}